@@ -6,16 +6,15 @@ use glfw::Context;
 
 use controller::Controller;
 use cuboid::opengl::components::{
-    Camera,
     PerspectiveCamera,
     Material,
     Renderer3D,
     Shape,
     Texture2D,
 };
-use cuboid::opengl::{Shader, Renderer, Window};
-use cuboid::opengl::io::CameraController;
-use cuboid::utils::{math::linalg, types};
+use cuboid::opengl::{Shader, Renderer, WindowBuilder};
+use cuboid::opengl::io::{CameraController, CameraRig, FlyController};
+use cuboid::utils::types;
 
 const WINDOW_TITLE: &str = "Texture example";
 
@@ -39,9 +38,7 @@ fn main() {
         [-6.0,  10.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0],
     ];
 
-    let square_i: Vec<u32> = vec![0, 1, 2, 3];
-
-    let mut window = Window::new()
+    let mut window = WindowBuilder::new()
         .dimensions(1000, 1000)
         .title(WINDOW_TITLE)
         .windowed()
@@ -70,32 +67,30 @@ fn main() {
         .build();
 
     // renderer.add_item_with_mode(&axes, gl::LINE);
-    renderer.add_item_with_mode(&square, gl::QUADS);
-    let mut camera_pos = [0.0, 0.0, 20.0];
-    let mut camera_dir = [0.0, 0.0, 1.0];
-    let mut camera_up = [0.0, 1.0, 0.0];
-    let mut camera_right = linalg::normalize_v3(&linalg::cross_v3(&camera_up, &camera_dir));
-
-    let mut camera = PerspectiveCamera::new(
-        &camera_pos,
-        &camera_dir,
-        &camera_up,
-        -1.0,
-        1.0,
-        -1.0,
+    renderer.add_item(&square);
+    let camera = PerspectiveCamera::new(
+        &[0.0, 0.0, 20.0],
+        &[0.0, 0.0, 1.0],
+        &[0.0, 1.0, 0.0],
+        90.0,
         1.0,
         1.0,
         1000.0,
     );
-
-    let cam_mov_speed = 0.01;
-    let cam_rot_speed = 0.5;
+    let mut fly = FlyController::from_camera(&camera).move_speed(10.0).smoothing(0.05);
+    let (width, height) = window.get_framebuffer_size();
+    renderer.set_camera(camera);
+    renderer.resize(width, height);
 
     let mut wireframe = false;
     let mut controller = Controller::new();
 
     while !window.should_close() {
         controller.poll_window_events(&mut window);
+        if window.was_resized() {
+            let (width, height) = window.get_framebuffer_size();
+            renderer.resize(width, height);
+        }
         if controller.esc_pressed {
             window.set_should_close(true);
         }
@@ -115,55 +110,15 @@ fn main() {
             wireframe = controller.wireframe;
         }
 
-        // Camera movement control
-        if controller.w_pressed {
-            camera_pos[0] -= cam_mov_speed * camera_dir[0];
-            camera_pos[1] -= cam_mov_speed * camera_dir[1];
-            camera_pos[2] -= cam_mov_speed * camera_dir[2];
-        }
-        if controller.s_pressed {
-            camera_pos[0] += cam_mov_speed * camera_dir[0];
-            camera_pos[1] += cam_mov_speed * camera_dir[1];
-            camera_pos[2] += cam_mov_speed * camera_dir[2];
-        }
-        if controller.a_pressed {
-            camera_pos[0] -= cam_mov_speed * camera_right[0];
-            camera_pos[1] -= cam_mov_speed * camera_right[1];
-            camera_pos[2] -= cam_mov_speed * camera_right[2];
-        }
-        if controller.d_pressed {
-            camera_pos[0] += cam_mov_speed * camera_right[0];
-            camera_pos[1] += cam_mov_speed * camera_right[1];
-            camera_pos[2] += cam_mov_speed * camera_right[2];
-        }
-
-        // Camera rotation control
-        if controller.up_pressed {
-            camera_dir = linalg::mat3_mul_v3(
-                &linalg::rot_mat3(&camera._right, cam_rot_speed),
-                &camera_dir,
-            );
-            camera_up =
-                linalg::mat3_mul_v3(&linalg::rot_mat3(&camera._right, cam_rot_speed), &camera_up);
-        }
-        if controller.down_pressed {
-            camera_dir =
-                linalg::mat3_mul_v3(&linalg::rot_mat3(&camera._right, -cam_rot_speed), &camera_dir);
-            camera_up =
-                linalg::mat3_mul_v3(&linalg::rot_mat3(&camera._right, -cam_rot_speed), &camera_up);
-        }
-        if controller.left_pressed {
-            camera_dir = linalg::mat3_mul_v3(&linalg::rot_mat3(&camera._up, cam_rot_speed), &camera_dir);
-        }
-        if controller.right_pressed {
-            camera_dir = linalg::mat3_mul_v3(&linalg::rot_mat3(&camera._up, -cam_rot_speed), &camera_dir);
-        }
-        camera_right = camera._right;
-        camera.update(&camera_pos, &camera_dir, &camera_up);
+        fly.update(window.input(), delta as f32, renderer.camera_mut().unwrap());
+        renderer.set_time(time as f32);
 
         renderer.clear();
         renderer.render();
         window.swap_buffers();
         prev_time = time;
     }
+    // The shapes only delete their buffers, so the programs are deleted here
+    axes_material.del();
+    cube_material.del();
 }
@@ -2,7 +2,6 @@ mod controller;
 
 use gl;
 use glfw;
-use glfw::Context;
 
 use controller::Controller;
 use cuboid::opengl::components::{
@@ -10,20 +9,57 @@ use cuboid::opengl::components::{
     Renderer3D,
     Shape
 };
-use cuboid::opengl::{Shader, Renderer, Window};
+use cuboid::opengl::{App, AppRunner, FrameTime, Shader, Renderer, Window, WindowBuilder};
 use cuboid::opengl::io::CameraController;
 use cuboid::utils::types;
 
 const WINDOW_TITLE: &str = "Hello world triangle";
 
+struct HelloWorld<'a> {
+    renderer: Renderer3D<'a>,
+    controller: Controller,
+    wireframe: bool,
+}
+
+impl<'a> App for HelloWorld<'a> {
+    fn handle_event(&mut self, _window: &mut Window, event: glfw::WindowEvent) {
+        self.controller.handle_event(event);
+    }
+
+    fn update(&mut self, window: &mut Window, _time: &FrameTime) {
+        if self.controller.esc_pressed {
+            window.set_should_close(true);
+        }
+
+        if self.wireframe != self.controller.wireframe {
+            if self.controller.wireframe {
+                self.renderer.set_polygon_mode(gl::FRONT_AND_BACK, gl::LINE);
+                println!("LINE")
+            } else {
+                self.renderer.set_polygon_mode(gl::FRONT_AND_BACK, gl::FILL);
+                println!("FILL")
+            }
+            self.wireframe = self.controller.wireframe;
+        }
+    }
+
+    fn render(&mut self, window: &mut Window, time: &FrameTime) {
+        if time._frame % 600 == 0 {
+            window.set_title(&format!("{WINDOW_TITLE} ({:.0} FPS)", time._stats._fps));
+        }
+        self.renderer.clear();
+        self.renderer.render();
+    }
+}
+
 fn main() {
     // Initialization of the window
-    let mut window = Window::new()
+    let mut window = WindowBuilder::new()
         .dimensions(1000, 1000)
         .title(WINDOW_TITLE)
         .windowed()
         .build();
-    let mut renderer = Renderer3D::new().clear_color(0.0, 0.0, 0.0, 1.0);
+    let renderer = Renderer3D::new().clear_color(0.0, 0.0, 0.0, 1.0);
 
     // Define a material
     let shader = Shader::new()
@@ -47,31 +83,14 @@ fn main() {
         .build();
 
     // Add the item to the renderer
-    renderer.add_item(&triangle);
-
-    // Making a basic controller for the example
-    let mut wireframe = false;
-    let mut controller = Controller::new();
-
-    while !window.should_close() {
-        controller.poll_window_events(&mut window);
-        if controller.esc_pressed {
-            window.set_should_close(true);
-        }
+    let mut app = HelloWorld {
+        renderer,
+        controller: Controller::new(),
+        wireframe: false,
+    };
+    app.renderer.add_item(&triangle);
 
-        if wireframe != controller.wireframe {
-            if controller.wireframe {
-                renderer.set_polygon_mode(gl::FRONT_AND_BACK, gl::LINE);
-                println!("LINE")
-            } else {
-                renderer.set_polygon_mode(gl::FRONT_AND_BACK, gl::FILL);
-                println!("FILL")
-            }
-            wireframe = controller.wireframe;
-        }
-
-        renderer.clear();
-        renderer.render();
-        window.swap_buffers();
-    }
+    AppRunner::new().max_fps(240.0).run(&mut window, &mut app);
+    // The shapes only delete their buffers, so the program is deleted here
+    material.del();
 }
@@ -13,7 +13,7 @@ use cuboid::opengl::components::{
     Shape,
     Texture2D,
 };
-use cuboid::opengl::{Shader, Renderer, Window};
+use cuboid::opengl::{Shader, Renderer, WindowBuilder};
 use cuboid::opengl::io::CameraController;
 use cuboid::utils::{math::linalg, types};
 
@@ -30,9 +30,9 @@ fn main() {
         [-10.0, 10.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0],
     ];
 
-    let square_i: Vec<u32> = vec![0, 1, 2, 3];
+    let square_i: Vec<u32> = vec![0, 1, 2, 0, 2, 3];
 
-    let mut window = Window::new()
+    let mut window = WindowBuilder::new()
         .width(1000)
         .height(1000)
         .title(WINDOW_TITLE)
@@ -53,7 +53,7 @@ fn main() {
         .texture(&Texture2D::from_path("examples/gl_texture_example/resources/images/perroxd.png"))
         .build();
 
-    renderer.add_item_with_mode(&square, gl::QUADS);
+    renderer.add_item(&square);
     let mut camera_pos = [0.0, 0.0, 20.0];
     let mut camera_dir = [0.0, 0.0, 1.0];
     let mut camera_up = [0.0, 1.0, 0.0];
@@ -63,9 +63,7 @@ fn main() {
         &camera_pos,
         &camera_dir,
         &camera_up,
-        -1.0,
-        1.0,
-        -1.0,
+        90.0,
         1.0,
         1.0,
         1000.0,
@@ -145,11 +143,13 @@ fn main() {
             camera_dir = linalg::mat3_mul_v3(&linalg::rot_mat3_y(cam_rot_speed), &camera_dir);
             camera_up = linalg::mat3_mul_v3(&linalg::rot_mat3_y(cam_rot_speed), &camera_up);
         }
-        camera.update();
+        camera.update(&camera_pos, &camera_dir, &camera_up);
 
         renderer.clear();
         renderer.render();
         window.swap_buffers();
         prev_time = time;
     }
+    // The shapes only delete their buffers, so the program is deleted here
+    material.del();
 }
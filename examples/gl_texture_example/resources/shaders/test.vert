@@ -9,11 +9,13 @@ layout (std140, binding = 0) uniform View
     mat4 view;
 };
 
+uniform mat4 uModel;
+
 out vec4 outColor;
 out vec2 texCoord;
 
 void main() {
     outColor = vec4(aColor, 1.0);
-    gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0) * view;
+    gl_Position = vec4(aPos.x, aPos.y, aPos.z, 1.0) * uModel * view;
     texCoord = aTexCoord.xy;
 }
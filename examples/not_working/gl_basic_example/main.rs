@@ -89,9 +89,7 @@ fn main() {
         &camera_pos,
         &camera_dir,
         &camera_up,
-        -1.0,
-        1.0,
-        -1.0,
+        90.0,
         1.0,
         1.0,
         1000.0,
@@ -0,0 +1,230 @@
+use crate::opengl::{
+    assert_gl_is_loaded,
+    buffers::{EBO, VAO, VBO},
+    check_gl_error,
+    components::{FloatVertex, Material, Shape, Texture2D},
+    BindingCache, DrawKey, Drawable, RenderState, Shader,
+};
+use crate::utils::math::geometry::Aabb;
+use crate::utils::math::linalg;
+use crate::utils::mesh::VertexAttribute;
+use gl::types::*;
+use std::mem::size_of;
+
+/// Static shapes sharing a material, a texture and a vertex layout, merged into a single vertex
+/// array to be drawn with one draw call. The vertices are copied once placed by the transforms
+/// of the shapes, so the shapes keep being drawn where they were, while picking on the CPU is
+/// lost. The batch is drawn with an identity `uModel`.
+pub struct ShapeBatch<'a> {
+    _vao: VAO<'a>,
+    _vbo: u32,
+    _ebo: u32,
+    pub _material: Material,
+    pub _texture: Texture2D,
+    pub _count: u32,
+    pub _draw_mode: GLenum,
+    /// Union of the bounds of the shapes.
+    pub _bounds: Option<Aabb>,
+    /// Number of shapes merged into the batch.
+    pub _shapes: usize,
+}
+
+impl<'a> Drawable for ShapeBatch<'a> {
+    fn draw_with_mode(&self, mode: GLenum) {
+        self._material.use_program();
+        self._material
+            ._shader
+            .set_matrix4fv("uModel", &linalg::eye4());
+        self._texture.bind();
+        self.draw_geometry(mode);
+        self._texture.unbind();
+    }
+
+    fn draw(&self) {
+        self.draw_with_mode(self._draw_mode);
+    }
+
+    fn draw_geometry(&self, mode: GLenum) {
+        self._vao.bind();
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DrawElements(
+                mode,
+                self._count.try_into().unwrap(),
+                gl::UNSIGNED_INT,
+                0 as *const _,
+            );
+        }
+        self._vao.unbind();
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return self._bounds;
+    }
+
+    fn render_state(&self) -> Option<RenderState> {
+        return self._material._render_state;
+    }
+
+    fn shader(&self) -> Option<Shader> {
+        return Some(self._material._shader);
+    }
+
+    fn draw_key(&self) -> Option<DrawKey> {
+        return Some(DrawKey {
+            _shader: self._material._shader.get_id(),
+            _material: self._material.key(),
+            _texture: self._texture.get_id(),
+            _vao: self._vao.get_id(),
+        });
+    }
+
+    fn draw_cached(&self, mode: GLenum, bindings: &mut BindingCache) {
+        self._material.use_program_cached(bindings);
+        self._material
+            ._shader
+            .set_matrix4fv("uModel", &linalg::eye4());
+        bindings.bind_texture(self._texture.get_id());
+        bindings.bind_vao(self._vao.get_id());
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DrawElements(
+                mode,
+                self._count.try_into().unwrap(),
+                gl::UNSIGNED_INT,
+                0 as *const _,
+            );
+        }
+    }
+}
+
+impl<'a> Drop for ShapeBatch<'a> {
+    fn drop(&mut self) {
+        self.del();
+    }
+}
+
+impl<'a> ShapeBatch<'a> {
+    /// Merges the given shapes, which must all be batchable with each other (see
+    /// `Shape::can_batch_with`). The shapes don't need to be built.
+    pub fn new<T: FloatVertex>(shapes: &[&Shape<'a, T>]) -> ShapeBatch<'a> {
+        let first = shapes.first().expect("A batch needs at least one shape.");
+        for shape in shapes {
+            assert!(
+                first.can_batch_with(shape),
+                "The shapes of a batch must be static lists sharing a material, a texture and a \
+                 vertex layout."
+            );
+        }
+
+        let floats_per_vertex = first.floats().1;
+        assert!(
+            floats_per_vertex > 0,
+            "The vertices of a batch must be at least one float long."
+        );
+        let mut vertices: Vec<f32> = vec![];
+        let mut indices: Vec<u32> = vec![];
+        let mut bounds: Option<Aabb> = None;
+        for shape in shapes {
+            let base = (vertices.len() / floats_per_vertex) as u32;
+            let (floats, _) = shape.floats();
+            // Trailing floats that don't make up a whole vertex would shift the next shapes
+            let whole = floats.len() - floats.len() % floats_per_vertex;
+            let start = vertices.len();
+            vertices.extend_from_slice(&floats[..whole]);
+            place_vertices(shape, &mut vertices[start..], floats_per_vertex);
+            let count = (shape._count as usize).min(shape._indices.len());
+            indices.extend(shape._indices[..count].iter().map(|i| i + base));
+            if let Some(shape_bounds) = shape.bounds() {
+                bounds.get_or_insert_with(Aabb::empty).merge(&shape_bounds);
+            }
+        }
+
+        let stride = (floats_per_vertex * size_of::<f32>()) as u32;
+        let vao = VAO::new().stride(stride).sizes(first._vert_sizes).build();
+        vao.bind();
+        let vbo = VBO::new()
+            .vertices(&vertices)
+            .usage(gl::STATIC_DRAW)
+            .build();
+        let ebo = EBO::new()
+            .indices(&indices)
+            .count(indices.len() as u32)
+            .usage(gl::STATIC_DRAW)
+            .build();
+        for l in first._layouts {
+            vao.link_vbo(&vbo, *l);
+        }
+        vao.unbind();
+        vbo.unbind();
+        ebo.unbind();
+
+        return ShapeBatch {
+            _vao: vao,
+            _vbo: vbo.get_id(),
+            _ebo: ebo.get_id(),
+            _material: first._material,
+            _texture: first._texture,
+            _count: indices.len() as u32,
+            _draw_mode: first._draw_mode,
+            _bounds: bounds,
+            _shapes: shapes.len(),
+        };
+    }
+
+    /// Merges every batchable shape into the first batch it is compatible with. The shapes that
+    /// can't be batched are left out, and have to be drawn on their own.
+    pub fn group<T: FloatVertex>(shapes: &[&Shape<'a, T>]) -> Vec<ShapeBatch<'a>> {
+        let mut groups: Vec<Vec<&Shape<'a, T>>> = vec![];
+        for shape in shapes.iter().copied().filter(|shape| shape.is_batchable()) {
+            match groups
+                .iter_mut()
+                .find(|group| group[0].can_batch_with(shape))
+            {
+                Some(group) => group.push(shape),
+                None => groups.push(vec![shape]),
+            }
+        }
+        return groups.iter().map(|group| ShapeBatch::new(group)).collect();
+    }
+
+    /// Deletes the buffers of the batch. The material and the texture are shared with the
+    /// shapes, so they are left to be deleted by whoever created them.
+    pub fn del(&self) {
+        self._vao.del();
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DeleteBuffers(1, &self._vbo);
+            gl::DeleteBuffers(1, &self._ebo);
+        }
+        check_gl_error("ShapeBatch::del");
+    }
+}
+
+/// Moves the positions and the normals of the copied vertices of a shape by its transform.
+fn place_vertices<T>(shape: &Shape<'_, T>, vertices: &mut [f32], floats_per_vertex: usize) {
+    let matrix = shape._transform.mat4();
+    let position = shape
+        .attribute_offset(&VertexAttribute::Position)
+        .filter(|offset| offset + 3 <= floats_per_vertex);
+    let normal = shape
+        .attribute_offset(&VertexAttribute::Normal)
+        .filter(|offset| offset + 3 <= floats_per_vertex);
+    // Normals are moved by the inverse transpose, to stay orthogonal to the scaled surfaces
+    let inverse = linalg::inverse4(&matrix);
+    for vertex in vertices.chunks_exact_mut(floats_per_vertex) {
+        if let Some(offset) = position {
+            let point = [vertex[offset], vertex[offset + 1], vertex[offset + 2]];
+            vertex[offset..offset + 3].copy_from_slice(&linalg::transform_point(&matrix, &point));
+        }
+        if let (Some(offset), Some(inverse)) = (normal, inverse) {
+            let n = [vertex[offset], vertex[offset + 1], vertex[offset + 2]];
+            let moved = [0, 1, 2].map(|i| {
+                return inverse[0][i] * n[0] + inverse[1][i] * n[1] + inverse[2][i] * n[2];
+            });
+            if linalg::norm_v3(&moved) > 0.0 {
+                vertex[offset..offset + 3].copy_from_slice(&linalg::normalize_v3(&moved));
+            }
+        }
+    }
+}
@@ -1,40 +1,231 @@
 use crate::utils::conversions;
+use crate::utils::math::geometry::{Frustum, Ray};
 use crate::utils::math::linalg;
 use crate::utils::types::{V3, V4};
 use crate::opengl::{buffers::UBO, Transform, Transformable};
+use std::mem::size_of;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Uniform block |======================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Declaration of the camera uniform block, to be pasted in the shaders that use it. The
+/// matrices are stored by rows, so GLSL sees their transpose: vectors are multiplied on the
+/// left, as in `vec4(aPos, 1.0) * uViewProjection`. Shaders only needing the combined matrix
+/// can still declare a block holding a single `mat4`, as it comes first.
+pub const CAMERA_BLOCK_GLSL: &str = "layout (std140, binding = 0) uniform Camera
+{
+    mat4 uViewProjection;         // offset 0
+    mat4 uView;                   // offset 64
+    mat4 uProjection;             // offset 128
+    mat4 uInverseView;            // offset 192
+    mat4 uInverseProjection;      // offset 256
+    mat4 uInverseViewProjection;  // offset 320
+    vec4 uCameraPosition;         // offset 384, w = 1
+    vec2 uNearFar;                // offset 400, far is infinite for an infinite projection
+    vec2 uViewportSize;           // offset 408, in pixels
+    float uTime;                  // offset 416, in seconds
+};
+";
+
+/// Fields of the camera uniform block that depend on the camera alone, in the std140 layout of
+/// `CAMERA_BLOCK_GLSL`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraBlock {
+    pub _view_projection: [V4; 4],
+    pub _view: [V4; 4],
+    pub _projection: [V4; 4],
+    pub _inverse_view: [V4; 4],
+    pub _inverse_projection: [V4; 4],
+    pub _inverse_view_projection: [V4; 4],
+    pub _position: V4,
+    pub _near_far: [f32; 2],
+}
+
+/// Fields of the camera uniform block that change with every frame or viewport. They are set
+/// by `Renderer3D` when drawing with the camera.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CameraFrameBlock {
+    pub _viewport_size: [f32; 2],
+    pub _time: f32,
+    _padding: [f32; 3],
+}
+
+impl CameraFrameBlock {
+    pub fn new(viewport_size: (u32, u32), time: f32) -> CameraFrameBlock {
+        return CameraFrameBlock {
+            _viewport_size: [viewport_size.0 as f32, viewport_size.1 as f32],
+            _time: time,
+            _padding: [0.0; 3],
+        };
+    }
+}
+
+/// Offset of the `CameraFrameBlock` in the camera uniform block.
+pub const CAMERA_FRAME_OFFSET: u32 = size_of::<CameraBlock>() as u32;
+/// Size in bytes of the whole camera uniform block.
+pub const CAMERA_BLOCK_SIZE: u32 = CAMERA_FRAME_OFFSET + size_of::<CameraFrameBlock>() as u32;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=========================================| Camera |==========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub trait Camera {
     fn get_position(&self) -> V3;
     fn get_direction(&self) -> V3;
     fn get_up(&self) -> V3;
     fn get_right(&self) -> V3;
-    fn get_transform(&self) -> Vec<V4>;
+    /// Matrix going from the space of the camera to clip space, with the zoom applied.
+    fn get_projection(&self) -> [V4; 4];
     fn get_applied_transform(&self) -> Transform;
     fn update(&mut self, new_pos: &V3, new_dir: &V3, new_up: &V3);
     fn get_ubo(&self) -> UBO;
     fn set_ubo(&mut self, ubo: UBO);
 
+    /// Combined view and projection matrix.
+    fn get_transform(&self) -> Vec<V4> {
+        return linalg::mat4_mul4(&self.get_projection(), &self.get_view()).to_vec();
+    }
+
+    /// Matrix going from world space to the space of the camera.
+    fn get_view(&self) -> [V4; 4] {
+        return linalg::look_at(&self.get_position(), &self.get_up(), &self.get_direction());
+    }
+
+    /// Distances to the near and far planes. The far one is infinite for an infinite projection.
+    /// By default they are read from the projection, assuming the depth range of OpenGL.
+    fn get_clip_planes(&self) -> (f32, f32) {
+        let inverse = match linalg::inverse4(&self.get_projection()) {
+            Some(inverse) => inverse,
+            None => return (0.0, f32::INFINITY),
+        };
+        let distance = |depth: f32| -> f32 {
+            let point = linalg::mat4_mul_v4(&inverse, &[0.0, 0.0, depth, 1.0]);
+            // The far plane of an infinite projection is at w = 0
+            return (point[2] / point[3]).abs();
+        };
+        return (distance(-1.0), distance(1.0));
+    }
+
+    /// Adapts the horizontal extent of the view to an aspect ratio (width over height), keeping
+    /// the vertical one. Cameras that don't depend on it ignore it.
+    fn set_aspect_ratio(&mut self, _aspect: f32) {}
+
+    /// Scales the extent of the view around its center by 1 / zoom, so that values above 1 zoom
+    /// in. The position of the camera is unchanged. Cameras that can't zoom ignore it.
+    fn set_zoom(&mut self, _zoom: f32) {}
+
+    fn get_zoom(&self) -> f32 {
+        return 1.0;
+    }
+
+    /// Projects a point from world space to the screen of a window of the given size. Returns
+    /// its screen coordinates, from the upper-left corner like the cursor position, along with
+    /// its depth in normalized device coordinates. Points behind the camera give `None`.
+    fn project(&self, point: &V3, size: (u32, u32)) -> Option<V3> {
+        let transform = conversions::vec4_to_v4(&self.get_transform());
+        let clip = linalg::mat4_mul_v4(&transform, &conversions::v3_to_v4(point));
+        if clip[3] <= 0.0 {
+            return None;
+        }
+        let ndc = conversions::v4_to_v3(&clip);
+        return Some([
+            (ndc[0] + 1.0) / 2.0 * size.0 as f32,
+            (1.0 - ndc[1]) / 2.0 * size.1 as f32,
+            ndc[2],
+        ]);
+    }
+
+    /// Inverse of `project`, giving the point in world space seen at the given screen
+    /// coordinates and depth, or `None` if the transform of the camera can't be inverted.
+    fn unproject(&self, screen: &V3, size: (u32, u32)) -> Option<V3> {
+        let transform = conversions::vec4_to_v4(&self.get_transform());
+        let inverse = linalg::inverse4(&transform)?;
+        let ndc = [
+            2.0 * screen[0] / size.0 as f32 - 1.0,
+            1.0 - 2.0 * screen[1] / size.1 as f32,
+            screen[2],
+            1.0,
+        ];
+        return Some(conversions::v4_to_v3(&linalg::mat4_mul_v4(&inverse, &ndc)));
+    }
+
+    /// Ray in world space going from the camera through the given screen coordinates, such as
+    /// the cursor position, or `None` if the transform of the camera can't be inverted.
+    fn ray(&self, screen: (f64, f64), size: (u32, u32)) -> Option<Ray> {
+        let (x, y) = (screen.0 as f32, screen.1 as f32);
+        // These depths are finite for every projection, including the infinite and reversed ones
+        let a = self.unproject(&[x, y, 0.25], size)?;
+        let b = self.unproject(&[x, y, 0.75], size)?;
+        let mut direction = linalg::normalize_v3(&linalg::sub_v3(&b, &a));
+        if linalg::dot_v3(&direction, &self.get_direction()) > 0.0 {
+            direction = linalg::scale_v3(&direction, -1.0);
+        }
+        // The ray starts in the plane of the camera, which is its position for a perspective
+        let line = Ray::new(&a, &direction);
+        let origin = line.at(line.closest_distance(&self.get_position()));
+        return Some(Ray::new(&origin, &direction));
+    }
+
+    /// Region of world space seen by the camera, used to cull what is outside of it.
+    fn frustum(&self) -> Frustum {
+        return Frustum::from_matrix(&conversions::vec4_to_v4(&self.get_transform()));
+    }
+
     /*
     The following functions (update_ubo and make_ubo) are not meant to be overwritten. Instead,
     they define the default behaviour for the camera's uniform, which is bound to the index 0
     and contains the data associated with the MVP matrix.
     */
 
-    /// Update the UBO
+    /// Fields of the uniform block given by the camera.
+    fn get_block(&self) -> CameraBlock {
+        let view = self.get_view();
+        let projection = self.get_projection();
+        let view_projection = linalg::mat4_mul4(&projection, &view);
+        // A degenerate camera gives the identity rather than stopping the frame
+        let inverse = |matrix: &[V4; 4]| -> [V4; 4] {
+            return linalg::inverse4(matrix).unwrap_or(linalg::eye4());
+        };
+        let (near, far) = self.get_clip_planes();
+        return CameraBlock {
+            _view_projection: view_projection,
+            _view: view,
+            _projection: projection,
+            _inverse_view: inverse(&view),
+            _inverse_projection: inverse(&projection),
+            _inverse_view_projection: inverse(&view_projection),
+            _position: conversions::v3_to_v4(&self.get_position()),
+            _near_far: [near, far],
+        };
+    }
+
+    /// Update the UBO, leaving the fields set by `update_ubo_frame` as they are.
     fn update_ubo(&self) {
-        self.get_ubo().buffer_data(0, &self.get_transform());
+        self.get_ubo().buffer_data(0, &[self.get_block()]);
+    }
+
+    /// Sets the size in pixels of the viewport drawn into and the time in seconds.
+    fn update_ubo_frame(&self, viewport_size: (u32, u32), time: f32) {
+        let frame = CameraFrameBlock::new(viewport_size, time);
+        self.get_ubo().buffer_data(CAMERA_FRAME_OFFSET, &[frame]);
     }
 
     /// Makes an UBO
-    fn make_ubo() -> UBO {
-        let camera_ubo = UBO::new().size(64).build();
+    fn make_ubo() -> UBO
+    where
+        Self: Sized,
+    {
+        let camera_ubo = UBO::new().size(CAMERA_BLOCK_SIZE).build();
         camera_ubo.bind_index(0);
         return camera_ubo;
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-//|================================| Orthogonal camera |========================================|//
+//|====================================| Orthogonal camera |====================================|//
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub struct OrthoCamera {
@@ -44,6 +235,7 @@ pub struct OrthoCamera {
     _ymax: f32,
     _zmin: f32,
     _zmax: f32,
+    _zoom: f32,
     pub _position: V3,
     pub _direction: V3,
     pub _up: V3,
@@ -81,20 +273,22 @@ impl Camera for OrthoCamera {
         return self._transform;
     }
 
-    fn get_transform(&self) -> Vec<V4> {
-        let look = linalg::look_at(
+    fn get_view(&self) -> [V4; 4] {
+        return linalg::look_at(
             &self._position,
             &self._up,
             &self._direction,
             // &self.right,
         );
-        let orth_view = linalg::ortho(
-            self._xmin, self._xmax, self._ymin, self._ymax, self._zmin, self._zmax,
-        );
-        return linalg::mat4_mul4(
-            &conversions::vec4_to_v4(&orth_view),
-            &conversions::vec4_to_v4(&look),
-        );
+    }
+
+    fn get_projection(&self) -> [V4; 4] {
+        let (xmin, xmax, ymin, ymax) = self.zoomed_bounds();
+        return linalg::ortho(xmin, xmax, ymin, ymax, self._zmin, self._zmax);
+    }
+
+    fn get_clip_planes(&self) -> (f32, f32) {
+        return (self._zmin, self._zmax);
     }
 
     fn update(&mut self, new_pos: &V3, new_dir: &V3, new_up: &V3) {
@@ -105,6 +299,23 @@ impl Camera for OrthoCamera {
         self._right = linalg::cross_v3(&self._up, &self._direction);
         self.update_ubo();
     }
+
+    fn set_aspect_ratio(&mut self, aspect: f32) {
+        let center = (self._xmin + self._xmax) / 2.0;
+        let half_width = (self._ymax - self._ymin) / 2.0 * aspect;
+        self._xmin = center - half_width;
+        self._xmax = center + half_width;
+        self.update_ubo();
+    }
+
+    fn set_zoom(&mut self, zoom: f32) {
+        self._zoom = zoom;
+        self.update_ubo();
+    }
+
+    fn get_zoom(&self) -> f32 {
+        return self._zoom;
+    }
 }
 
 impl OrthoCamera {
@@ -127,6 +338,7 @@ impl OrthoCamera {
             _ymax: ymax,
             _zmin: zmin,
             _zmax: zmax,
+            _zoom: 1.0,
             _position: *position,
             _direction: new_direction,
             _up: linalg::normalize_v3(up),
@@ -156,6 +368,7 @@ impl OrthoCamera {
             _ymax: ymax,
             _zmin: zmin,
             _zmax: zmax,
+            _zoom: 1.0,
             _position: *position,
             _direction: new_direction,
             _up: linalg::normalize_v3(up),
@@ -170,6 +383,15 @@ impl OrthoCamera {
         self._direction = OrthoCamera::dir_from_target(&self._position, target);
     }
 
+    /// Bounds of the view in x and y, scaled around their center by the zoom.
+    fn zoomed_bounds(&self) -> (f32, f32, f32, f32) {
+        let cx = (self._xmin + self._xmax) / 2.0;
+        let cy = (self._ymin + self._ymax) / 2.0;
+        let hw = (self._xmax - self._xmin) / 2.0 / self._zoom;
+        let hh = (self._ymax - self._ymin) / 2.0 / self._zoom;
+        return (cx - hw, cx + hw, cy - hh, cy + hh);
+    }
+
     fn dir_from_target(position: &V3, target: &V3) -> V3 {
         return linalg::normalize_v3(&linalg::sub_v3(position, target));
     }
@@ -182,16 +404,20 @@ impl Transformable for OrthoCamera {
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-//|================================| Perspective camera |=======================================|//
+//|===================================| Perspective camera |====================================|//
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Camera with a symmetric perspective projection, defined by its vertical field of view, aspect
+/// ratio and clipping planes. The far plane can be at infinity, and the depth can be reversed
+/// (see `Renderer3D::set_reverse_z`) to keep its precision over very large scenes.
 pub struct PerspectiveCamera {
-    _xmin: f32,
-    _xmax: f32,
-    _ymin: f32,
-    _ymax: f32,
-    _zmin: f32,
-    _zmax: f32,
+    _fov: f32,
+    _aspect: f32,
+    _near: f32,
+    _far: Option<f32>,
+    _reverse_z: bool,
+    _zoom: f32,
+    _projection: [V4; 4],
     pub _position: V3,
     pub _direction: V3,
     pub _up: V3,
@@ -229,98 +455,240 @@ impl Camera for PerspectiveCamera {
         return self._transform;
     }
 
-    fn get_transform(&self) -> Vec<V4> {
-        let look = linalg::look_at(
-            &self._position,
-            &self._up,
-            &self._direction,
-            // &self.right,
-        );
-        let persp_view = linalg::perspective(
-            self._xmin, self._xmax, self._ymin, self._ymax, self._zmin, self._zmax,
-        );
-        return linalg::mat4_mul4(
-            &conversions::vec4_to_v4(&persp_view),
-            &conversions::vec4_to_v4(&look),
-        );
+    fn get_view(&self) -> [V4; 4] {
+        return linalg::look_at(&self._position, &self._up, &self._direction);
+    }
+
+    fn get_projection(&self) -> [V4; 4] {
+        return self._projection;
+    }
+
+    fn get_clip_planes(&self) -> (f32, f32) {
+        return (self._near, self._far.unwrap_or(f32::INFINITY));
     }
 
     fn update(&mut self, new_pos: &V3, new_dir: &V3, new_up: &V3) {
         self._position = *new_pos;
-        // self.direction = *new_dir;
-        // self.up = *new_up;
         self._direction = linalg::normalize_v3(new_dir);
         self._up = linalg::normalize_v3(new_up);
-        // self.right = linalg::cross_v3(&self.direction, &self.up);
         self._right = linalg::cross_v3(&self._up, &self._direction);
         self.update_ubo();
     }
+
+    fn set_aspect_ratio(&mut self, aspect: f32) {
+        self._aspect = aspect;
+        self.rebuild_projection();
+    }
+
+    /// Zooming narrows the field of view, as a telephoto lens would.
+    fn set_zoom(&mut self, zoom: f32) {
+        self._zoom = zoom;
+        self.rebuild_projection();
+    }
+
+    fn get_zoom(&self) -> f32 {
+        return self._zoom;
+    }
 }
 
 impl PerspectiveCamera {
+    /// Makes a camera from its vertical field of view in degrees, its aspect ratio (width over
+    /// height) and the distances to its near and far planes.
     pub fn new(
         position: &V3,
         direction: &V3,
         up: &V3,
-        xmin: f32,
-        xmax: f32,
-        ymin: f32,
-        ymax: f32,
-        zmin: f32,
-        zmax: f32,
+        fov: f32,
+        aspect: f32,
+        near: f32,
+        far: f32,
     ) -> PerspectiveCamera {
         let new_direction = linalg::normalize_v3(direction);
-        return PerspectiveCamera {
-            _xmin: xmin,
-            _xmax: xmax,
-            _ymin: ymin,
-            _ymax: ymax,
-            _zmin: zmin,
-            _zmax: zmax,
+        let mut camera = PerspectiveCamera {
+            _fov: fov,
+            _aspect: aspect,
+            _near: near,
+            _far: Some(far),
+            _reverse_z: false,
+            _zoom: 1.0,
+            _projection: linalg::eye4(),
             _position: *position,
             _direction: new_direction,
             _up: linalg::normalize_v3(up),
-            // _right: linalg::normalize_v3(&linalg::cross_v3(&new_direction, &up)),
             _right: linalg::normalize_v3(&linalg::cross_v3(&up, &new_direction)),
             _ubo: PerspectiveCamera::make_ubo(),
             _transform: Transform::new(),
         };
+        camera.rebuild_projection();
+        return camera;
     }
 
     pub fn new_from_target(
         position: &V3,
         target: &V3,
         up: &V3,
-        xmin: f32,
-        xmax: f32,
-        ymin: f32,
-        ymax: f32,
-        zmin: f32,
-        zmax: f32,
+        fov: f32,
+        aspect: f32,
+        near: f32,
+        far: f32,
     ) -> PerspectiveCamera {
-        let new_direction = PerspectiveCamera::dir_from_target(position, target);
-        return PerspectiveCamera {
-            _xmin: xmin,
-            _xmax: xmax,
-            _ymin: ymin,
-            _ymax: ymax,
-            _zmin: zmin,
-            _zmax: zmax,
-            _position: *position,
-            _direction: new_direction,
-            _up: linalg::normalize_v3(up),
-            // _right: linalg::normalize_v3(&linalg::cross_v3(&new_direction, &up)),
-            _right: linalg::normalize_v3(&linalg::cross_v3(&up, &new_direction)),
-            _ubo: PerspectiveCamera::make_ubo(),
-            _transform: Transform::new(),
-        };
+        let direction = PerspectiveCamera::dir_from_target(position, target);
+        return PerspectiveCamera::new(position, &direction, up, fov, aspect, near, far);
+    }
+
+    /// Moves the far plane to infinity.
+    pub fn infinite(mut self) -> PerspectiveCamera {
+        self.set_far(None);
+        return self;
+    }
+
+    pub fn reverse_z(mut self, reverse_z: bool) -> PerspectiveCamera {
+        self.set_reverse_z(reverse_z);
+        return self;
     }
 
     pub fn set_target(&mut self, target: &V3) {
         self._direction = PerspectiveCamera::dir_from_target(&self._position, target);
+        self._right = linalg::normalize_v3(&linalg::cross_v3(&self._up, &self._direction));
+        self.update_ubo();
+    }
+
+    /// Sets the vertical field of view in degrees.
+    pub fn set_fov(&mut self, fov: f32) {
+        self._fov = fov;
+        self.rebuild_projection();
+    }
+
+    pub fn set_near(&mut self, near: f32) {
+        self._near = near;
+        self.rebuild_projection();
+    }
+
+    /// Sets the distance to the far plane, `None` being infinitely far.
+    pub fn set_far(&mut self, far: Option<f32>) {
+        self._far = far;
+        self.rebuild_projection();
+    }
+
+    /// Uses a depth going from 1 at the near plane to 0 at the far one. The depth test has to
+    /// be configured accordingly, with `Renderer3D::set_reverse_z`.
+    pub fn set_reverse_z(&mut self, reverse_z: bool) {
+        self._reverse_z = reverse_z;
+        self.rebuild_projection();
+    }
+
+    pub fn get_fov(&self) -> f32 {
+        return self._fov;
+    }
+
+    pub fn get_aspect_ratio(&self) -> f32 {
+        return self._aspect;
+    }
+
+    pub fn get_near(&self) -> f32 {
+        return self._near;
+    }
+
+    pub fn get_far(&self) -> Option<f32> {
+        return self._far;
+    }
+
+    pub fn is_reverse_z(&self) -> bool {
+        return self._reverse_z;
+    }
+
+    /// Field of view in degrees once the zoom is applied.
+    fn zoomed_fov(&self) -> f32 {
+        let half = (self._fov.to_radians() / 2.0).tan() / self._zoom;
+        return 2.0 * half.atan().to_degrees();
+    }
+
+    fn rebuild_projection(&mut self) {
+        let fov = self.zoomed_fov();
+        self._projection = if self._reverse_z {
+            linalg::perspective_reverse_z(fov, self._aspect, self._near, self._far)
+        } else {
+            linalg::perspective_fov(fov, self._aspect, self._near, self._far)
+        };
+        self.update_ubo();
     }
 
     fn dir_from_target(position: &V3, target: &V3) -> V3 {
-        return linalg::normalize_v3(&linalg::sub_v3(target, position));
+        return linalg::normalize_v3(&linalg::sub_v3(position, target));
+    }
+}
+
+impl Transformable for PerspectiveCamera {
+    fn get_trans(&self) -> Transform {
+        return self.get_applied_transform();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (u32, u32) = (800, 600);
+
+    /// A camera at (1, 2, 3) looking down -z, made without the uniform buffer that needs a
+    /// context.
+    fn camera(far: Option<f32>) -> PerspectiveCamera {
+        return PerspectiveCamera {
+            _fov: 60.0,
+            _aspect: 4.0 / 3.0,
+            _near: 0.1,
+            _far: far,
+            _reverse_z: false,
+            _zoom: 1.0,
+            _projection: linalg::perspective_fov(60.0, 4.0 / 3.0, 0.1, far),
+            _position: [1.0, 2.0, 3.0],
+            _direction: [0.0, 0.0, 1.0],
+            _up: [0.0, 1.0, 0.0],
+            _right: [1.0, 0.0, 0.0],
+            _ubo: UBO::new(),
+            _transform: Transform::new(),
+        };
+    }
+
+    fn assert_close(a: &V3, b: &V3) {
+        let distance = linalg::norm_v3(&linalg::sub_v3(a, b));
+        assert!(distance < 1e-3, "{a:?} is not {b:?}.");
+    }
+
+    #[test]
+    fn unprojects_projected_points() {
+        for camera in [camera(Some(100.0)), camera(None)] {
+            for point in [[1.0, 2.0, -5.0], [0.5, 2.5, -1.0], [-3.0, 0.0, -40.0]] {
+                let screen = camera.project(&point, SIZE).unwrap();
+                assert_close(&camera.unproject(&screen, SIZE).unwrap(), &point);
+            }
+            // The point in front of the camera is at the center of the screen
+            let center = camera.project(&[1.0, 2.0, -5.0], SIZE).unwrap();
+            assert_close(&[center[0], center[1], 0.0], &[400.0, 300.0, 0.0]);
+            assert_eq!(camera.project(&[1.0, 2.0, 5.0], SIZE), None);
+        }
+    }
+
+    #[test]
+    fn casts_rays_into_the_view() {
+        for camera in [camera(Some(100.0)), camera(None)] {
+            let ray = camera.ray((400.0, 300.0), SIZE).unwrap();
+            assert_close(&ray._origin, &[1.0, 2.0, 3.0]);
+            assert_close(&ray._direction, &[0.0, 0.0, -1.0]);
+
+            // The upper-left corner is to the left, above and in front of the camera
+            let corner = camera.ray((0.0, 0.0), SIZE).unwrap();
+            assert!(corner._direction[0] < 0.0);
+            assert!(corner._direction[1] > 0.0);
+            assert!(corner._direction[2] < 0.0);
+
+            let point = [0.5, 2.5, -1.0];
+            let screen = camera.project(&point, SIZE).unwrap();
+            let ray = camera
+                .ray((screen[0] as f64, screen[1] as f64), SIZE)
+                .unwrap();
+            assert_close(&ray.at(ray.closest_distance(&point)), &point);
+            assert!(ray.closest_distance(&point) > 0.0);
+        }
     }
 }
@@ -1,14 +1,37 @@
-use crate::opengl::Shader;
+use crate::opengl::{BindingCache, RenderState, Shader};
+use crate::utils::types::V3;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
+/// Lighting properties of a material. When a material has them, they are uploaded to the
+/// uniforms `uAmbient`, `uDiffuse`, `uSpecular`, `uShininess` and `uOpacity` of its shader.
+#[derive(Copy, Clone, PartialEq)]
+pub struct MaterialProperties {
+    pub _ambient: V3,
+    pub _diffuse: V3,
+    pub _specular: V3,
+    pub _shininess: f32,
+    pub _opacity: f32,
+}
+
+/// The program and the properties a shape is drawn with. A material is copied into every shape
+/// using it, and none of them owns its program, which has to be deleted with `del` once nothing
+/// draws with it anymore.
 #[derive(Copy, Clone)]
 pub struct Material {
     pub _shader: Shader,
+    pub _properties: Option<MaterialProperties>,
+    /// State the objects using the material are drawn with by the renderer, instead of its
+    /// default one.
+    pub _render_state: Option<RenderState>,
 }
 
 impl Material {
     pub fn new() -> Material {
         return Material {
             _shader: Shader::new(),
+            _properties: None,
+            _render_state: None,
         };
     }
 
@@ -17,11 +40,116 @@ impl Material {
         return self;
     }
 
+    pub fn properties(mut self, properties: &MaterialProperties) -> Material {
+        self._properties = Some(*properties);
+        return self;
+    }
+
+    pub fn render_state(mut self, state: &RenderState) -> Material {
+        self._render_state = Some(*state);
+        return self;
+    }
+
+    /// Deletes the program, which the copies of the material share, so none of them may be
+    /// drawn afterwards.
     pub fn del(&self) {
         self._shader.del();
     }
 
     pub fn use_program(&self) {
         self._shader.use_program();
+        if let Some(properties) = self._properties {
+            properties.set_uniforms(&self._shader);
+        }
+    }
+
+    /// Like `use_program`, but leaves out the program and the uniforms that the previous draw
+    /// calls already set.
+    pub fn use_program_cached(&self, bindings: &mut BindingCache) {
+        bindings.use_program(self._shader.get_id());
+        if !bindings.set_material(self.key()) {
+            return;
+        }
+        if let Some(properties) = self._properties {
+            properties.set_uniforms(&self._shader);
+        }
+    }
+
+    /// Identifies the program and the uniforms set by the material, equal for the materials
+    /// that set the same ones.
+    pub fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self._shader.get_id().hash(&mut hasher);
+        if let Some(properties) = self._properties {
+            for value in properties.values() {
+                value.to_bits().hash(&mut hasher);
+            }
+        }
+        return hasher.finish();
+    }
+}
+
+impl MaterialProperties {
+    pub fn new() -> MaterialProperties {
+        return MaterialProperties {
+            _ambient: [0.0, 0.0, 0.0],
+            _diffuse: [1.0, 1.0, 1.0],
+            _specular: [0.0, 0.0, 0.0],
+            _shininess: 0.0,
+            _opacity: 1.0,
+        };
+    }
+
+    pub fn ambient(mut self, r: f32, g: f32, b: f32) -> MaterialProperties {
+        self._ambient = [r, g, b];
+        return self;
+    }
+
+    pub fn diffuse(mut self, r: f32, g: f32, b: f32) -> MaterialProperties {
+        self._diffuse = [r, g, b];
+        return self;
+    }
+
+    pub fn specular(mut self, r: f32, g: f32, b: f32) -> MaterialProperties {
+        self._specular = [r, g, b];
+        return self;
+    }
+
+    pub fn shininess(mut self, shininess: f32) -> MaterialProperties {
+        self._shininess = shininess;
+        return self;
+    }
+
+    pub fn opacity(mut self, opacity: f32) -> MaterialProperties {
+        self._opacity = opacity;
+        return self;
+    }
+
+    /// The properties as a list of floats, in the order of the fields.
+    pub fn values(&self) -> [f32; 11] {
+        let (a, d, s) = (self._ambient, self._diffuse, self._specular);
+        return [
+            a[0],
+            a[1],
+            a[2],
+            d[0],
+            d[1],
+            d[2],
+            s[0],
+            s[1],
+            s[2],
+            self._shininess,
+            self._opacity,
+        ];
+    }
+
+    /// Uploads the properties to the given shader, which must be in use.
+    pub fn set_uniforms(&self, shader: &Shader) {
+        let (a, d, s) = (self._ambient, self._diffuse, self._specular);
+        shader.set_3f("uAmbient", a[0], a[1], a[2]);
+        shader.set_3f("uDiffuse", d[0], d[1], d[2]);
+        shader.set_3f("uSpecular", s[0], s[1], s[2]);
+        shader.set_1f("uShininess", self._shininess);
+        shader.set_1f("uOpacity", self._opacity);
     }
 }
@@ -1,13 +1,24 @@
+pub mod batch;
 pub mod camera;
 pub mod material;
+pub mod model;
+pub mod oit;
+pub mod picking;
 pub mod renderer2d;
 pub mod renderer3d;
 pub mod shape;
 pub mod texture;
 
-pub use camera::{Camera, OrthoCamera, PerspectiveCamera};
-pub use material::Material;
+pub use batch::ShapeBatch;
+pub use camera::{
+    Camera, CameraBlock, CameraFrameBlock, OrthoCamera, PerspectiveCamera, CAMERA_BLOCK_GLSL,
+    CAMERA_BLOCK_SIZE, CAMERA_FRAME_OFFSET,
+};
+pub use material::{Material, MaterialProperties};
+pub use model::{Model, ModelCamera, ModelMesh, ModelNode};
+pub use oit::{OitBuffer, OIT_GLSL};
+pub use picking::{GpuPick, PickingBuffer};
 pub use renderer2d::Renderer2D;
-pub use renderer3d::Renderer3D;
-pub use shape::Shape;
+pub use renderer3d::{CullingStats, RenderStats, Renderer3D, TransparencyMode, Viewport};
+pub use shape::{FloatVertex, Shape};
 pub use texture::Texture2D;
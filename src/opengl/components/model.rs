@@ -0,0 +1,286 @@
+use crate::opengl::components::{
+    Material, MaterialProperties, OrthoCamera, PerspectiveCamera, Shape, Texture2D,
+};
+use crate::opengl::{Bounded, Shader};
+use crate::utils::conversions;
+use crate::utils::math::geometry::{Aabb, BoundingSphere, Obb};
+use crate::utils::math::linalg;
+use crate::utils::mesh::gltf::{self, GltfImage, GltfProjection};
+use crate::utils::mesh::{obj, Mesh};
+use crate::utils::types::V4;
+use gl::types::*;
+
+/// A mesh of a model, along with the index of the material it uses and how it is drawn.
+pub struct ModelMesh {
+    pub _name: String,
+    pub _mesh: Mesh,
+    pub _material: Option<usize>,
+    pub _draw_mode: GLenum,
+}
+
+/// A camera of a model, placed where its node is.
+pub enum ModelCamera {
+    Perspective(PerspectiveCamera),
+    Ortho(OrthoCamera),
+}
+
+/// A node of the scene graph of a model. The local matrix is relative to the parent, while the
+/// matrix is the world transform of the node. The bounds are those of its meshes, in the
+/// coordinates of the node.
+pub struct ModelNode {
+    pub _name: String,
+    pub _local: [V4; 4],
+    pub _matrix: [V4; 4],
+    pub _bounds: Aabb,
+    pub _children: Vec<usize>,
+    pub _meshes: Vec<usize>,
+    pub _camera: Option<usize>,
+}
+
+/// Geometry and materials loaded from a file. The model owns the vertex data, so the shapes
+/// made from it borrow the model.
+pub struct Model {
+    pub _meshes: Vec<ModelMesh>,
+    pub _materials: Vec<Material>,
+    pub _textures: Vec<Texture2D>,
+    pub _default_material: Material,
+    pub _nodes: Vec<ModelNode>,
+    pub _roots: Vec<usize>,
+    pub _cameras: Vec<ModelCamera>,
+}
+
+impl Model {
+    pub fn new() -> Model {
+        return Model {
+            _meshes: vec![],
+            _materials: vec![],
+            _textures: vec![],
+            _default_material: Material::new(),
+            _nodes: vec![],
+            _roots: vec![],
+            _cameras: vec![],
+        };
+    }
+
+    /// Loads an OBJ file and its MTL materials, which all use the given shader. The diffuse map
+    /// of each material is loaded as its texture.
+    pub fn from_obj(path: &str, shader: &Shader) -> Model {
+        let data = obj::load(path);
+        let mut model = Model::new();
+        model._default_material = Material::new().shader(shader);
+        for m in data._materials.iter() {
+            let properties = MaterialProperties::new()
+                .ambient(m._ambient[0], m._ambient[1], m._ambient[2])
+                .diffuse(m._diffuse[0], m._diffuse[1], m._diffuse[2])
+                .specular(m._specular[0], m._specular[1], m._specular[2])
+                .shininess(m._shininess)
+                .opacity(m._opacity);
+            model
+                ._materials
+                .push(Material::new().shader(shader).properties(&properties));
+            model._textures.push(match &m._diffuse_map {
+                Some(map) => Texture2D::from_path(map),
+                None => Texture2D::new(),
+            });
+        }
+        for object in data._objects.into_iter() {
+            model._meshes.push(ModelMesh {
+                _name: object._name,
+                _mesh: object._mesh,
+                _material: object._material,
+                _draw_mode: gl::TRIANGLES,
+            });
+        }
+        return model;
+    }
+
+    /// Loads a glTF 2.0 scene (`.gltf` or `.glb`) whose materials all use the given shader. The
+    /// base color of each material is loaded as its texture, and every camera of the scene gets
+    /// placed where its node is.
+    pub fn from_gltf(path: &str, shader: &Shader) -> Model {
+        let document = gltf::load(path);
+        let mut model = Model::new();
+        model._default_material = Material::new().shader(shader);
+
+        let images: Vec<Texture2D> = document
+            ._images
+            .iter()
+            .map(|image| match image {
+                GltfImage::Path(p) => Texture2D::from_path(p),
+                GltfImage::Bytes(b) => Texture2D::from_memory(b),
+            })
+            .collect();
+        for m in document._materials.iter() {
+            let c = m._base_color;
+            let properties = MaterialProperties::new()
+                .diffuse(c[0], c[1], c[2])
+                .opacity(c[3]);
+            model
+                ._materials
+                .push(Material::new().shader(shader).properties(&properties));
+            model._textures.push(match m._base_color_texture {
+                Some(i) => images[i],
+                None => Texture2D::new(),
+            });
+        }
+
+        let world = document.world_matrices();
+        let roots = document.roots();
+
+        // Every primitive becomes a mesh, so keep track of the ones of each glTF mesh
+        let mut mesh_primitives: Vec<Vec<usize>> = vec![];
+        for mesh in document._meshes.into_iter() {
+            let count = mesh._primitives.len();
+            let mut indices = vec![];
+            for (i, primitive) in mesh._primitives.into_iter().enumerate() {
+                indices.push(model._meshes.len());
+                model._meshes.push(ModelMesh {
+                    _name: if count == 1 {
+                        mesh._name.clone()
+                    } else {
+                        format!("{}.{i}", mesh._name)
+                    },
+                    _mesh: primitive._mesh,
+                    _material: primitive._material,
+                    _draw_mode: primitive._mode,
+                });
+            }
+            mesh_primitives.push(indices);
+        }
+
+        for (i, node) in document._nodes.iter().enumerate() {
+            let camera = node._camera.map(|c| {
+                model
+                    ._cameras
+                    .push(make_camera(&document._cameras[c]._projection, &world[i]));
+                model._cameras.len() - 1
+            });
+            let meshes = node._mesh.map_or(vec![], |m| mesh_primitives[m].clone());
+            let mut bounds = Aabb::empty();
+            for m in meshes.iter() {
+                bounds.merge(&Aabb::from_points(&model._meshes[*m]._mesh.positions()));
+            }
+            model._nodes.push(ModelNode {
+                _name: node._name.clone(),
+                _local: node._matrix,
+                _matrix: world[i],
+                _bounds: bounds,
+                _children: node._children.clone(),
+                _meshes: meshes,
+                _camera: camera,
+            });
+        }
+        model._roots = roots;
+        return model;
+    }
+
+    /// Builds a shape for every mesh of the model.
+    pub fn shapes(&self) -> Vec<Shape<'_, f32>> {
+        return self._meshes.iter().map(|m| self.make_shape(m)).collect();
+    }
+
+    /// Builds the shape of the mesh with the given name, if there is one.
+    pub fn shape(&self, name: &str) -> Option<Shape<'_, f32>> {
+        return self
+            ._meshes
+            .iter()
+            .find(|m| m._name == name)
+            .map(|m| self.make_shape(m));
+    }
+
+    fn make_shape<'a>(&'a self, mesh: &'a ModelMesh) -> Shape<'a, f32> {
+        let shape = Shape::from_mesh(&mesh._mesh);
+        let shape = match mesh._material {
+            Some(i) => shape
+                .material(&self._materials[i])
+                .texture(&self._textures[i]),
+            None => shape.material(&self._default_material),
+        };
+        return shape.draw_mode(mesh._draw_mode).build();
+    }
+}
+
+impl Bounded for ModelNode {
+    fn bounding_box(&self) -> Aabb {
+        return self._bounds.transform(&self._matrix);
+    }
+
+    fn oriented_box(&self) -> Obb {
+        return Obb::from_aabb(&self._bounds, &self._matrix);
+    }
+
+    fn bounding_sphere(&self) -> BoundingSphere {
+        return self._bounds.bounding_sphere().transform(&self._matrix);
+    }
+}
+
+/// The bounds of a whole model, with its meshes placed by the nodes using them. The meshes of a
+/// model without nodes, such as an OBJ file, are taken as they are.
+impl Bounded for Model {
+    fn bounding_box(&self) -> Aabb {
+        let mut aabb = Aabb::empty();
+        if self._nodes.is_empty() {
+            for mesh in self._meshes.iter() {
+                aabb.merge(&Aabb::from_points(&mesh._mesh.positions()));
+            }
+        }
+        for node in self._nodes.iter() {
+            aabb.merge(&node.bounding_box());
+        }
+        return aabb;
+    }
+
+    /// The box of the whole model is aligned with the world axes.
+    fn oriented_box(&self) -> Obb {
+        return Obb::from_aabb(&self.bounding_box(), &linalg::eye4());
+    }
+
+    fn bounding_sphere(&self) -> BoundingSphere {
+        let mut sphere = BoundingSphere::empty();
+        if self._nodes.is_empty() {
+            for mesh in self._meshes.iter() {
+                sphere.merge(&BoundingSphere::from_points(&mesh._mesh.positions()));
+            }
+        }
+        for node in self._nodes.iter() {
+            sphere.merge(&node.bounding_sphere());
+        }
+        return sphere;
+    }
+}
+
+/// Makes a camera looking down the -Z axis of the given world matrix.
+fn make_camera(projection: &GltfProjection, world: &[V4; 4]) -> ModelCamera {
+    let position = [world[0][3], world[1][3], world[2][3]];
+    let direction = conversions::v4_to_v3_dir(&linalg::mat4_mul_v4(world, &[0.0, 0.0, 1.0, 0.0]));
+    let up = conversions::v4_to_v3_dir(&linalg::mat4_mul_v4(world, &[0.0, 1.0, 0.0, 0.0]));
+    return match *projection {
+        GltfProjection::Perspective {
+            _yfov: yfov,
+            _aspect_ratio: aspect_ratio,
+            _znear: znear,
+            _zfar: zfar,
+        } => {
+            let mut camera = PerspectiveCamera::new(
+                &position,
+                &direction,
+                &up,
+                yfov.to_degrees(),
+                aspect_ratio.unwrap_or(1.0),
+                znear,
+                zfar.unwrap_or(f32::MAX),
+            );
+            // Infinite projections are the ones without a far plane
+            camera.set_far(zfar);
+            ModelCamera::Perspective(camera)
+        }
+        GltfProjection::Orthographic {
+            _xmag: xmag,
+            _ymag: ymag,
+            _znear: znear,
+            _zfar: zfar,
+        } => ModelCamera::Ortho(OrthoCamera::new(
+            &position, &direction, &up, -xmag, xmag, -ymag, ymag, znear, zfar,
+        )),
+    };
+}
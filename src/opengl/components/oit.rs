@@ -0,0 +1,324 @@
+use crate::opengl::{assert_gl_is_loaded, check_gl_error, Shader};
+use gl;
+use gl::types::GLenum;
+
+/// Outputs and helper to paste in the fragment shaders of transparent objects, so that they can
+/// be drawn both sorted and with `OitBuffer`. The color given to `writeTransparent` isn't
+/// premultiplied by its alpha. The renderer sets `uOitPass` to 1 during the OIT pass, and
+/// `uOitReverseZ` to 1 when the depth is reversed.
+pub const OIT_GLSL: &str = "layout (location = 0) out vec4 outColor;
+layout (location = 1) out float outRevealage;
+
+uniform int uOitPass;
+uniform int uOitReverseZ;
+
+void writeTransparent(vec4 color) {
+    if (uOitPass == 0) {
+        outColor = color;
+        return;
+    }
+    // Weight of McGuire and Bavoil, favoring the opaque fragments close to the camera
+    float depth = uOitReverseZ == 1 ? 1.0 - gl_FragCoord.z : gl_FragCoord.z;
+    float weight = clamp(
+        pow(min(1.0, color.a * 10.0) + 0.01, 3.0) * 1e8 * pow(1.0 - depth * 0.9, 3.0),
+        1e-2,
+        3e3
+    );
+    outColor = vec4(color.rgb * color.a, color.a) * weight;
+    outRevealage = color.a;
+}
+";
+
+const COMPOSITE_VERTEX: &str = "#version 460 core
+
+void main() {
+    // A triangle covering the viewport
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+const COMPOSITE_FRAGMENT: &str = "#version 460 core
+
+layout (binding = 0) uniform sampler2D uAccumulation;
+layout (binding = 1) uniform sampler2D uRevealage;
+
+out vec4 outColor;
+
+void main() {
+    ivec2 pixel = ivec2(gl_FragCoord.xy);
+    float revealage = texelFetch(uRevealage, pixel, 0).r;
+    if (revealage >= 1.0) {
+        discard;
+    }
+    vec4 accumulation = texelFetch(uAccumulation, pixel, 0);
+    vec3 average = accumulation.rgb / max(accumulation.a, 1e-5);
+    outColor = vec4(average, 1.0 - revealage);
+}
+";
+
+/// Offscreen buffers for weighted blended order-independent transparency. The transparent
+/// fragments are accumulated with a weight in one attachment and their coverage multiplied in
+/// another, which are then averaged over the opaque image by `composite`. It approximates the
+/// sorted result without sorting anything, even for intersecting objects.
+///
+/// The depth of the default framebuffer is copied to occlude the transparent fragments, so it
+/// must have a depth buffer and no multisampling (see `depth_format`).
+pub struct OitBuffer {
+    _fbo: u32,
+    _accumulation: u32,
+    _revealage: u32,
+    _depth: u32,
+    _depth_format: GLenum,
+    _vao: u32,
+    _width: u32,
+    _height: u32,
+    _shader: Shader,
+}
+
+impl OitBuffer {
+    /// Makes a buffer of the given size in pixels, which must be the size of the framebuffer.
+    /// It panics if the default framebuffer is not supported.
+    pub fn new(width: u32, height: u32) -> OitBuffer {
+        let shader = Shader::new()
+            .vertex_source(COMPOSITE_VERTEX)
+            .fragment_source(COMPOSITE_FRAGMENT);
+        let mut buffer = OitBuffer {
+            _fbo: 0,
+            _accumulation: 0,
+            _revealage: 0,
+            _depth: 0,
+            _depth_format: OitBuffer::depth_format(),
+            _vao: 0,
+            _width: 0,
+            _height: 0,
+            _shader: shader,
+        };
+        assert_gl_is_loaded();
+        unsafe {
+            // The composite triangle has no attributes, but core profiles need a VAO to draw
+            gl::GenVertexArrays(1, &mut buffer._vao);
+        }
+        buffer.resize(width, height);
+        return buffer;
+    }
+
+    /// Reallocates the attachments, which must follow the size of the framebuffer.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) == (self._width, self._height) {
+            return;
+        }
+        self.del_attachments();
+        self._width = width.max(1);
+        self._height = height.max(1);
+        let (width, height) = (self._width as i32, self._height as i32);
+        assert_gl_is_loaded();
+        unsafe {
+            gl::GenFramebuffers(1, &mut self._fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self._fbo);
+
+            let attachments = [
+                (&mut self._accumulation, gl::RGBA16F, gl::COLOR_ATTACHMENT0),
+                (&mut self._revealage, gl::R16F, gl::COLOR_ATTACHMENT1),
+            ];
+            for (texture, format, attachment) in attachments {
+                gl::GenTextures(1, texture);
+                gl::BindTexture(gl::TEXTURE_2D, *texture);
+                gl::TexStorage2D(gl::TEXTURE_2D, 1, format, width, height);
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, attachment, gl::TEXTURE_2D, *texture, 0);
+            }
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            let draw_buffers = [gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1];
+            gl::DrawBuffers(2, draw_buffers.as_ptr());
+
+            gl::GenRenderbuffers(1, &mut self._depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self._depth);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, self._depth_format, width, height);
+            let depth_attachment = match self._depth_format {
+                gl::DEPTH24_STENCIL8 | gl::DEPTH32F_STENCIL8 => gl::DEPTH_STENCIL_ATTACHMENT,
+                _ => gl::DEPTH_ATTACHMENT,
+            };
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                depth_attachment,
+                gl::RENDERBUFFER,
+                self._depth,
+            );
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                panic!("The OIT framebuffer is incomplete ({status:#x}).");
+            }
+        }
+        check_gl_error("OitBuffer::resize");
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        return (self._width, self._height);
+    }
+
+    /// Format of the depth buffer of the default framebuffer, which the copy of the depth needs
+    /// to match. It panics if the default framebuffer is multisampled or has no depth buffer, as
+    /// its depth can't be copied then.
+    pub fn depth_format() -> GLenum {
+        let mut samples = 0;
+        let mut depth_type = gl::NONE as i32;
+        let mut depth_bits = 0;
+        let mut component_type = gl::NONE as i32;
+        let mut stencil_type = gl::NONE as i32;
+        let mut stencil_bits = 0;
+        assert_gl_is_loaded();
+        unsafe {
+            gl::GetNamedFramebufferParameteriv(0, gl::SAMPLES, &mut samples);
+            let attachment = |name, parameter, value: &mut i32| {
+                gl::GetNamedFramebufferAttachmentParameteriv(0, name, parameter, value)
+            };
+            attachment(
+                gl::DEPTH,
+                gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+                &mut depth_type,
+            );
+            attachment(
+                gl::STENCIL,
+                gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+                &mut stencil_type,
+            );
+            // The sizes can only be queried for the buffers the framebuffer has
+            if depth_type as u32 != gl::NONE {
+                attachment(
+                    gl::DEPTH,
+                    gl::FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE,
+                    &mut depth_bits,
+                );
+                attachment(
+                    gl::DEPTH,
+                    gl::FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE,
+                    &mut component_type,
+                );
+            }
+            if stencil_type as u32 != gl::NONE {
+                attachment(
+                    gl::STENCIL,
+                    gl::FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE,
+                    &mut stencil_bits,
+                );
+            }
+        }
+        check_gl_error("OitBuffer::depth_format");
+
+        if samples > 0 {
+            panic!("Weighted blended transparency doesn't support a multisampled framebuffer.");
+        }
+        let float = component_type as u32 == gl::FLOAT;
+        return match (depth_bits, float, stencil_bits > 0) {
+            (24, false, true) => gl::DEPTH24_STENCIL8,
+            (32, true, true) => gl::DEPTH32F_STENCIL8,
+            (16, false, false) => gl::DEPTH_COMPONENT16,
+            (24, false, false) => gl::DEPTH_COMPONENT24,
+            (32, false, false) => gl::DEPTH_COMPONENT32,
+            (32, true, false) => gl::DEPTH_COMPONENT32F,
+            _ => panic!(
+                "Weighted blended transparency doesn't support {depth_bits} bits of depth and \
+                {stencil_bits} of stencil."
+            ),
+        };
+    }
+
+    /// Copies the depth of the default framebuffer, then binds the buffer and clears it. Like
+    /// `glClear`, it is restricted by the scissor and the color mask.
+    pub fn begin(&self) {
+        let (width, height) = (self._width as i32, self._height as i32);
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self._fbo);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                width,
+                height,
+                0,
+                0,
+                width,
+                height,
+                gl::DEPTH_BUFFER_BIT,
+                gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self._fbo);
+            gl::ClearBufferfv(gl::COLOR, 0, [0.0_f32; 4].as_ptr());
+            gl::ClearBufferfv(gl::COLOR, 1, [1.0_f32; 4].as_ptr());
+        }
+        check_gl_error("OitBuffer::begin");
+    }
+
+    /// Sets the blending of the attachments, which differs between them. It has to be called
+    /// after any other change of the blending, and the depth writes should be disabled.
+    pub fn set_blending(&self) {
+        assert_gl_is_loaded();
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendEquation(gl::FUNC_ADD);
+            gl::BlendFunci(0, gl::ONE, gl::ONE);
+            gl::BlendFunci(1, gl::ZERO, gl::ONE_MINUS_SRC_COLOR);
+        }
+        check_gl_error("OitBuffer::set_blending");
+    }
+
+    /// Goes back to the default framebuffer.
+    pub fn end(&self) {
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        check_gl_error("OitBuffer::end");
+    }
+
+    /// Draws the transparent fragments over the current viewport of the default framebuffer.
+    /// It expects the usual alpha blending and no depth test.
+    pub fn composite(&self) {
+        self._shader.use_program();
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindTextureUnit(0, self._accumulation);
+            gl::BindTextureUnit(1, self._revealage);
+            gl::BindVertexArray(self._vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            gl::BindVertexArray(0);
+            gl::BindTextureUnit(0, 0);
+            gl::BindTextureUnit(1, 0);
+        }
+        check_gl_error("OitBuffer::composite");
+    }
+
+    fn del_attachments(&self) {
+        if self._fbo == 0 {
+            return;
+        }
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DeleteFramebuffers(1, &self._fbo);
+            gl::DeleteTextures(1, &self._accumulation);
+            gl::DeleteTextures(1, &self._revealage);
+            gl::DeleteRenderbuffers(1, &self._depth);
+        }
+        check_gl_error("OitBuffer::del_attachments");
+    }
+
+    pub fn del(&self) {
+        self.del_attachments();
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DeleteVertexArrays(1, &self._vao);
+        }
+        check_gl_error("OitBuffer::del");
+    }
+}
+
+impl Drop for OitBuffer {
+    fn drop(&mut self) {
+        self.del();
+        self._shader.del();
+    }
+}
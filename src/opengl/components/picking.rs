@@ -0,0 +1,242 @@
+use crate::opengl::{assert_gl_is_loaded, check_gl_error, Shader};
+use gl;
+
+const PICKING_VERTEX: &str = "#version 460 core
+
+layout (location = 0) in vec3 aPos;
+
+layout (std140, binding = 0) uniform View
+{
+    mat4 view;
+};
+
+uniform mat4 uModel;
+
+void main() {
+    gl_Position = vec4(aPos, 1.0) * uModel * view;
+}
+";
+
+const PICKING_FRAGMENT: &str = "#version 460 core
+
+uniform uint uObjectId;
+
+out uvec2 outId;
+
+void main() {
+    outId = uvec2(uObjectId, uint(gl_PrimitiveID));
+}
+";
+
+/// What the GPU found under a pixel of a `PickingBuffer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GpuPick {
+    /// Index of the item in the renderer.
+    pub _item: usize,
+    /// Index of the primitive (the triangle for triangles) in the draw call of the item.
+    pub _primitive: u32,
+}
+
+/// An offscreen framebuffer in which every item is drawn with its index instead of its color,
+/// so that the object under a pixel can be read back. The items are drawn with the position in
+/// the layout 0, their model matrix in `uModel` and the camera bound to the uniform block 0, as
+/// for the default shaders.
+pub struct PickingBuffer {
+    _fbo: u32,
+    _ids: u32,
+    _depth: u32,
+    _width: u32,
+    _height: u32,
+    _shader: Shader,
+    _previous_viewport: [i32; 4],
+}
+
+impl PickingBuffer {
+    /// Makes a buffer of the given size in pixels, usually the size of the framebuffer.
+    pub fn new(width: u32, height: u32) -> PickingBuffer {
+        let shader = Shader::new()
+            .vertex_source(PICKING_VERTEX)
+            .fragment_source(PICKING_FRAGMENT);
+        let mut buffer = PickingBuffer {
+            _fbo: 0,
+            _ids: 0,
+            _depth: 0,
+            _width: 0,
+            _height: 0,
+            _shader: shader,
+            _previous_viewport: [0; 4],
+        };
+        buffer.resize(width, height);
+        return buffer;
+    }
+
+    /// Reallocates the attachments, which must follow the size of the framebuffer for the
+    /// cursor to land on the right pixel.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if (width, height) == (self._width, self._height) {
+            return;
+        }
+        self.del();
+        self._width = width.max(1);
+        self._height = height.max(1);
+        assert_gl_is_loaded();
+        unsafe {
+            gl::GenFramebuffers(1, &mut self._fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self._fbo);
+
+            gl::GenTextures(1, &mut self._ids);
+            gl::BindTexture(gl::TEXTURE_2D, self._ids);
+            gl::TexStorage2D(
+                gl::TEXTURE_2D,
+                1,
+                gl::RG32UI,
+                self._width as i32,
+                self._height as i32,
+            );
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                self._ids,
+                0,
+            );
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenRenderbuffers(1, &mut self._depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self._depth);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH_COMPONENT32F,
+                self._width as i32,
+                self._height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                self._depth,
+            );
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                panic!("The picking framebuffer is incomplete ({status:#x}).");
+            }
+        }
+        check_gl_error("PickingBuffer::resize");
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        return (self._width, self._height);
+    }
+
+    /// Binds the buffer and clears it, ready for the items to be drawn with `shader()`. The
+    /// viewport is saved, to be restored by `end`.
+    pub fn begin(&mut self) {
+        assert_gl_is_loaded();
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, self._previous_viewport.as_mut_ptr());
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self._fbo);
+            gl::Viewport(0, 0, self._width as i32, self._height as i32);
+        }
+        self.clear();
+        self._shader.use_program();
+        check_gl_error("PickingBuffer::begin");
+    }
+
+    /// Clears the ids and the depth of the bound buffer, within the scissor box if it is enabled.
+    pub fn clear(&self) {
+        assert_gl_is_loaded();
+        unsafe {
+            // 0 is left for the pixels without any item
+            gl::ClearBufferuiv(gl::COLOR, 0, [0_u32; 4].as_ptr());
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+        }
+        check_gl_error("PickingBuffer::clear");
+    }
+
+    /// Goes back to the default framebuffer.
+    pub fn end(&self) {
+        let [x, y, width, height] = self._previous_viewport;
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(x, y, width, height);
+        }
+        check_gl_error("PickingBuffer::end");
+    }
+
+    /// Program writing the ids, whose `uObjectId` uniform must be set to the index of the item
+    /// plus one before drawing it, and its `uModel` uniform to the model matrix of the item.
+    pub fn shader(&self) -> &Shader {
+        return &self._shader;
+    }
+
+    /// Reads what was drawn at a pixel, counted from the lower-left corner.
+    pub fn read(&self, x: u32, y: u32) -> Option<GpuPick> {
+        if x >= self._width || y >= self._height {
+            return None;
+        }
+        let mut pixel = [0_u32; 2];
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self._fbo);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::ReadPixels(
+                x as i32,
+                y as i32,
+                1,
+                1,
+                gl::RG_INTEGER,
+                gl::UNSIGNED_INT,
+                pixel.as_mut_ptr().cast(),
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+        check_gl_error("PickingBuffer::read");
+        if pixel[0] == 0 {
+            return None;
+        }
+        return Some(GpuPick {
+            _item: pixel[0] as usize - 1,
+            _primitive: pixel[1],
+        });
+    }
+
+    /// Reads what was drawn under the cursor, given in screen coordinates for a window of the
+    /// given size (see `Window::get_size`).
+    pub fn read_at_cursor(&self, cursor: (f64, f64), window_size: (u32, u32)) -> Option<GpuPick> {
+        if cursor.0 < 0.0 || cursor.1 < 0.0 {
+            return None;
+        }
+        let x = cursor.0 / window_size.0.max(1) as f64 * self._width as f64;
+        let y = cursor.1 / window_size.1.max(1) as f64 * self._height as f64;
+        // The pixels of the framebuffer go up, while the cursor goes down
+        let y = self._height as f64 - 1.0 - y.floor();
+        if y < 0.0 {
+            return None;
+        }
+        return self.read(x as u32, y as u32);
+    }
+
+    pub fn del(&self) {
+        if self._fbo == 0 {
+            return;
+        }
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DeleteFramebuffers(1, &self._fbo);
+            gl::DeleteTextures(1, &self._ids);
+            gl::DeleteRenderbuffers(1, &self._depth);
+        }
+        check_gl_error("PickingBuffer::del");
+    }
+}
+
+impl Drop for PickingBuffer {
+    fn drop(&mut self) {
+        self.del();
+        self._shader.del();
+    }
+}
@@ -1,33 +1,205 @@
-use crate::opengl::{assert_gl_is_loaded, Drawable, Renderer};
+use crate::opengl::components::{Camera, GpuPick, OitBuffer, PickingBuffer};
+use crate::opengl::{
+    assert_gl_is_loaded, check_gl_error, BindingCache, BlendFunction, BlendMode, Drawable,
+    RenderState, RenderStateCache, Renderer,
+};
+use crate::utils::math::geometry::{Frustum, Ray, RayHit};
+use crate::utils::math::linalg;
 use gl;
 use gl::types::*;
+use std::cell::{Cell, RefCell};
 
+/// Number of items drawn and skipped by the last call to `render`, counted once per viewport.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CullingStats {
+    pub _total: u32,
+    pub _visible: u32,
+    pub _culled: u32,
+}
+
+/// Work done by the last call to `render`, to see how well the draw calls are sorted and
+/// batched.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    pub _draw_calls: u32,
+    /// Number of render states applied, see `RenderStateCache`.
+    pub _state_changes: u32,
+    /// Number of programs, materials, textures and vertex arrays bound, see `BindingCache`.
+    pub _binding_changes: u32,
+}
+
+/// How the items with a blending state are drawn, after the opaque ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransparencyMode {
+    /// Drawn from back to front along the axis of the camera, without writing the depth. It is
+    /// exact as long as the items don't overlap in depth.
+    Sorted,
+    /// Drawn in any order with weighted blended order-independent transparency (see
+    /// `OitBuffer`). Their shaders have to write their color with `OIT_GLSL`.
+    WeightedBlended,
+}
+
+/// A camera drawing into a region of the framebuffer, for split-screen or picture-in-picture.
+pub struct Viewport {
+    pub _camera: Box<dyn Camera>,
+    /// Position and size of the region as fractions of the framebuffer, from its upper-left
+    /// corner like the cursor position.
+    pub _rect: [f32; 4],
+    /// Color the region is cleared to before drawing, along with its depth. The regions without
+    /// one are only cleared by `Renderer::clear`.
+    pub _clear_color: Option<[f32; 4]>,
+}
+
+impl Viewport {
+    /// A viewport covering the whole framebuffer.
+    pub fn new<C: Camera + 'static>(camera: C) -> Viewport {
+        return Viewport {
+            _camera: Box::new(camera),
+            _rect: [0.0, 0.0, 1.0, 1.0],
+            _clear_color: None,
+        };
+    }
+
+    pub fn rect(mut self, x: f32, y: f32, width: f32, height: f32) -> Viewport {
+        self._rect = [x, y, width, height];
+        return self;
+    }
+
+    pub fn clear_color(mut self, r: f32, g: f32, b: f32, a: f32) -> Viewport {
+        self._clear_color = Some([r, g, b, a]);
+        return self;
+    }
+
+    /// Region in pixels for a framebuffer of the given size, as given to `glViewport`, with the
+    /// origin in the lower-left corner.
+    pub fn pixel_rect(&self, size: (u32, u32)) -> [i32; 4] {
+        let (width, height) = (size.0 as f32, size.1 as f32);
+        let [x, y, w, h] = self._rect;
+        let left = (x * width).round() as i32;
+        let right = ((x + w) * width).round() as i32;
+        let top = (y * height).round() as i32;
+        let bottom = ((y + h) * height).round() as i32;
+        return [left, size.1 as i32 - bottom, right - left, bottom - top];
+    }
+
+    pub fn aspect_ratio(&self, size: (u32, u32)) -> f32 {
+        let [_, _, width, height] = self.pixel_rect(size);
+        return width as f32 / height.max(1) as f32;
+    }
+
+    /// Whether a point in screen coordinates, such as the cursor, is inside the region.
+    pub fn contains(&self, screen: (f64, f64), size: (u32, u32)) -> bool {
+        let (x, y) = (
+            screen.0 as f32 / size.0 as f32,
+            screen.1 as f32 / size.1 as f32,
+        );
+        let [left, top, width, height] = self._rect;
+        return x >= left && x < left + width && y >= top && y < top + height;
+    }
+}
+
+/// Renders a list of items with the cameras of its viewports. Without any camera, the items are
+/// drawn with whatever is bound to the camera uniform block.
 pub struct Renderer3D<'a> {
     _clear_color: [f32; 4],
-    _items: Vec<(&'a dyn Drawable, GLenum)>,
+    _items: Vec<(&'a dyn Drawable, GLenum, Option<RenderState>)>,
+    _viewports: Vec<Viewport>,
+    _size: (u32, u32),
+    _time: f32,
+    _culling: bool,
+    _frustum: Option<Frustum>,
+    _stats: Cell<CullingStats>,
+    _render_stats: Cell<RenderStats>,
+    _sorting: bool,
+    _bindings: RefCell<BindingCache>,
+    _default_state: RenderState,
+    _reverse_z: Cell<bool>,
+    _state_cache: RefCell<RenderStateCache>,
+    _transparency: TransparencyMode,
+    _oit_buffer: RefCell<Option<OitBuffer>>,
 }
 
 impl<'a> Renderer for Renderer3D<'a> {
     fn clear(&self) {
+        // The masks and the scissor apply to the clear as well
+        self.apply_state(&RenderState::new());
         assert_gl_is_loaded();
         unsafe {
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
     }
 
+    /// Draws the items once per viewport, after uploading the matrices of its camera and
+    /// restricting the drawing to its region. The default state is restored afterwards, and the
+    /// texture and the vertex array are unbound.
     fn render(&self) {
-        for i in 0..self._items.len() {
-            let drawable = self.get_item(i);
-            self.draw_mode(drawable.0, drawable.1);
+        let mut stats = CullingStats::default();
+        self._render_stats.set(RenderStats::default());
+        self._state_cache.borrow_mut().reset_changes();
+        self._bindings.borrow_mut().reset_changes();
+        if self._viewports.is_empty() {
+            self.render_items(None, self._frustum.as_ref(), None, &mut stats);
+            self.end_render(stats);
+            return;
         }
+
+        assert_gl_is_loaded();
+        for viewport in self._viewports.iter() {
+            let rect = viewport.pixel_rect(self._size);
+            let [x, y, width, height] = rect;
+            self.apply_state(&RenderState::new().scissor(Some(rect)));
+            unsafe {
+                gl::Viewport(x, y, width, height);
+                if let Some([r, g, b, a]) = viewport._clear_color {
+                    gl::ClearColor(r, g, b, a);
+                    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                    let [r, g, b, a] = self._clear_color;
+                    gl::ClearColor(r, g, b, a);
+                }
+            }
+            let camera = &viewport._camera;
+            // Every camera has its own buffer, so it is bound again to take the shared index
+            camera.get_ubo().bind_index(0);
+            camera.update_ubo();
+            camera.update_ubo_frame((width as u32, height as u32), self._time);
+            let frustum = camera.frustum();
+            self.render_items(
+                Some(camera.as_ref()),
+                Some(&frustum),
+                Some(rect),
+                &mut stats,
+            );
+        }
+        unsafe {
+            gl::Viewport(0, 0, self._size.0 as i32, self._size.1 as i32);
+        }
+        check_gl_error("Renderer3D::render");
+        self.end_render(stats);
     }
 }
 
 impl<'a> Renderer3D<'a> {
+    /// Makes a renderer whose size is the one of the current viewport, which the window sets to
+    /// cover its framebuffer. It must be made once the window is built, and be given the new size
+    /// through `resize` whenever the window is resized.
     pub fn new() -> Renderer3D<'a> {
         return Renderer3D {
             _clear_color: [0.0, 0.0, 0.0, 1.0],
             _items: vec![],
+            _viewports: vec![],
+            _size: current_viewport_size(),
+            _time: 0.0,
+            _culling: true,
+            _frustum: None,
+            _stats: Cell::new(CullingStats::default()),
+            _render_stats: Cell::new(RenderStats::default()),
+            _sorting: true,
+            _bindings: RefCell::new(BindingCache::new()),
+            _default_state: RenderState::new(),
+            _reverse_z: Cell::new(false),
+            _state_cache: RefCell::new(RenderStateCache::new()),
+            _transparency: TransparencyMode::Sorted,
+            _oit_buffer: RefCell::new(None),
         };
     }
 
@@ -36,7 +208,17 @@ impl<'a> Renderer3D<'a> {
     }
 
     pub fn add_item_with_mode(&mut self, drawable: &'a dyn Drawable, mode: GLenum) {
-        self._items.push((drawable, mode));
+        self._items.push((drawable, mode, None));
+    }
+
+    /// Adds an item drawn with the given state, whatever the state of its material.
+    pub fn add_item_with_state(
+        &mut self,
+        drawable: &'a dyn Drawable,
+        mode: GLenum,
+        state: &RenderState,
+    ) {
+        self._items.push((drawable, mode, Some(*state)));
     }
 
     pub fn clear_color(mut self, r: f32, g: f32, b: f32, a: f32) -> Renderer3D<'a> {
@@ -48,8 +230,431 @@ impl<'a> Renderer3D<'a> {
         return self;
     }
 
+    /// Gives the renderer a camera covering the whole framebuffer, replacing its viewports.
+    pub fn camera<C: Camera + 'static>(mut self, camera: C) -> Renderer3D<'a> {
+        self.set_camera(camera);
+        return self;
+    }
+
+    /// State of the items whose material doesn't have one.
+    pub fn default_state(mut self, state: &RenderState) -> Renderer3D<'a> {
+        self._default_state = *state;
+        return self;
+    }
+
+    pub fn set_default_state(&mut self, state: &RenderState) {
+        self._default_state = *state;
+    }
+
+    pub fn get_default_state(&self) -> RenderState {
+        return self._default_state;
+    }
+
+    /// Applies a state through the cache of the renderer, only changing what differs from the
+    /// last state applied. With a reversed depth, the depth function is reversed as well.
+    pub fn apply_state(&self, state: &RenderState) {
+        let state = match self._reverse_z.get() {
+            true => state.reversed_depth(),
+            false => *state,
+        };
+        self._state_cache.borrow_mut().apply(&state);
+    }
+
+    /// Makes the next state applied be set entirely. It has to be called after changing the
+    /// state without the renderer, for instance with raw OpenGL calls.
+    pub fn invalidate_state(&self) {
+        self._state_cache.borrow_mut().invalidate();
+    }
+
+    /// Size of the framebuffer, which the viewports are relative to. It defaults to the size of
+    /// the viewport when the renderer is made.
+    pub fn size(mut self, width: u32, height: u32) -> Renderer3D<'a> {
+        self.resize(width, height);
+        return self;
+    }
+
+    /// Skips the items whose bounds are outside the view. The viewports cull with the frustum
+    /// of their camera, and a renderer without cameras with the one given by `set_frustum`.
+    pub fn culling(mut self, enabled: bool) -> Renderer3D<'a> {
+        self._culling = enabled;
+        return self;
+    }
+
+    pub fn set_culling(&mut self, enabled: bool) {
+        self._culling = enabled;
+    }
+
+    pub fn set_camera<C: Camera + 'static>(&mut self, camera: C) {
+        self._viewports.clear();
+        self.add_viewport(Viewport::new(camera));
+    }
+
+    /// Adds a viewport drawn after the existing ones, returning its index.
+    pub fn add_viewport(&mut self, mut viewport: Viewport) -> usize {
+        let aspect = viewport.aspect_ratio(self._size);
+        viewport._camera.set_aspect_ratio(aspect);
+        self._viewports.push(viewport);
+        return self._viewports.len() - 1;
+    }
+
+    pub fn remove_viewport(&mut self, index: usize) -> Viewport {
+        return self._viewports.remove(index);
+    }
+
+    pub fn get_viewport(&self, index: usize) -> &Viewport {
+        return &self._viewports[index];
+    }
+
+    pub fn viewport_mut(&mut self, index: usize) -> &mut Viewport {
+        return &mut self._viewports[index];
+    }
+
+    pub fn viewport_count(&self) -> usize {
+        return self._viewports.len();
+    }
+
+    /// Camera of the first viewport.
+    pub fn get_camera(&self) -> Option<&dyn Camera> {
+        return self
+            ._viewports
+            .first()
+            .map(|viewport| viewport._camera.as_ref());
+    }
+
+    pub fn camera_mut(&mut self) -> Option<&mut (dyn Camera + 'static)> {
+        return self
+            ._viewports
+            .first_mut()
+            .map(|viewport| viewport._camera.as_mut());
+    }
+
+    /// Updates the size of the framebuffer and the aspect ratio of the cameras to match their
+    /// viewport. It should be called when the window is resized.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self._size = (width, height);
+        for viewport in self._viewports.iter_mut() {
+            let aspect = viewport.aspect_ratio(self._size);
+            viewport._camera.set_aspect_ratio(aspect);
+        }
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        return self._size;
+    }
+
+    /// Time in seconds given to the shaders through the camera uniform block.
+    pub fn set_time(&mut self, time: f32) {
+        self._time = time;
+    }
+
+    /// Index of the last viewport drawn under a point in screen coordinates, for a window of the
+    /// given size.
+    pub fn viewport_at(&self, screen: (f64, f64), window_size: (u32, u32)) -> Option<usize> {
+        return self
+            ._viewports
+            .iter()
+            .rposition(|viewport| viewport.contains(screen, window_size));
+    }
+
+    /// Ray going through a point in screen coordinates from the camera of the viewport under
+    /// it, to be given to `pick`.
+    pub fn ray_at(&self, screen: (f64, f64), window_size: (u32, u32)) -> Option<Ray> {
+        let viewport = &self._viewports[self.viewport_at(screen, window_size)?];
+        let (width, height) = (window_size.0 as f64, window_size.1 as f64);
+        let [x, y, w, h] = viewport._rect.map(|v| v as f64);
+        let local = (screen.0 - x * width, screen.1 - y * height);
+        let local_size = ((w * width).round() as u32, (h * height).round() as u32);
+        return viewport._camera.ray(local, local_size);
+    }
+
+    /// Culls the items against the view of a camera when the renderer has no camera of its own.
+    /// It has to be called again whenever the camera moves.
+    pub fn set_camera_frustum<C: Camera + ?Sized>(&mut self, camera: &C) {
+        self._frustum = Some(camera.frustum());
+    }
+
+    /// Sets the frustum used for culling without cameras, or disables it with `None`.
+    pub fn set_frustum(&mut self, frustum: Option<Frustum>) {
+        self._frustum = frustum;
+    }
+
+    /// Frustum culling the items, which is the one of the first camera if there is one.
+    pub fn get_frustum(&self) -> Option<Frustum> {
+        return match self.get_camera() {
+            Some(camera) => Some(camera.frustum()),
+            None => self._frustum,
+        };
+    }
+
+    /// How the transparent items are drawn. Weighted blended transparency checks right away that
+    /// the default framebuffer is supported (see `OitBuffer::depth_format`), and panics if not.
+    pub fn transparency(mut self, mode: TransparencyMode) -> Renderer3D<'a> {
+        self.set_transparency(mode);
+        return self;
+    }
+
+    pub fn set_transparency(&mut self, mode: TransparencyMode) {
+        if mode == TransparencyMode::WeightedBlended {
+            OitBuffer::depth_format();
+        }
+        self._transparency = mode;
+    }
+
+    pub fn get_transparency(&self) -> TransparencyMode {
+        return self._transparency;
+    }
+
+    /// Statistics of the last frame rendered.
+    pub fn get_stats(&self) -> CullingStats {
+        return self._stats.get();
+    }
+
+    /// Draw calls and state changes of the last frame rendered.
+    pub fn get_render_stats(&self) -> RenderStats {
+        return self._render_stats.get();
+    }
+
+    /// Whether the opaque items are reordered to share their state and bindings, which is on by
+    /// default. Otherwise they are drawn in the order they were added.
+    pub fn sorting(mut self, enabled: bool) -> Renderer3D<'a> {
+        self._sorting = enabled;
+        return self;
+    }
+
+    pub fn set_sorting(&mut self, enabled: bool) {
+        self._sorting = enabled;
+    }
+
+    /// Whether an item would be drawn by `render` in the first viewport, given its bounds.
+    pub fn is_visible(&self, drawable: &dyn Drawable) -> bool {
+        return self.is_visible_in(drawable, self.get_frustum().as_ref());
+    }
+
+    /// Draws the ids of the items into the bound picking buffer, within the region of a
+    /// viewport and with its camera, or over the whole buffer without one.
+    fn draw_ids(&self, buffer: &PickingBuffer, viewport: Option<&Viewport>) {
+        let frustum = match viewport {
+            Some(viewport) => {
+                let rect = viewport.pixel_rect(buffer.get_size());
+                let [x, y, width, height] = rect;
+                self.apply_state(&RenderState::new().scissor(Some(rect)));
+                assert_gl_is_loaded();
+                unsafe {
+                    gl::Viewport(x, y, width, height);
+                }
+                buffer.clear();
+                let camera = &viewport._camera;
+                camera.get_ubo().bind_index(0);
+                camera.update_ubo();
+                camera.update_ubo_frame((width as u32, height as u32), self._time);
+                Some(camera.frustum())
+            }
+            None => self._frustum,
+        };
+        for (i, (drawable, mode, _)) in self._items.iter().enumerate() {
+            if !self.is_visible_in(*drawable, frustum.as_ref()) {
+                continue;
+            }
+            buffer.shader().set_1ui("uObjectId", i as u32 + 1);
+            let model = drawable.model_matrix().unwrap_or_else(linalg::eye4);
+            buffer.shader().set_matrix4fv("uModel", &model);
+            drawable.draw_geometry(*mode);
+        }
+    }
+
+    fn is_visible_in(&self, drawable: &dyn Drawable, frustum: Option<&Frustum>) -> bool {
+        if !self._culling {
+            return true;
+        }
+        return match (frustum, drawable.bounds()) {
+            (Some(frustum), Some(bounds)) => frustum.intersects_aabb(&bounds),
+            _ => true,
+        };
+    }
+
+    fn end_render(&self, stats: CullingStats) {
+        self.apply_state(&self._default_state);
+        let mut bindings = self._bindings.borrow_mut();
+        bindings.unbind();
+        self._stats.set(stats);
+        self._render_stats.set(RenderStats {
+            _draw_calls: self._render_stats.get()._draw_calls,
+            _state_changes: self._state_cache.borrow().get_changes(),
+            _binding_changes: bindings.get_changes(),
+        });
+    }
+
+    /// Draws the opaque items sorted by state and bindings, then the transparent ones as set by
+    /// the transparency mode. Without a camera, the transparent items can't be sorted and keep
+    /// their order.
+    fn render_items(
+        &self,
+        camera: Option<&dyn Camera>,
+        frustum: Option<&Frustum>,
+        scissor: Option<[i32; 4]>,
+        stats: &mut CullingStats,
+    ) {
+        let mut opaque: Vec<(usize, RenderState)> = vec![];
+        let mut transparent: Vec<(usize, RenderState)> = vec![];
+        for i in 0..self._items.len() {
+            let (drawable, _) = self.get_item(i);
+            stats._total += 1;
+            if !self.is_visible_in(drawable, frustum) {
+                stats._culled += 1;
+                continue;
+            }
+            stats._visible += 1;
+            let mut state = self.get_item_state(i);
+            // Items without a region of their own are restricted to the viewport
+            state._scissor = state._scissor.or(scissor);
+            if state._blend != BlendMode::Opaque {
+                transparent.push((i, state));
+                continue;
+            }
+            opaque.push((i, state));
+        }
+        if self._sorting {
+            self.sort_by_bindings(&mut opaque);
+        }
+        for (i, state) in opaque {
+            let (drawable, mode) = self.get_item(i);
+            self.apply_state(&state);
+            self.draw_item(drawable, mode);
+        }
+        if transparent.is_empty() {
+            return;
+        }
+
+        match self._transparency {
+            TransparencyMode::Sorted => {
+                if let Some(camera) = camera {
+                    self.sort_back_to_front(camera, &mut transparent);
+                }
+                for (i, state) in transparent {
+                    let (drawable, mode) = self.get_item(i);
+                    self.apply_state(&state.depth_write(false));
+                    self.draw_item(drawable, mode);
+                }
+            }
+            TransparencyMode::WeightedBlended => self.render_oit(&transparent, scissor),
+        }
+    }
+
+    /// Groups the items drawn with the same state, in the order the states first appear, then
+    /// sorts each group by `DrawKey`. The items that don't test and write the depth depend on
+    /// what is drawn before them, so none is moved across them.
+    fn sort_by_bindings(&self, items: &mut [(usize, RenderState)]) {
+        let mut states: Vec<RenderState> = vec![];
+        let mut run = 0;
+        let mut keyed = Vec::with_capacity(items.len());
+        for (i, state) in items.iter() {
+            let ordered = !state._depth_test || !state._depth_write;
+            if ordered {
+                run += 1;
+            }
+            let group = match states.iter().position(|s| s == state) {
+                Some(group) => group,
+                None => {
+                    states.push(*state);
+                    states.len() - 1
+                }
+            };
+            let key = (run, group, self._items[*i].0.draw_key());
+            keyed.push((key, (*i, *state)));
+            if ordered {
+                run += 1;
+            }
+        }
+        // The sort is stable, so items with the same key keep their order
+        keyed.sort_by_key(|(key, _)| *key);
+        for (item, (_, sorted)) in items.iter_mut().zip(keyed) {
+            *item = sorted;
+        }
+    }
+
+    /// Draws an item with the bindings left by the previous one, counting the draw call.
+    fn draw_item(&self, drawable: &dyn Drawable, mode: GLenum) {
+        drawable.draw_cached(mode, &mut self._bindings.borrow_mut());
+        let mut stats = self._render_stats.get();
+        stats._draw_calls += 1;
+        self._render_stats.set(stats);
+    }
+
+    /// Sorts items by decreasing depth of the center of their bounds, the items without bounds
+    /// being drawn first.
+    fn sort_back_to_front(&self, camera: &dyn Camera, items: &mut [(usize, RenderState)]) {
+        let position = camera.get_position();
+        // The direction of the cameras points backwards
+        let backward = camera.get_direction();
+        let depth = |i: usize| -> f32 {
+            return match self._items[i].0.bounds() {
+                Some(bounds) if !bounds.is_empty() => {
+                    -linalg::dot_v3(&linalg::sub_v3(&bounds.center(), &position), &backward)
+                }
+                _ => f32::INFINITY,
+            };
+        };
+        let mut depths: Vec<(f32, (usize, RenderState))> =
+            items.iter().map(|item| (depth(item.0), *item)).collect();
+        depths.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (item, (_, sorted)) in items.iter_mut().zip(depths) {
+            *item = sorted;
+        }
+    }
+
+    fn render_oit(&self, items: &[(usize, RenderState)], scissor: Option<[i32; 4]>) {
+        let mut oit_buffer = self._oit_buffer.borrow_mut();
+        let buffer = oit_buffer.get_or_insert_with(|| OitBuffer::new(self._size.0, self._size.1));
+        buffer.resize(self._size.0, self._size.1);
+
+        // The clear of the buffer follows the color mask and the scissor
+        self.apply_state(&RenderState::new().scissor(scissor));
+        buffer.begin();
+        let accumulate = BlendMode::Custom(BlendFunction::new(gl::ONE, gl::ONE));
+        for (i, state) in items {
+            let (drawable, mode) = self.get_item(*i);
+            self.apply_state(&state.blend(accumulate).depth_write(false));
+            buffer.set_blending();
+            if let Some(shader) = drawable.shader() {
+                self._bindings.borrow_mut().use_program(shader.get_id());
+                shader.set_1i("uOitPass", 1);
+                shader.set_1i("uOitReverseZ", self._reverse_z.get() as i32);
+            }
+            self.draw_item(drawable, mode);
+            if let Some(shader) = drawable.shader() {
+                shader.set_1i("uOitPass", 0);
+            }
+        }
+        buffer.end();
+        // The blending of the second attachment is unknown to the cache
+        self.invalidate_state();
+
+        let composite = RenderState::new()
+            .blend(BlendMode::Alpha)
+            .depth_test(false)
+            .depth_write(false)
+            .scissor(scissor);
+        self.apply_state(&composite);
+        buffer.composite();
+        // The composite uses its own program and vertex array
+        self._bindings.borrow_mut().invalidate();
+        let mut stats = self._render_stats.get();
+        stats._draw_calls += 1;
+        self._render_stats.set(stats);
+    }
+
     pub fn get_item(&self, index: usize) -> (&'a dyn Drawable, GLenum) {
-        return self._items[index];
+        let (drawable, mode, _) = self._items[index];
+        return (drawable, mode);
+    }
+
+    /// State an item is drawn with: its own, or else the one of its material, or else the
+    /// default state of the renderer.
+    pub fn get_item_state(&self, index: usize) -> RenderState {
+        let (drawable, _, state) = self._items[index];
+        return state
+            .or_else(|| drawable.render_state())
+            .unwrap_or(self._default_state);
     }
 
     /// Draws the given shape using triangles.
@@ -63,10 +668,102 @@ impl<'a> Renderer3D<'a> {
         // drawable.draw();
     }
 
+    /// Draws the given shape with a state, which stays applied afterwards.
+    pub fn draw_with_state(&self, drawable: &dyn Drawable, mode: GLenum, state: &RenderState) {
+        self.apply_state(state);
+        drawable.draw_with_mode(mode);
+    }
+
+    /// Finds the closest item hit by a ray in world space (see `Camera::ray`), testing its
+    /// triangles on the CPU. Returns the index of the item along with the hit.
+    pub fn pick(&self, ray: &Ray) -> Option<(usize, RayHit)> {
+        let mut closest: Option<(usize, RayHit)> = None;
+        for (i, (drawable, _, _)) in self._items.iter().enumerate() {
+            if let Some(hit) = drawable.intersect_ray(ray) {
+                if closest.is_none_or(|(_, closest)| hit._distance < closest._distance) {
+                    closest = Some((i, hit));
+                }
+            }
+        }
+        return closest;
+    }
+
+    /// Draws the items into a picking buffer, each with its index instead of its color, as seen
+    /// by the camera of every viewport within its region of the buffer. Where viewports overlap,
+    /// the last one is kept, as for `viewport_at`.
+    pub fn render_ids(&self, buffer: &mut PickingBuffer) {
+        // Every item is opaque in the picking buffer, whatever its state
+        self.apply_state(&RenderState::new());
+        buffer.begin();
+        if self._viewports.is_empty() {
+            self.draw_ids(buffer, None);
+        }
+        for viewport in self._viewports.iter() {
+            self.draw_ids(buffer, Some(viewport));
+        }
+        self.apply_state(&RenderState::new());
+        buffer.end();
+    }
+
+    /// Finds the item under the cursor on the GPU, by rendering the ids of the items seen by the
+    /// viewport under it and reading back the pixel under it. The cursor is in screen
+    /// coordinates, for a window of the given size.
+    pub fn pick_gpu(
+        &self,
+        buffer: &mut PickingBuffer,
+        cursor: (f64, f64),
+        window_size: (u32, u32),
+    ) -> Option<GpuPick> {
+        let viewport = match self.viewport_at(cursor, window_size) {
+            Some(index) => Some(&self._viewports[index]),
+            None if self._viewports.is_empty() => None,
+            None => return None,
+        };
+        self.apply_state(&RenderState::new());
+        buffer.begin();
+        self.draw_ids(buffer, viewport);
+        self.apply_state(&RenderState::new());
+        buffer.end();
+        return buffer.read_at_cursor(cursor, window_size);
+    }
+
     pub fn set_polygon_mode(&self, face: GLenum, mode: GLenum) {
         assert_gl_is_loaded();
         unsafe {
             gl::PolygonMode(face, mode);
         }
     }
+
+    /// Configures the depth test for cameras with a reversed depth (see
+    /// `PerspectiveCamera::reverse_z`): the clip range becomes [0, 1], the depth is cleared to 0
+    /// and the closest fragments are the ones with the greatest depth. It needs OpenGL 4.5, and
+    /// is most useful with a floating point depth buffer. The depth functions of the states
+    /// applied by the renderer are reversed to match.
+    pub fn set_reverse_z(&self, enabled: bool) {
+        self._reverse_z.set(enabled);
+        self.invalidate_state();
+        assert_gl_is_loaded();
+        unsafe {
+            if enabled {
+                gl::ClipControl(gl::LOWER_LEFT, gl::ZERO_TO_ONE);
+                gl::ClearDepth(0.0);
+                gl::DepthFunc(gl::GREATER);
+            } else {
+                gl::ClipControl(gl::LOWER_LEFT, gl::NEGATIVE_ONE_TO_ONE);
+                gl::ClearDepth(1.0);
+                gl::DepthFunc(gl::LESS);
+            }
+        }
+        check_gl_error("Renderer3D::set_reverse_z");
+    }
+}
+
+/// Size of the current OpenGL viewport, or 1 by 1 if it is empty.
+fn current_viewport_size() -> (u32, u32) {
+    assert_gl_is_loaded();
+    let mut viewport = [0; 4];
+    unsafe {
+        gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+    }
+    return (viewport[2].max(1) as u32, viewport[3].max(1) as u32);
 }
@@ -2,9 +2,24 @@ use crate::opengl::{
     assert_gl_is_loaded,
     buffers::{EBO, VAO, VBO},
     components::{Material, Texture2D},
-    Drawable,
+    BindingCache, Bounded, DrawKey, Drawable, RenderState, Shader, Transform, Transformable,
 };
+use crate::utils::math::geometry::{Aabb, BoundingSphere, Obb, Ray, RayHit};
+use crate::utils::math::linalg;
+use crate::utils::mesh::{Mesh, VertexAttribute};
+use crate::utils::types::{V3, V4};
 use gl::types::*;
+use std::mem::size_of;
+
+/// Vertex types made of `f32` values alone, whose slices can be read as floats on the CPU.
+///
+/// # Safety
+///
+/// The type must only contain `f32` values, without any padding.
+pub unsafe trait FloatVertex: Copy {}
+
+unsafe impl FloatVertex for f32 {}
+unsafe impl<const N: usize> FloatVertex for [f32; N] {}
 
 /// Container for a drawable shape
 pub struct Shape<'a, T> {
@@ -18,12 +33,18 @@ pub struct Shape<'a, T> {
     pub _count: u32,
     pub _usage: GLenum,
     pub _vert_sizes: &'a [u32],
+    pub _stride: u32,
+    pub _attributes: &'a [VertexAttribute],
     pub _draw_mode: GLenum,
+    pub _transform: Transform,
+    /// Bounding box of the vertices before the transform, computed by `build`.
+    _local_bounds: Aabb,
 }
 
-impl<'a, T> Drawable for Shape<'a, T> {
+impl<'a, T: FloatVertex> Drawable for Shape<'a, T> {
     fn draw_with_mode(&self, mode: GLenum) {
         self.use_material();
+        self.set_model_matrix();
         self.bind_vao();
         self.bind_ebo();
         self.bind_texture();
@@ -43,6 +64,7 @@ impl<'a, T> Drawable for Shape<'a, T> {
 
     fn draw(&self) {
         self.use_material();
+        self.set_model_matrix();
         self.bind_vao();
         self.bind_ebo();
         self.bind_texture();
@@ -59,13 +81,133 @@ impl<'a, T> Drawable for Shape<'a, T> {
         self.unbind_ebo();
         self.unbind_texture();
     }
+
+    fn draw_geometry(&self, mode: GLenum) {
+        self.bind_vao();
+        self.bind_ebo();
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DrawElements(
+                mode,
+                self._count.try_into().unwrap(),
+                gl::UNSIGNED_INT,
+                0 as *const _,
+            );
+        }
+        self.unbind_vao();
+        self.unbind_ebo();
+    }
+
+    fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let positions = self.world_positions();
+        ray.intersect_aabb(&Aabb::from_points(&positions))?;
+
+        let mut closest: Option<RayHit> = None;
+        for (i, triangle) in self.triangles().iter().enumerate() {
+            if triangle
+                .iter()
+                .any(|index| *index as usize >= positions.len())
+            {
+                continue;
+            }
+            let [a, b, c] = triangle.map(|index| positions[index as usize]);
+            let (distance, u, v) = match ray.intersect_triangle(&a, &b, &c) {
+                Some(hit) => hit,
+                None => continue,
+            };
+            if closest.is_some_and(|hit| hit._distance <= distance) {
+                continue;
+            }
+            let barycentric = [1.0 - u - v, u, v];
+            let mut nearest = 0;
+            for k in 1..3 {
+                if barycentric[k] > barycentric[nearest] {
+                    nearest = k;
+                }
+            }
+            closest = Some(RayHit {
+                _distance: distance,
+                _point: ray.at(distance),
+                _triangle: i,
+                _vertex: triangle[nearest],
+                _barycentric: barycentric,
+            });
+        }
+        return closest;
+    }
+
+    fn model_matrix(&self) -> Option<[V4; 4]> {
+        return Some(self._transform.mat4());
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        // Only the corners of the box kept by `build` are transformed, instead of every vertex,
+        // which is a bit looser for a rotated shape. Shapes whose positions can't be read (such
+        // as a `Shape<f32>` without a stride) have an empty box, and are always drawn
+        if self._local_bounds.is_empty() {
+            return None;
+        }
+        return Some(self._local_bounds.transform(&self._transform.mat4()));
+    }
+
+    fn render_state(&self) -> Option<RenderState> {
+        return self._material._render_state;
+    }
+
+    fn shader(&self) -> Option<Shader> {
+        return Some(self._material._shader);
+    }
+
+    fn draw_key(&self) -> Option<DrawKey> {
+        return Some(DrawKey {
+            _shader: self._material._shader.get_id(),
+            _material: self._material.key(),
+            _texture: self._texture.get_id(),
+            _vao: self._vao.get_id(),
+        });
+    }
+
+    fn draw_cached(&self, mode: GLenum, bindings: &mut BindingCache) {
+        self._material.use_program_cached(bindings);
+        self.set_model_matrix();
+        bindings.bind_texture(self._texture.get_id());
+        // The element buffer is part of the state of the vertex array
+        bindings.bind_vao(self._vao.get_id());
+        assert_gl_is_loaded();
+        unsafe {
+            gl::DrawElements(
+                mode,
+                self._count.try_into().unwrap(),
+                gl::UNSIGNED_INT,
+                0 as *const _,
+            );
+        }
+    }
 }
 
-// impl<'a> traits::Transformable for Shape<'a> {
-//     fn apply_transform(&self, transform: &Transform) -> Self {
-//
-//     }
-// }
+impl<'a, T> Transformable for Shape<'a, T> {
+    fn get_trans(&self) -> Transform {
+        return self._transform;
+    }
+
+    fn trans(&mut self, transform: &Transform) {
+        self._transform.add(transform);
+    }
+}
+
+impl<'a, T: FloatVertex> Bounded for Shape<'a, T> {
+    fn bounding_box(&self) -> Aabb {
+        return Aabb::from_points(&self.world_positions());
+    }
+
+    fn oriented_box(&self) -> Obb {
+        return Obb::from_aabb(&self.local_bounding_box(), &self._transform.mat4());
+    }
+
+    fn bounding_sphere(&self) -> BoundingSphere {
+        return BoundingSphere::from_points(&self.world_positions());
+    }
+}
 
 impl<'a, T> Drop for Shape<'a, T> {
     fn drop(&mut self) {
@@ -86,7 +228,11 @@ impl<'a, T> Shape<'a, T> {
             _count: 0,
             _usage: gl::STATIC_DRAW,
             _vert_sizes: &[3, 3, 2],
+            _stride: 0,
+            _attributes: &[],
             _draw_mode: gl::TRIANGLES,
+            _transform: Transform::new(),
+            _local_bounds: Aabb::empty(),
         };
     }
 
@@ -131,16 +277,42 @@ impl<'a, T> Shape<'a, T> {
         return self;
     }
 
+    /// Sets the size in bytes of each vertex. By default it is the size of `T`.
+    pub fn stride(mut self, stride: u32) -> Shape<'a, T> {
+        self._stride = stride;
+        return self;
+    }
+
+    /// Declares what each layout of the vertices holds, indexed by layout.
+    pub fn attributes(mut self, attributes: &'a [VertexAttribute]) -> Shape<'a, T> {
+        self._attributes = attributes;
+        return self;
+    }
+
     pub fn draw_mode(mut self, mode: GLenum) -> Shape<'a, T> {
         self._draw_mode = mode;
         return self;
     }
 
-    pub fn build(mut self) -> Shape<'a, T> {
-        let vao = VAO::new()
-            .stride_from_type::<T>()
-            .sizes(self._vert_sizes)
-            .build();
+    /// Places the shape in the world. It is given to the shader as `uModel` when drawing (see
+    /// `set_model_matrix`), and taken into account by the bounding volumes and by picking.
+    pub fn transform(mut self, transform: &Transform) -> Shape<'a, T> {
+        self._transform = *transform;
+        return self;
+    }
+
+    /// Uploads the vertices and indices. The vertices must be made of floats, so that their
+    /// bounding box can be kept for culling.
+    pub fn build(mut self) -> Shape<'a, T>
+    where
+        T: FloatVertex,
+    {
+        let vao = if self._stride == 0 {
+            VAO::new().stride_from_type::<T>()
+        } else {
+            VAO::new().stride(self._stride)
+        };
+        let vao = vao.sizes(self._vert_sizes).build();
         vao.bind();
         let vbo = VBO::new()
             .vertices(self._vertices)
@@ -161,6 +333,7 @@ impl<'a, T> Shape<'a, T> {
         ebo.unbind();
         self._vao = vao;
         self._ebo = ebo;
+        self._local_bounds = Aabb::from_points(&self.positions());
 
         return self;
     }
@@ -193,10 +366,180 @@ impl<'a, T> Shape<'a, T> {
         self._texture.unbind();
     }
 
+    /// Gives the transform of the shape to its shader, as the `mat4 uModel` uniform by which the
+    /// positions are multiplied before the view: `vec4(aPos, 1.0) * uModel * view`.
+    pub fn set_model_matrix(&self) {
+        self._material
+            ._shader
+            .set_matrix4fv("uModel", &self._transform.mat4());
+    }
+
+    /// Copies the CPU-side vertices and indices into a mesh, splitting each vertex according to
+    /// the declared attributes. Layouts without a declared attribute are treated as the position
+    /// for the first one and as custom attributes for the rest. Strips and fans are turned into
+    /// triangle lists, and any other primitive leaves the mesh without indices.
+    pub fn to_mesh(&self) -> Mesh
+    where
+        T: FloatVertex,
+    {
+        let (floats, floats_per_vertex) = self.floats();
+        let total_floats = floats.len();
+
+        let mut mesh = Mesh::new();
+        let mut offsets = vec![];
+        let mut offset = 0;
+        for (layout, size) in self._vert_sizes.iter().enumerate() {
+            if offset + *size as usize > floats_per_vertex {
+                break;
+            }
+            let attribute = match self._attributes.get(layout) {
+                Some(a) => a.clone(),
+                None if layout == 0 => VertexAttribute::Position,
+                None => VertexAttribute::Custom(format!("attribute{layout}")),
+            };
+            mesh = mesh.attribute(attribute, *size);
+            offsets.push(offset);
+            offset += *size as usize;
+        }
+
+        let mut vertex = Vec::with_capacity(mesh.vertex_size());
+        for v in 0..total_floats / floats_per_vertex.max(1) {
+            vertex.clear();
+            for (i, size) in mesh._vert_sizes.iter().enumerate() {
+                let start = v * floats_per_vertex + offsets[i];
+                vertex.extend_from_slice(&floats[start..start + *size as usize]);
+            }
+            mesh.push_vertex(&vertex);
+        }
+
+        mesh._indices = self.triangles().concat();
+        return mesh;
+    }
+
+    /// Positions of the vertices, read from the layout declared as `VertexAttribute::Position`,
+    /// or from the first one.
+    pub fn positions(&self) -> Vec<V3>
+    where
+        T: FloatVertex,
+    {
+        let (floats, floats_per_vertex) = self.floats();
+        let offset = self
+            .attribute_offset(&VertexAttribute::Position)
+            .unwrap_or(0);
+        if floats_per_vertex < offset + 3 {
+            return vec![];
+        }
+        return floats
+            .chunks_exact(floats_per_vertex)
+            .map(|vertex| [vertex[offset], vertex[offset + 1], vertex[offset + 2]])
+            .collect();
+    }
+
+    /// Offset in floats of an attribute in a vertex, if a layout is declared as this attribute.
+    /// The position is read from the first layout when none is declared as such.
+    pub fn attribute_offset(&self, attribute: &VertexAttribute) -> Option<usize> {
+        let layout = match self._attributes.iter().position(|a| a == attribute) {
+            Some(layout) => layout,
+            None if *attribute == VertexAttribute::Position => 0,
+            None => return None,
+        };
+        return Some(self._vert_sizes.iter().take(layout).sum::<u32>() as usize);
+    }
+
+    /// Indices of the drawn triangles, in the order in which OpenGL numbers them. Strips and fans
+    /// are turned into lists, and other primitives have no triangles.
+    pub fn triangles(&self) -> Vec<[u32; 3]> {
+        let indices = &self._indices[..(self._count as usize).min(self._indices.len())];
+        return match self._draw_mode {
+            gl::TRIANGLES => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            gl::TRIANGLE_STRIP => (2..indices.len())
+                .map(|i| {
+                    // Every other triangle of a strip is wound the other way
+                    if i % 2 == 0 {
+                        [indices[i - 2], indices[i - 1], indices[i]]
+                    } else {
+                        [indices[i - 1], indices[i - 2], indices[i]]
+                    }
+                })
+                .collect(),
+            gl::TRIANGLE_FAN => (2..indices.len())
+                .map(|i| [indices[0], indices[i - 1], indices[i]])
+                .collect(),
+            _ => vec![],
+        };
+    }
+
+    /// Positions of the vertices in world space, once placed by the transform of the shape.
+    pub fn world_positions(&self) -> Vec<V3>
+    where
+        T: FloatVertex,
+    {
+        let matrix = self._transform.mat4();
+        return self
+            .positions()
+            .iter()
+            .map(|position| linalg::transform_point(&matrix, position))
+            .collect();
+    }
+
+    /// Bounding box of the vertices, before the transform of the shape. It is computed once by
+    /// `build`.
+    pub fn local_bounding_box(&self) -> Aabb {
+        return self._local_bounds;
+    }
+
+    /// Whether the shape can be merged into a `ShapeBatch`: its vertices must be static and its
+    /// primitives a list.
+    pub fn is_batchable(&self) -> bool {
+        return self._usage == gl::STATIC_DRAW
+            && [gl::TRIANGLES, gl::LINES, gl::POINTS].contains(&self._draw_mode);
+    }
+
+    /// Whether both shapes can be merged into the same `ShapeBatch`, which draws them with the
+    /// same material, texture and vertex layout.
+    pub fn can_batch_with(&self, other: &Shape<'_, T>) -> bool
+    where
+        T: FloatVertex,
+    {
+        return self.is_batchable()
+            && other.is_batchable()
+            && self._material._shader.get_id() == other._material._shader.get_id()
+            && self._material._properties == other._material._properties
+            && self._material._render_state == other._material._render_state
+            && self._texture.get_id() == other._texture.get_id()
+            && self._draw_mode == other._draw_mode
+            && self.floats().1 == other.floats().1
+            && self._vert_sizes == other._vert_sizes
+            && self._layouts == other._layouts;
+    }
+
+    /// The vertices as floats, along with the number of floats per vertex.
+    pub fn floats(&self) -> (&[f32], usize)
+    where
+        T: FloatVertex,
+    {
+        let stride = if self._stride == 0 {
+            size_of::<T>()
+        } else {
+            self._stride as usize
+        };
+        let floats_per_vertex = stride / size_of::<f32>();
+        let total_floats = self._vertices.len() * size_of::<T>() / size_of::<f32>();
+        // Vertex types are made of floats alone, so the slice can be seen as one of floats
+        let floats: &[f32] = unsafe {
+            std::slice::from_raw_parts(self._vertices.as_ptr() as *const f32, total_floats)
+        };
+        return (floats, floats_per_vertex);
+    }
+
+    /// Deletes the buffers of the shape. The material is copied between shapes, so its program
+    /// is left to be deleted by whoever created it, once nothing draws with it.
     pub fn del(&self) {
         self._vao.del();
         self._ebo.del();
-        self._material.del();
     }
 
     ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -207,10 +550,21 @@ impl<'a, T> Shape<'a, T> {
         return Shape::new().vertices(vertices).indices(&[0, 1, 2]);
     }
 
+    /// Makes a quad out of two triangles, with the vertices given in counter-clockwise order.
     pub fn quad(vertices: &'a [T]) -> Shape<'a, T> {
+        return Shape::new().vertices(vertices).indices(&[0, 1, 2, 0, 2, 3]);
+    }
+}
+
+impl<'a> Shape<'a, f32> {
+    /// Makes a shape that uses the vertices, indices and layout of the given mesh.
+    pub fn from_mesh(mesh: &'a Mesh) -> Shape<'a, f32> {
         return Shape::new()
-            .vertices(vertices)
-            .indices(&[0, 1, 2, 3])
-            .draw_mode(gl::QUADS);
+            .vertices(&mesh._vertices)
+            .indices(&mesh._indices)
+            .layouts(&mesh._layouts)
+            .vert_sizes(&mesh._vert_sizes)
+            .attributes(&mesh._attributes)
+            .stride(mesh.stride());
     }
 }
@@ -1,5 +1,5 @@
-use crate::opengl::assert_gl_is_loaded;
-use crate::utils::images::load;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
+use crate::utils::images::{load, load_from_memory};
 use gl;
 
 #[derive(Copy, Clone)]
@@ -16,10 +16,24 @@ impl Texture2D {
         return Texture2D::new().load_from_path(path);
     }
 
-    pub fn load_from_path(mut self, path: &str) -> Texture2D {
+    /// Makes a texture from an encoded image (PNG, JPEG, ...) stored in memory.
+    pub fn from_memory(bytes: &[u8]) -> Texture2D {
+        return Texture2D::new().load_from_memory(bytes);
+    }
+
+    pub fn load_from_path(self, path: &str) -> Texture2D {
+        let (img, width, height) = load(path);
+        return self.load_image(&img, width, height);
+    }
+
+    pub fn load_from_memory(self, bytes: &[u8]) -> Texture2D {
+        let (img, width, height) = load_from_memory(bytes);
+        return self.load_image(&img, width, height);
+    }
+
+    fn load_image(mut self, img: &image::DynamicImage, width: i32, height: i32) -> Texture2D {
         assert_gl_is_loaded();
         let mut id = 0;
-        let (img, width, height) = load(path);
         let data = img.to_rgba8().into_vec();
 
         unsafe {
@@ -47,15 +61,21 @@ impl Texture2D {
             );
             gl::GenerateMipmap(gl::TEXTURE_2D);
         }
+        check_gl_error("Texture2D::load_image");
         self._id = id;
         return self;
     }
 
+    pub fn get_id(&self) -> u32 {
+        return self._id;
+    }
+
     pub fn bind(&self) {
         assert_gl_is_loaded();
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, self._id);
         }
+        check_gl_error("Texture2D::bind");
     }
 
     pub fn unbind(&self) {
@@ -63,5 +83,6 @@ impl Texture2D {
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, 0);
         }
+        check_gl_error("Texture2D::unbind");
     }
 }
@@ -0,0 +1,186 @@
+use crate::opengl::io::GamepadEvent;
+use crate::opengl::Window;
+use glfw;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// An application driven by an `AppRunner`. Only `update` and `render` have to be implemented,
+/// the rest of the callbacks do nothing by default.
+pub trait App {
+    /// Called once before the first frame.
+    fn init(&mut self, _window: &mut Window) {}
+
+    /// Called for every event received by the window, before the updates of the frame. The
+    /// state of the keys and buttons is also available through `window.input()`.
+    fn handle_event(&mut self, _window: &mut Window, _event: glfw::WindowEvent) {}
+
+    /// Called for every gamepad connection or button change, after the window events.
+    fn handle_gamepad_event(&mut self, _window: &mut Window, _event: GamepadEvent) {}
+
+    /// Advances the simulation by `time._fixed_delta` seconds. Depending on how long the frames
+    /// take, it can be called several times per frame, or not at all.
+    fn update(&mut self, window: &mut Window, time: &FrameTime);
+
+    /// Draws a frame. The buffers are swapped by the runner afterwards. `time._alpha` tells how
+    /// far the frame is between the last update and the next one, to interpolate the state.
+    fn render(&mut self, window: &mut Window, time: &FrameTime);
+
+    /// Called once after the window was asked to close, before `run` returns.
+    fn shutdown(&mut self, _window: &mut Window) {}
+}
+
+/// Frame rate statistics over the last measurement interval.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameStats {
+    pub _fps: f32,
+    pub _frame_time_avg: f32,
+    pub _frame_time_min: f32,
+    pub _frame_time_max: f32,
+}
+
+/// Timing information given to the callbacks of an `App`. All times are in seconds.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrameTime {
+    /// Time since the runner started.
+    pub _time: f32,
+    /// Duration of the last frame.
+    pub _delta: f32,
+    /// Duration of every fixed update.
+    pub _fixed_delta: f32,
+    /// Fraction of a fixed update accumulated but not simulated yet, between 0 and 1.
+    pub _alpha: f32,
+    /// Number of frames rendered so far.
+    pub _frame: u64,
+    /// Number of fixed updates run so far.
+    pub _updates: u64,
+    pub _stats: FrameStats,
+}
+
+/// Runs the main loop of an `App` on a window, with a fixed timestep for the updates and a
+/// variable one for rendering.
+pub struct AppRunner {
+    _fixed_timestep: f32,
+    _max_fps: Option<f32>,
+    _max_updates_per_frame: u32,
+    _stats_interval: f32,
+}
+
+impl AppRunner {
+    pub fn new() -> AppRunner {
+        return AppRunner {
+            _fixed_timestep: 1.0 / 60.0,
+            _max_fps: None,
+            _max_updates_per_frame: 8,
+            _stats_interval: 1.0,
+        };
+    }
+
+    /// Duration of a fixed update, which must be positive. The default is 1/60 seconds.
+    pub fn fixed_timestep(mut self, timestep: f32) -> AppRunner {
+        assert!(timestep > 0.0, "The fixed timestep must be positive.");
+        self._fixed_timestep = timestep;
+        return self;
+    }
+
+    /// Caps the frame rate by sleeping at the end of the frames that finish early. This is
+    /// independent from vsync, which is set when building the window. A frame rate that isn't
+    /// positive leaves it uncapped.
+    pub fn max_fps(mut self, fps: f32) -> AppRunner {
+        self._max_fps = if fps > 0.0 { Some(fps) } else { None };
+        return self;
+    }
+
+    pub fn uncapped(mut self) -> AppRunner {
+        self._max_fps = None;
+        return self;
+    }
+
+    /// Maximum number of fixed updates run in a single frame. When the updates can't keep up,
+    /// the remaining time is dropped instead of piling up. It must be at least one.
+    pub fn max_updates_per_frame(mut self, updates: u32) -> AppRunner {
+        assert!(updates > 0, "At least one update must run per frame.");
+        self._max_updates_per_frame = updates;
+        return self;
+    }
+
+    /// Interval over which the frame statistics are measured. The default is one second.
+    pub fn stats_interval(mut self, interval: f32) -> AppRunner {
+        self._stats_interval = interval;
+        return self;
+    }
+
+    /// Runs the application until its window is asked to close.
+    pub fn run<A: App>(&self, window: &mut Window, app: &mut A) {
+        let start = Instant::now();
+        let mut time = FrameTime {
+            _fixed_delta: self._fixed_timestep,
+            ..FrameTime::default()
+        };
+        let mut accumulator = 0.0;
+        let mut interval_frames = 0;
+        let mut interval_min = f32::MAX;
+        let mut interval_max = 0.0_f32;
+
+        app.init(window);
+        // The time taken by the initialization doesn't count as a frame
+        let mut previous = Instant::now();
+        let mut interval_start = previous;
+        while !window.should_close() {
+            let frame_start = Instant::now();
+            time._delta = (frame_start - previous).as_secs_f32();
+            time._time = (frame_start - start).as_secs_f32();
+            previous = frame_start;
+
+            for (_, event) in window.poll_events() {
+                app.handle_event(window, event);
+            }
+            for event in window.gamepads().events().to_vec() {
+                app.handle_gamepad_event(window, event);
+            }
+
+            accumulator += time._delta;
+            let max_accumulated = self._fixed_timestep * self._max_updates_per_frame as f32;
+            if accumulator > max_accumulated {
+                accumulator = max_accumulated;
+            }
+            while accumulator >= self._fixed_timestep {
+                app.update(window, &time);
+                accumulator -= self._fixed_timestep;
+                time._updates += 1;
+            }
+
+            time._alpha = accumulator / self._fixed_timestep;
+            app.render(window, &time);
+            window.swap_buffers();
+            time._frame += 1;
+
+            if let Some(fps) = self._max_fps {
+                let target = Duration::from_secs_f32(1.0 / fps);
+                let elapsed = frame_start.elapsed();
+                if elapsed < target {
+                    thread::sleep(target - elapsed);
+                }
+            }
+
+            // The statistics count the whole frame, including the time spent sleeping
+            let frame_time = frame_start.elapsed().as_secs_f32();
+            interval_frames += 1;
+            interval_min = interval_min.min(frame_time);
+            interval_max = interval_max.max(frame_time);
+            let interval = interval_start.elapsed().as_secs_f32();
+            if interval >= self._stats_interval {
+                time._stats = FrameStats {
+                    _fps: interval_frames as f32 / interval,
+                    _frame_time_avg: interval / interval_frames as f32,
+                    _frame_time_min: interval_min,
+                    _frame_time_max: interval_max,
+                };
+                interval_start = Instant::now();
+                interval_frames = 0;
+                interval_min = f32::MAX;
+                interval_max = 0.0;
+            }
+        }
+        app.shutdown(window);
+    }
+}
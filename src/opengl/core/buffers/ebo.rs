@@ -1,4 +1,4 @@
-use crate::opengl::assert_gl_is_loaded;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
 use gl;
 use gl::types::*;
 use std::mem::size_of;
@@ -54,15 +54,21 @@ impl<'a> EBO<'a> {
                 self._usage,
             );
         }
+        check_gl_error("EBO::build");
         self._id = ebo;
         return self;
     }
 
+    pub fn get_id(&self) -> u32 {
+        return self._id;
+    }
+
     pub fn bind(&self) {
         assert_gl_is_loaded();
         unsafe {
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self._id);
         }
+        check_gl_error("EBO::bind");
     }
 
     pub fn unbind(&self) {
@@ -70,6 +76,7 @@ impl<'a> EBO<'a> {
         unsafe {
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
         }
+        check_gl_error("EBO::unbind");
     }
 
     pub fn del(&self) {
@@ -77,5 +84,6 @@ impl<'a> EBO<'a> {
         unsafe {
             gl::DeleteBuffers(1, &self._id);
         }
+        check_gl_error("EBO::del");
     }
 }
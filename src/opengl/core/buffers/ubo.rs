@@ -1,4 +1,4 @@
-use crate::opengl::assert_gl_is_loaded;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
 use gl;
 use gl::types::*;
 use std::mem::size_of;
@@ -48,6 +48,7 @@ impl UBO {
 
             gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
         }
+        check_gl_error("UBO::build");
 
         self._id = ubo;
         return self;
@@ -59,6 +60,7 @@ impl UBO {
         unsafe {
             gl::BindBuffer(gl::UNIFORM_BUFFER, self._id);
         }
+        check_gl_error("UBO::bind");
     }
 
     /// Uninds the UBO
@@ -67,6 +69,7 @@ impl UBO {
         unsafe {
             gl::BindBuffer(gl::UNIFORM_BUFFER, 0);
         }
+        check_gl_error("UBO::unbind");
     }
 
     /// Binds the UBO to the specified index in memory. It's important to note that, by default,
@@ -76,6 +79,7 @@ impl UBO {
         unsafe {
             gl::BindBufferBase(gl::UNIFORM_BUFFER, index, self._id);
         }
+        check_gl_error("UBO::bind_index");
     }
 
     /// Buffers the given data with a certain offset.
@@ -91,5 +95,6 @@ impl UBO {
             );
             self.unbind();
         }
+        check_gl_error("UBO::buffer_data");
     }
 }
@@ -1,4 +1,4 @@
-use crate::opengl::assert_gl_is_loaded;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
 use crate::opengl::buffers::VBO;
 use gl;
 use std::mem::size_of;
@@ -50,15 +50,21 @@ impl<'a> VAO<'a> {
             assert_ne!(vao, 0);
             gl::BindVertexArray(vao);
         }
+        check_gl_error("VAO::build");
         self._id = vao;
         return self;
     }
 
+    pub fn get_id(&self) -> u32 {
+        return self._id;
+    }
+
     pub fn bind(&self) {
         assert_gl_is_loaded();
         unsafe {
             gl::BindVertexArray(self._id);
         }
+        check_gl_error("VAO::bind");
     }
 
     pub fn unbind(&self) {
@@ -66,13 +72,15 @@ impl<'a> VAO<'a> {
         unsafe {
             gl::BindVertexArray(0);
         }
+        check_gl_error("VAO::unbind");
     }
 
     pub fn del(&self) {
         assert_gl_is_loaded();
         unsafe {
-            gl::DeleteBuffers(1, &self._id);
+            gl::DeleteVertexArrays(1, &self._id);
         }
+        check_gl_error("VAO::del");
     }
 
     pub fn link_vbo<T>(&self, vbo: &VBO<T>, layout: u32) {
@@ -95,11 +103,12 @@ impl<'a> VAO<'a> {
                     gl::FLOAT,
                     gl::FALSE,
                     self._stride.try_into().unwrap(),
-                    (self._sizes[layout as usize] * self._size * layout) as *const _,
+                    (self._sizes[..layout as usize].iter().sum::<u32>() * self._size) as *const _,
                 );
             }
             gl::EnableVertexAttribArray(layout);
         }
+        check_gl_error("VAO::link_vbo");
         vbo.unbind();
     }
 }
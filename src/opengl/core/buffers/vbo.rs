@@ -1,4 +1,4 @@
-use crate::opengl::assert_gl_is_loaded;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
 use gl;
 use gl::types::*;
 use std::mem::size_of;
@@ -46,6 +46,7 @@ impl<'a, T> VBO<'a, T> {
 
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
+        check_gl_error("VBO::build");
         self._id = vbo;
         return self;
     }
@@ -60,6 +61,11 @@ impl<'a, T> VBO<'a, T> {
                 self._usage,
             );
         }
+        check_gl_error("VBO::buffer_data");
+    }
+
+    pub fn get_id(&self) -> u32 {
+        return self._id;
     }
 
     pub fn bind(&self) {
@@ -67,6 +73,7 @@ impl<'a, T> VBO<'a, T> {
         unsafe {
             gl::BindBuffer(gl::ARRAY_BUFFER, self._id);
         }
+        check_gl_error("VBO::bind");
     }
 
     pub fn unbind(&self) {
@@ -74,5 +81,6 @@ impl<'a, T> VBO<'a, T> {
         unsafe {
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
         }
+        check_gl_error("VBO::unbind");
     }
 }
@@ -0,0 +1,133 @@
+use crate::opengl::assert_gl_is_loaded;
+use gl;
+use gl::types::*;
+use log;
+use std::ffi::{c_void, CStr};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CHECK_ERRORS: AtomicBool = AtomicBool::new(false);
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Debug output |=======================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Installs a `glDebugMessageCallback` that forwards the messages of the driver to `log`, with
+/// a level matching their severity. The messages are only guaranteed to be reported on a debug
+/// context (see `Window::debug_context`), and are delivered synchronously so that they can be
+/// traced back to the call that produced them.
+pub fn enable_debug_output() {
+    assert_gl_is_loaded();
+    unsafe {
+        if !gl::DebugMessageCallback::is_loaded() {
+            log::warn!("glDebugMessageCallback is not available, debug output is disabled.");
+            return;
+        }
+        gl::Enable(gl::DEBUG_OUTPUT);
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+        gl::DebugMessageControl(
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            0,
+            std::ptr::null(),
+            gl::TRUE,
+        );
+    }
+}
+
+pub fn disable_debug_output() {
+    assert_gl_is_loaded();
+    unsafe {
+        gl::Disable(gl::DEBUG_OUTPUT);
+    }
+}
+
+extern "system" fn debug_callback(
+    source: GLenum,
+    gltype: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    _length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut c_void,
+) {
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy();
+    let level = match severity {
+        gl::DEBUG_SEVERITY_HIGH => log::Level::Error,
+        gl::DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        gl::DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    };
+    log::log!(
+        level,
+        "OpenGL {} {} ({id}): {message}",
+        source_name(source),
+        type_name(gltype)
+    );
+}
+
+fn source_name(source: GLenum) -> &'static str {
+    return match source {
+        gl::DEBUG_SOURCE_API => "API",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    };
+}
+
+fn type_name(gltype: GLenum) -> &'static str {
+    return match gltype {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behavior",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        _ => "message",
+    };
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=====================================| Error checking |======================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Enables or disables the checked mode, in which `glGetError` is run after the OpenGL calls
+/// wrapped by cuboid. It is disabled by default, as it forces the driver to synchronize.
+pub fn set_error_checking(enabled: bool) {
+    CHECK_ERRORS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn error_checking() -> bool {
+    return CHECK_ERRORS.load(Ordering::Relaxed);
+}
+
+/// Logs every pending OpenGL error as coming from the given function, when the checked mode
+/// is enabled.
+pub fn check_gl_error(function: &str) {
+    if !error_checking() {
+        return;
+    }
+    loop {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            break;
+        }
+        log::error!("OpenGL error {} in {function}.", error_name(error));
+    }
+}
+
+pub fn error_name(error: GLenum) -> &'static str {
+    return match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        _ => "unknown",
+    };
+}
@@ -1,10 +1,16 @@
+pub mod app;
 pub mod buffers;
+pub mod debug;
 pub mod shader;
+pub mod state;
 pub mod traits;
 pub mod transform;
 pub mod window;
 
+pub use app::*;
+pub use debug::*;
 pub use shader::*;
+pub use state::*;
 pub use traits::*;
 pub use transform::*;
 pub use window::*;
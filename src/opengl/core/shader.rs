@@ -1,5 +1,5 @@
-use crate::opengl::assert_gl_is_loaded;
-use crate::utils::str::name_to_ptr;
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
+use crate::utils::str::to_cstring;
 use crate::utils::types::V4;
 use gl;
 use gl::types::*;
@@ -14,18 +14,21 @@ pub struct Shader {
 impl Shader {
     pub fn new() -> Shader {
         assert_gl_is_loaded();
-        unsafe {
-            return Shader {
-                _id: gl::CreateProgram(),
-            };
-        }
+        let id = unsafe { gl::CreateProgram() };
+        check_gl_error("Shader::new");
+        return Shader { _id: id };
     }
 
     pub fn vertex(self, vertex_path: &str) -> Shader {
+        let vertex_content = fs::read_to_string(vertex_path).expect("Error reading vertex shader.");
+        return self.vertex_source(&vertex_content);
+    }
+
+    /// Same as `vertex`, with the source code of the shader instead of its path.
+    pub fn vertex_source(self, vertex_content: &str) -> Shader {
         assert_gl_is_loaded();
         // Making the vertex and fragment shaders
-        let vertex_content = fs::read_to_string(vertex_path).expect("Error reading vertex shader.");
-        let vertex_shader = Shader::make_vertex_shader(&vertex_content);
+        let vertex_shader = Shader::make_vertex_shader(vertex_content);
         Shader::verify_vertex_shader(&vertex_shader);
 
         unsafe {
@@ -43,15 +46,21 @@ impl Shader {
             }
             gl::DeleteShader(vertex_shader);
         }
+        check_gl_error("Shader::vertex_source");
         return self;
     }
 
     pub fn fragment(self, fragment_path: &str) -> Shader {
-        assert_gl_is_loaded();
-        // Making the vertex and fragment shaders
         let fragment_content =
             fs::read_to_string(fragment_path).expect("Error reading fragment shader.");
-        let fragment_shader = Shader::make_fragment_shader(&fragment_content);
+        return self.fragment_source(&fragment_content);
+    }
+
+    /// Same as `fragment`, with the source code of the shader instead of its path.
+    pub fn fragment_source(self, fragment_content: &str) -> Shader {
+        assert_gl_is_loaded();
+        // Making the vertex and fragment shaders
+        let fragment_shader = Shader::make_fragment_shader(fragment_content);
         Shader::verify_fragment_shader(&fragment_shader);
 
         unsafe {
@@ -69,6 +78,7 @@ impl Shader {
             }
             gl::DeleteShader(fragment_shader);
         }
+        check_gl_error("Shader::fragment_source");
         return self;
     }
 
@@ -86,6 +96,7 @@ impl Shader {
             );
             gl::CompileShader(shader);
         }
+        check_gl_error("Shader::make_shader");
         return shader;
     }
 
@@ -110,6 +121,7 @@ impl Shader {
                 panic!("{} : {}", message, String::from_utf8_lossy(&v));
             }
         }
+        check_gl_error("Shader::verify_shader");
     }
 
     fn verify_vertex_shader(vertex_shader: &GLuint) {
@@ -125,103 +137,130 @@ impl Shader {
         unsafe {
             gl::UseProgram(self._id);
         }
+        check_gl_error("Shader::use_program");
     }
 
     pub fn del(&self) {
         assert_gl_is_loaded();
         unsafe {
-            gl::DeleteShader(self._id);
+            gl::DeleteProgram(self._id);
         }
+        check_gl_error("Shader::del");
     }
 
     pub fn set_1i(&self, name: &str, v0: i32) {
         assert_gl_is_loaded();
         unsafe {
-            gl::Uniform1i(gl::GetUniformLocation(self._id, name_to_ptr(name)), v0);
+            gl::Uniform1i(self.uniform_location(name), v0);
         }
+        check_gl_error("Shader::set_1i");
     }
 
     pub fn set_2i(&self, name: &str, v0: i32, v1: i32) {
         assert_gl_is_loaded();
         unsafe {
-            gl::Uniform2i(gl::GetUniformLocation(self._id, name_to_ptr(name)), v0, v1);
+            gl::Uniform2i(self.uniform_location(name), v0, v1);
         }
+        check_gl_error("Shader::set_2i");
     }
 
     pub fn set_3i(&self, name: &str, v0: i32, v1: i32, v2: i32) {
         assert_gl_is_loaded();
         unsafe {
             gl::Uniform3i(
-                gl::GetUniformLocation(self._id, name_to_ptr(name)),
+                self.uniform_location(name),
                 v0,
                 v1,
                 v2,
             );
         }
+        check_gl_error("Shader::set_3i");
     }
 
     pub fn set_4i(&self, name: &str, v0: i32, v1: i32, v2: i32, v3: i32) {
         assert_gl_is_loaded();
         unsafe {
             gl::Uniform4i(
-                gl::GetUniformLocation(self._id, name_to_ptr(name)),
+                self.uniform_location(name),
                 v0,
                 v1,
                 v2,
                 v3,
             );
         }
+        check_gl_error("Shader::set_4i");
+    }
+
+    pub fn set_1ui(&self, name: &str, v0: u32) {
+        assert_gl_is_loaded();
+        unsafe {
+            gl::Uniform1ui(self.uniform_location(name), v0);
+        }
+        check_gl_error("Shader::set_1ui");
     }
 
     pub fn set_1f(&self, name: &str, v0: f32) {
         assert_gl_is_loaded();
         unsafe {
-            gl::Uniform1f(gl::GetUniformLocation(self._id, name_to_ptr(name)), v0);
+            gl::Uniform1f(self.uniform_location(name), v0);
         }
+        check_gl_error("Shader::set_1f");
     }
 
     pub fn set_2f(&self, name: &str, v0: f32, v1: f32) {
         assert_gl_is_loaded();
         unsafe {
-            gl::Uniform2f(gl::GetUniformLocation(self._id, name_to_ptr(name)), v0, v1);
+            gl::Uniform2f(self.uniform_location(name), v0, v1);
         }
+        check_gl_error("Shader::set_2f");
     }
 
     pub fn set_3f(&self, name: &str, v0: f32, v1: f32, v2: f32) {
         assert_gl_is_loaded();
         unsafe {
             gl::Uniform3f(
-                gl::GetUniformLocation(self._id, name_to_ptr(name)),
+                self.uniform_location(name),
                 v0,
                 v1,
                 v2,
             );
         }
+        check_gl_error("Shader::set_3f");
     }
 
     pub fn set_4f(&self, name: &str, v0: f32, v1: f32, v2: f32, v3: f32) {
         assert_gl_is_loaded();
         unsafe {
             gl::Uniform4f(
-                gl::GetUniformLocation(self._id, name_to_ptr(name)),
+                self.uniform_location(name),
                 v0,
                 v1,
                 v2,
                 v3,
             );
         }
+        check_gl_error("Shader::set_4f");
     }
 
     pub fn set_matrix4fv(&self, name: &str, value: &[V4; 4]) {
         assert_gl_is_loaded();
         unsafe {
             gl::UniformMatrix4fv(
-                gl::GetUniformLocation(self._id, name_to_ptr(name)),
+                self.uniform_location(name),
                 1,
                 gl::FALSE,
                 value.as_ptr().cast(),
             );
         }
+        check_gl_error("Shader::set_matrix4fv");
+    }
+
+    /// Location of a uniform of the program, or -1 if it doesn't exist.
+    pub fn uniform_location(&self, name: &str) -> i32 {
+        let name = to_cstring(name);
+        let location = unsafe { gl::GetUniformLocation(self._id, name.as_ptr()) };
+        check_gl_error("Shader::uniform_location");
+        return location;
     }
 
     pub fn get_id(&self) -> u32 {
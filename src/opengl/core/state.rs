@@ -0,0 +1,498 @@
+use crate::opengl::{assert_gl_is_loaded, check_gl_error};
+use gl;
+use gl::types::*;
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Descriptions |=======================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Factors and equation of `glBlendFuncSeparate` and `glBlendEquation`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BlendFunction {
+    pub _src_rgb: GLenum,
+    pub _dst_rgb: GLenum,
+    pub _src_alpha: GLenum,
+    pub _dst_alpha: GLenum,
+    pub _equation: GLenum,
+}
+
+impl BlendFunction {
+    pub fn new(src: GLenum, dst: GLenum) -> BlendFunction {
+        return BlendFunction::separate(src, dst, src, dst);
+    }
+
+    pub fn separate(
+        src_rgb: GLenum,
+        dst_rgb: GLenum,
+        src_alpha: GLenum,
+        dst_alpha: GLenum,
+    ) -> BlendFunction {
+        return BlendFunction {
+            _src_rgb: src_rgb,
+            _dst_rgb: dst_rgb,
+            _src_alpha: src_alpha,
+            _dst_alpha: dst_alpha,
+            _equation: gl::FUNC_ADD,
+        };
+    }
+
+    pub fn equation(mut self, equation: GLenum) -> BlendFunction {
+        self._equation = equation;
+        return self;
+    }
+}
+
+/// How the fragments are combined with the color already in the framebuffer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BlendMode {
+    /// Blending disabled, the fragments replace the color.
+    Opaque,
+    /// Usual transparency, for colors that aren't multiplied by their alpha.
+    Alpha,
+    /// Transparency for colors already multiplied by their alpha, which filters correctly and
+    /// can mix additive and transparent parts.
+    Premultiplied,
+    Additive,
+    Multiply,
+    Custom(BlendFunction),
+}
+
+impl BlendMode {
+    /// Blending function of the mode, or `None` when blending is disabled.
+    pub fn function(&self) -> Option<BlendFunction> {
+        return match *self {
+            BlendMode::Opaque => None,
+            BlendMode::Alpha => Some(BlendFunction::separate(
+                gl::SRC_ALPHA,
+                gl::ONE_MINUS_SRC_ALPHA,
+                gl::ONE,
+                gl::ONE_MINUS_SRC_ALPHA,
+            )),
+            BlendMode::Premultiplied => Some(BlendFunction::new(gl::ONE, gl::ONE_MINUS_SRC_ALPHA)),
+            BlendMode::Additive => Some(BlendFunction::separate(
+                gl::SRC_ALPHA,
+                gl::ONE,
+                gl::ONE,
+                gl::ONE,
+            )),
+            BlendMode::Multiply => Some(BlendFunction::new(gl::DST_COLOR, gl::ZERO)),
+            BlendMode::Custom(function) => Some(function),
+        };
+    }
+}
+
+/// Test and operations of `glStencilFunc`, `glStencilMask` and `glStencilOp`, for both faces.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StencilState {
+    pub _function: GLenum,
+    pub _reference: i32,
+    pub _read_mask: u32,
+    pub _write_mask: u32,
+    pub _fail: GLenum,
+    pub _depth_fail: GLenum,
+    pub _pass: GLenum,
+}
+
+impl StencilState {
+    /// Stencil test passing everywhere and keeping the values, to be customized.
+    pub fn new() -> StencilState {
+        return StencilState {
+            _function: gl::ALWAYS,
+            _reference: 0,
+            _read_mask: u32::MAX,
+            _write_mask: u32::MAX,
+            _fail: gl::KEEP,
+            _depth_fail: gl::KEEP,
+            _pass: gl::KEEP,
+        };
+    }
+
+    pub fn function(mut self, function: GLenum, reference: i32, read_mask: u32) -> StencilState {
+        self._function = function;
+        self._reference = reference;
+        self._read_mask = read_mask;
+        return self;
+    }
+
+    pub fn write_mask(mut self, mask: u32) -> StencilState {
+        self._write_mask = mask;
+        return self;
+    }
+
+    /// Operations when the stencil test fails, when the depth test fails, and when both pass.
+    pub fn operations(mut self, fail: GLenum, depth_fail: GLenum, pass: GLenum) -> StencilState {
+        self._fail = fail;
+        self._depth_fail = depth_fail;
+        self._pass = pass;
+        return self;
+    }
+}
+
+/// Fixed-function state used to draw something. It can be given to a material or to an item of
+/// `Renderer3D`, which applies it through a `RenderStateCache`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderState {
+    pub _blend: BlendMode,
+    pub _depth_test: bool,
+    pub _depth_write: bool,
+    pub _depth_function: GLenum,
+    /// Faces removed by face culling, or `None` to draw both.
+    pub _cull_face: Option<GLenum>,
+    pub _front_face: GLenum,
+    pub _stencil: Option<StencilState>,
+    /// Rectangle (x, y, width, height) outside of which nothing is drawn, from the lower-left
+    /// corner of the framebuffer.
+    pub _scissor: Option<[i32; 4]>,
+    pub _color_mask: [bool; 4],
+    /// Width of the lines. Core profiles may only support 1.
+    pub _line_width: f32,
+    /// Size of the points, when not written by the shaders.
+    pub _point_size: f32,
+    /// Factor and units of `glPolygonOffset`, applied to filled polygons.
+    pub _polygon_offset: Option<(f32, f32)>,
+}
+
+impl RenderState {
+    /// Opaque state with the depth test enabled, as set by `init_gl`.
+    pub fn new() -> RenderState {
+        return RenderState {
+            _blend: BlendMode::Opaque,
+            _depth_test: true,
+            _depth_write: true,
+            _depth_function: gl::LESS,
+            _cull_face: None,
+            _front_face: gl::CCW,
+            _stencil: None,
+            _scissor: None,
+            _color_mask: [true; 4],
+            _line_width: 1.0,
+            _point_size: 1.0,
+            _polygon_offset: None,
+        };
+    }
+
+    /// State for transparent objects, which are blended and tested against the depth without
+    /// writing it.
+    pub fn transparent() -> RenderState {
+        return RenderState::new()
+            .blend(BlendMode::Alpha)
+            .depth_write(false);
+    }
+
+    pub fn blend(mut self, blend: BlendMode) -> RenderState {
+        self._blend = blend;
+        return self;
+    }
+
+    pub fn depth_test(mut self, enabled: bool) -> RenderState {
+        self._depth_test = enabled;
+        return self;
+    }
+
+    pub fn depth_write(mut self, enabled: bool) -> RenderState {
+        self._depth_write = enabled;
+        return self;
+    }
+
+    pub fn depth_function(mut self, function: GLenum) -> RenderState {
+        self._depth_function = function;
+        return self;
+    }
+
+    pub fn cull_face(mut self, face: Option<GLenum>) -> RenderState {
+        self._cull_face = face;
+        return self;
+    }
+
+    pub fn front_face(mut self, front_face: GLenum) -> RenderState {
+        self._front_face = front_face;
+        return self;
+    }
+
+    pub fn stencil(mut self, stencil: Option<StencilState>) -> RenderState {
+        self._stencil = stencil;
+        return self;
+    }
+
+    pub fn scissor(mut self, scissor: Option<[i32; 4]>) -> RenderState {
+        self._scissor = scissor;
+        return self;
+    }
+
+    pub fn color_mask(mut self, r: bool, g: bool, b: bool, a: bool) -> RenderState {
+        self._color_mask = [r, g, b, a];
+        return self;
+    }
+
+    pub fn line_width(mut self, width: f32) -> RenderState {
+        self._line_width = width;
+        return self;
+    }
+
+    pub fn point_size(mut self, size: f32) -> RenderState {
+        self._point_size = size;
+        return self;
+    }
+
+    pub fn polygon_offset(mut self, offset: Option<(f32, f32)>) -> RenderState {
+        self._polygon_offset = offset;
+        return self;
+    }
+
+    /// Same state for a reversed depth, where the closest fragments have the greatest depth.
+    pub fn reversed_depth(mut self) -> RenderState {
+        self._depth_function = match self._depth_function {
+            gl::LESS => gl::GREATER,
+            gl::LEQUAL => gl::GEQUAL,
+            gl::GREATER => gl::LESS,
+            gl::GEQUAL => gl::LEQUAL,
+            function => function,
+        };
+        return self;
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|==========================================| Cache |==========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Remembers the last state applied to the context, so that applying a new one only makes the
+/// OpenGL calls for what changed. The state set by other means is unknown to the cache, which
+/// has to be invalidated afterwards.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RenderStateCache {
+    _current: Option<RenderState>,
+    _changes: u32,
+}
+
+impl RenderStateCache {
+    pub fn new() -> RenderStateCache {
+        return RenderStateCache::default();
+    }
+
+    /// Forgets the current state, so that the next one is applied entirely.
+    pub fn invalidate(&mut self) {
+        self._current = None;
+    }
+
+    pub fn current(&self) -> Option<RenderState> {
+        return self._current;
+    }
+
+    /// Number of parts of the state changed since the last reset, which are each one or a few
+    /// OpenGL calls.
+    pub fn get_changes(&self) -> u32 {
+        return self._changes;
+    }
+
+    pub fn reset_changes(&mut self) {
+        self._changes = 0;
+    }
+
+    pub fn apply(&mut self, state: &RenderState) {
+        let previous = self._current;
+        assert_gl_is_loaded();
+        unsafe {
+            if previous.is_none_or(|previous| previous._blend != state._blend) {
+                match state._blend.function() {
+                    Some(function) => {
+                        gl::Enable(gl::BLEND);
+                        gl::BlendFuncSeparate(
+                            function._src_rgb,
+                            function._dst_rgb,
+                            function._src_alpha,
+                            function._dst_alpha,
+                        );
+                        gl::BlendEquation(function._equation);
+                    }
+                    None => gl::Disable(gl::BLEND),
+                }
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._depth_test != state._depth_test) {
+                set_capability(gl::DEPTH_TEST, state._depth_test);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._depth_write != state._depth_write) {
+                gl::DepthMask(state._depth_write as GLboolean);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._depth_function != state._depth_function) {
+                gl::DepthFunc(state._depth_function);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._cull_face != state._cull_face) {
+                set_capability(gl::CULL_FACE, state._cull_face.is_some());
+                if let Some(face) = state._cull_face {
+                    gl::CullFace(face);
+                }
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._front_face != state._front_face) {
+                gl::FrontFace(state._front_face);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._stencil != state._stencil) {
+                set_capability(gl::STENCIL_TEST, state._stencil.is_some());
+                if let Some(stencil) = state._stencil {
+                    gl::StencilFunc(stencil._function, stencil._reference, stencil._read_mask);
+                    gl::StencilMask(stencil._write_mask);
+                    gl::StencilOp(stencil._fail, stencil._depth_fail, stencil._pass);
+                }
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._scissor != state._scissor) {
+                set_capability(gl::SCISSOR_TEST, state._scissor.is_some());
+                if let Some([x, y, width, height]) = state._scissor {
+                    gl::Scissor(x, y, width, height);
+                }
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._color_mask != state._color_mask) {
+                let [r, g, b, a] = state._color_mask.map(|channel| channel as GLboolean);
+                gl::ColorMask(r, g, b, a);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._line_width != state._line_width) {
+                gl::LineWidth(state._line_width);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._point_size != state._point_size) {
+                gl::PointSize(state._point_size);
+                self._changes += 1;
+            }
+            if previous.is_none_or(|previous| previous._polygon_offset != state._polygon_offset) {
+                set_capability(gl::POLYGON_OFFSET_FILL, state._polygon_offset.is_some());
+                if let Some((factor, units)) = state._polygon_offset {
+                    gl::PolygonOffset(factor, units);
+                }
+                self._changes += 1;
+            }
+        }
+        check_gl_error("RenderStateCache::apply");
+        self._current = Some(*state);
+    }
+}
+
+unsafe fn set_capability(capability: GLenum, enabled: bool) {
+    if enabled {
+        gl::Enable(capability);
+    } else {
+        gl::Disable(capability);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|========================================| Bindings |=========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What a draw call binds, used to order the draw calls so that consecutive ones share as many
+/// bindings as possible. The fields are compared in order, the program changing least often.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DrawKey {
+    pub _shader: u32,
+    /// Identifies the uniforms given by the material, see `Material::key`.
+    pub _material: u64,
+    pub _texture: u32,
+    pub _vao: u32,
+}
+
+/// Remembers the program, material, texture and vertex array bound by the last draw calls, so
+/// that the next ones only bind what changed. Like `RenderStateCache`, it has to be invalidated
+/// when something else binds them.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BindingCache {
+    _program: Option<u32>,
+    _material: Option<u64>,
+    _texture: Option<u32>,
+    _vao: Option<u32>,
+    _changes: u32,
+}
+
+impl BindingCache {
+    pub fn new() -> BindingCache {
+        return BindingCache::default();
+    }
+
+    pub fn invalidate(&mut self) {
+        self._program = None;
+        self._material = None;
+        self._texture = None;
+        self._vao = None;
+    }
+
+    /// Unbinds the texture and the vertex array, as the objects drawn on their own do.
+    pub fn unbind(&mut self) {
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        check_gl_error("BindingCache::unbind");
+        self.invalidate();
+    }
+
+    /// Number of bindings changed since the last reset.
+    pub fn get_changes(&self) -> u32 {
+        return self._changes;
+    }
+
+    pub fn reset_changes(&mut self) {
+        self._changes = 0;
+    }
+
+    /// Uses a program if it isn't already, returning whether it changed.
+    pub fn use_program(&mut self, program: u32) -> bool {
+        if self._program == Some(program) {
+            return false;
+        }
+        assert_gl_is_loaded();
+        unsafe {
+            gl::UseProgram(program);
+        }
+        check_gl_error("BindingCache::use_program");
+        self._program = Some(program);
+        self._material = None;
+        self._changes += 1;
+        return true;
+    }
+
+    /// Records the material whose uniforms are set in the current program, returning whether
+    /// they have to be uploaded again.
+    pub fn set_material(&mut self, material: u64) -> bool {
+        if self._material == Some(material) {
+            return false;
+        }
+        self._material = Some(material);
+        self._changes += 1;
+        return true;
+    }
+
+    pub fn bind_texture(&mut self, texture: u32) -> bool {
+        if self._texture == Some(texture) {
+            return false;
+        }
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+        }
+        check_gl_error("BindingCache::bind_texture");
+        self._texture = Some(texture);
+        self._changes += 1;
+        return true;
+    }
+
+    pub fn bind_vao(&mut self, vao: u32) -> bool {
+        if self._vao == Some(vao) {
+            return false;
+        }
+        assert_gl_is_loaded();
+        unsafe {
+            gl::BindVertexArray(vao);
+        }
+        check_gl_error("BindingCache::bind_vao");
+        self._vao = Some(vao);
+        self._changes += 1;
+        return true;
+    }
+}
@@ -1,4 +1,6 @@
-use crate::opengl::Transform;
+use crate::opengl::{BindingCache, DrawKey, RenderState, Shader, Transform};
+use crate::utils::math::geometry::{Aabb, BoundingSphere, Obb, Ray, RayHit};
+use crate::utils::types::V4;
 use gl::types::*;
 
 pub trait Renderer {
@@ -10,6 +12,54 @@ pub trait Renderer {
 pub trait Drawable {
     fn draw_with_mode(&self, mode: GLenum);
     fn draw(&self);
+
+    /// Draws the geometry alone, with the program in use instead of the material of the object.
+    /// It is used by passes that replace the shading, such as picking.
+    fn draw_geometry(&self, mode: GLenum);
+
+    /// Intersects a ray in world space with the triangles of the object, giving the closest hit.
+    /// Objects that can't be picked on the CPU return `None`.
+    fn intersect_ray(&self, _ray: &Ray) -> Option<RayHit> {
+        return None;
+    }
+
+    /// Matrix placing the vertices of the object in world space, which is given to the shaders
+    /// as the `mat4 uModel` uniform. Objects without one have their vertices in world space.
+    fn model_matrix(&self) -> Option<[V4; 4]> {
+        return None;
+    }
+
+    /// Box in world space containing the object, used to skip it when it is out of view.
+    /// Objects without bounds are always drawn.
+    fn bounds(&self) -> Option<Aabb> {
+        return None;
+    }
+
+    /// State the object asks to be drawn with, such as the one of its material. The renderer
+    /// uses its default state for the objects without one.
+    fn render_state(&self) -> Option<RenderState> {
+        return None;
+    }
+
+    /// Program the object is drawn with, which the renderer gives some uniforms to, such as
+    /// `uOitPass` (see `OIT_GLSL`).
+    fn shader(&self) -> Option<Shader> {
+        return None;
+    }
+
+    /// What the object binds to be drawn, by which the renderer orders the draw calls. Objects
+    /// without a key are drawn before the others sharing their state.
+    fn draw_key(&self) -> Option<DrawKey> {
+        return None;
+    }
+
+    /// Draws the object after the previous ones, only binding what they didn't and leaving its
+    /// bindings for the next ones. By default the object is drawn as usual, after which what
+    /// is bound is unknown.
+    fn draw_cached(&self, mode: GLenum, bindings: &mut BindingCache) {
+        self.draw_with_mode(mode);
+        bindings.invalidate();
+    }
 }
 
 pub trait Transformable {
@@ -18,3 +68,14 @@ pub trait Transformable {
         self.get_trans().add(transform);
     }
 }
+
+/// An object occupying a known region of world space, used to frame, cull or pick it.
+pub trait Bounded {
+    /// Smallest box aligned with the world axes containing the object.
+    fn bounding_box(&self) -> Aabb;
+
+    /// Box aligned with the axes of the object, which stays tight when it is rotated.
+    fn oriented_box(&self) -> Obb;
+
+    fn bounding_sphere(&self) -> BoundingSphere;
+}
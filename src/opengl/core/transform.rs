@@ -1,3 +1,5 @@
+use crate::utils::conversions;
+use crate::utils::math::linalg;
 use crate::utils::types::V4;
 use std::ops;
 
@@ -70,6 +72,12 @@ impl Transform {
     pub fn scale(&mut self, x: f32, y: f32, z: f32) {
         self._scale += TransformScale::new_value(x, y, z);
     }
+
+    /// Matrix applying the scale, then the rotation and then the translation.
+    pub fn mat4(&self) -> [V4; 4] {
+        let rotate_scale = linalg::mat4_mul4(&self._rotate.mat4(), &self._scale.mat4());
+        return linalg::mat4_mul4(&self._translate.mat4(), &rotate_scale);
+    }
 }
 
 impl ops::Add<Transform> for Transform {
@@ -218,6 +226,16 @@ impl TransformRotate {
     pub fn get_phi(&self) -> f32 {
         return self._phi;
     }
+
+    /// Rotation taking the Z axis to the heading `theta` (around Y) and elevation `phi` in
+    /// degrees.
+    pub fn mat4(&self) -> [V4; 4] {
+        let rotation = linalg::mat3_mul3(
+            &linalg::rot_mat3_y(self._theta),
+            &linalg::rot_mat3_x(-self._phi),
+        );
+        return conversions::mat3_to_mat4(&rotation);
+    }
 }
 
 impl ops::Add<TransformRotate> for TransformRotate {
@@ -284,6 +302,15 @@ impl TransformScale {
     pub fn get_z(&self) -> f32 {
         return self._z;
     }
+
+    pub fn mat4(&self) -> [V4; 4] {
+        return [
+            [self._x, 0.0, 0.0, 0.0],
+            [0.0, self._y, 0.0, 0.0],
+            [0.0, 0.0, self._z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+    }
 }
 
 impl ops::Add<TransformScale> for TransformScale {
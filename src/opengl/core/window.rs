@@ -1,6 +1,9 @@
+use crate::opengl::debug::{enable_debug_output, set_error_checking};
+use crate::opengl::io::{GamepadSource, Gamepads, GlfwGamepads, InputState};
 use gl;
 use glfw;
 use glfw::Context;
+use std::ffi::CStr;
 use std::sync::mpsc::Receiver;
 
 pub static mut GL_IS_LOADED: bool = false;
@@ -10,85 +13,358 @@ by running init::init_gl(&window), where window is the instance of glfw::Window
 using.
 ";
 
-#[derive(Copy, Clone)]
+/// Whether a window is windowed or fullscreen on the monitor with the given index, as listed
+/// by `Window::monitors`.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum WindowMode {
     Windowed,
     FullScreen(u32),
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CursorMode {
+    Normal,
+    /// The cursor is invisible while over the window, but moves freely.
+    Hidden,
+    /// The cursor is hidden and locked to the window, giving unbounded movement. This is the
+    /// mode used by first person cameras.
+    Disabled,
+}
+
+/// A video mode supported by a monitor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VideoMode {
+    pub _width: u32,
+    pub _height: u32,
+    pub _refresh_rate: u32,
+    pub _color_bits: (u32, u32, u32),
+}
+
+impl From<glfw::VidMode> for VideoMode {
+    fn from(mode: glfw::VidMode) -> Self {
+        return VideoMode {
+            _width: mode.width,
+            _height: mode.height,
+            _refresh_rate: mode.refresh_rate,
+            _color_bits: (mode.red_bits, mode.green_bits, mode.blue_bits),
+        };
+    }
+}
+
+/// Description of a connected monitor.
+#[derive(Clone, Debug)]
+pub struct MonitorInfo {
+    pub _index: u32,
+    pub _name: String,
+    pub _position: (i32, i32),
+    /// Physical size of the monitor in millimetres.
+    pub _physical_size: (i32, i32),
+    pub _current_mode: Option<VideoMode>,
+    pub _video_modes: Vec<VideoMode>,
+}
+
+/// OpenGL profile requested for the context.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GlProfile {
+    Core,
+    Compatibility,
+    Any,
+}
+
+/// Context and framebuffer hints given to GLFW when building a window. The fields left as `None`
+/// are left to the platform to choose. The profile is only requested along with a version of 3.2
+/// or later.
+#[derive(Copy, Clone, Debug)]
+pub struct ContextConfig {
+    pub _gl_version: Option<(u32, u32)>,
+    pub _profile: GlProfile,
+    pub _debug: bool,
+    pub _samples: Option<u32>,
+    pub _depth_bits: Option<u32>,
+    pub _stencil_bits: Option<u32>,
+    pub _srgb: bool,
+    pub _swap_interval: u32,
+    pub _error_checking: bool,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        return ContextConfig {
+            _gl_version: None,
+            _profile: GlProfile::Core,
+            _debug: false,
+            _samples: None,
+            _depth_bits: Some(24),
+            _stencil_bits: Some(8),
+            _srgb: false,
+            _swap_interval: 1,
+            _error_checking: false,
+        };
+    }
+}
+
+/// Properties of the context that was actually obtained, which may differ from the requested
+/// ones depending on the driver.
+#[derive(Clone, Debug, Default)]
+pub struct ContextInfo {
+    pub _gl_version: (u32, u32, u32),
+    pub _profile: Option<GlProfile>,
+    pub _debug: bool,
+    pub _forward_compat: bool,
+    pub _samples: u32,
+    pub _depth_bits: u32,
+    pub _stencil_bits: u32,
+    pub _srgb: bool,
+    pub _vendor: String,
+    pub _renderer: String,
+    pub _glsl_version: String,
+}
+
+impl std::fmt::Display for ContextInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (major, minor, revision) = self._gl_version;
+        let profile = match self._profile {
+            Some(GlProfile::Core) => "core",
+            Some(GlProfile::Compatibility) => "compatibility",
+            Some(GlProfile::Any) | None => "unknown",
+        };
+        writeln!(f, "OpenGL {major}.{minor}.{revision} ({profile} profile)")?;
+        writeln!(f, "Renderer: {} ({})", self._renderer, self._vendor)?;
+        writeln!(f, "GLSL: {}", self._glsl_version)?;
+        write!(
+            f,
+            "Debug: {}, samples: {}, depth: {} bits, stencil: {} bits, sRGB: {}",
+            self._debug, self._samples, self._depth_bits, self._stencil_bits, self._srgb
+        )
+    }
+}
+
 pub struct Window {
     _width: u32,
     _height: u32,
     _title: String,
     _mode: WindowMode,
+    _context_info: ContextInfo,
+    _framebuffer_size: (u32, u32),
+    _resized: bool,
+    _windowed_rect: (i32, i32, u32, u32),
+    _fullscreen_monitor: u32,
+    _input: InputState,
+    _gamepads: Gamepads,
     _glfw_window: glfw::Window,
     _events: Receiver<(f64, glfw::WindowEvent)>,
     _glfw_instance: glfw::Glfw,
 }
 
-impl Window {
-    pub fn new() -> Window {
-        let empty_instance = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
-        let (window, receiver) = empty_instance
-            .create_window(1, 1, "", glfw::WindowMode::Windowed)
-            .expect("");
-        return Window {
+/// Configuration of a window, which is created by `build`. GLFW is only initialized once, so
+/// any number of windows can be built, and they can share their OpenGL objects by building
+/// them with `shared_with`.
+pub struct WindowBuilder<'a> {
+    _width: u32,
+    _height: u32,
+    _title: String,
+    _mode: WindowMode,
+    _context_config: ContextConfig,
+    _fullscreen_monitor: u32,
+    _shared: Option<&'a Window>,
+}
+
+impl<'a> WindowBuilder<'a> {
+    pub fn new() -> WindowBuilder<'a> {
+        return WindowBuilder {
             _width: 640,
             _height: 480,
             _title: "Hello World!".to_string(),
             _mode: WindowMode::Windowed,
-            _glfw_window: window,
-            _events: receiver,
-            _glfw_instance: empty_instance,
+            _context_config: ContextConfig::default(),
+            _fullscreen_monitor: 0,
+            _shared: None,
         };
     }
 
-    pub fn width(mut self, width: u32) -> Window {
+    pub fn width(mut self, width: u32) -> WindowBuilder<'a> {
         self._width = width;
         return self;
     }
 
-    pub fn height(mut self, height: u32) -> Window {
+    pub fn height(mut self, height: u32) -> WindowBuilder<'a> {
         self._height = height;
         return self;
     }
 
-    pub fn dimensions(mut self, width: u32, height: u32) -> Window {
+    pub fn dimensions(mut self, width: u32, height: u32) -> WindowBuilder<'a> {
         self._width = width;
         self._height = height;
         return self;
     }
 
-    pub fn title(mut self, title: &str) -> Window {
+    pub fn title(mut self, title: &str) -> WindowBuilder<'a> {
         self._title = title.to_string();
         return self;
     }
 
-    pub fn mode(mut self, mode: WindowMode) -> Window {
+    pub fn mode(mut self, mode: WindowMode) -> WindowBuilder<'a> {
         self._mode = mode;
+        if let WindowMode::FullScreen(monitor) = mode {
+            self._fullscreen_monitor = monitor;
+        }
         return self;
     }
 
-    pub fn windowed(mut self) -> Window {
+    pub fn windowed(mut self) -> WindowBuilder<'a> {
         self._mode = WindowMode::Windowed;
         return self;
     }
 
-    pub fn fullscreen(mut self, monitor: u32) -> Window {
+    pub fn fullscreen(mut self, monitor: u32) -> WindowBuilder<'a> {
         self._mode = WindowMode::FullScreen(monitor);
+        self._fullscreen_monitor = monitor;
+        return self;
+    }
+
+    /// Replaces all the context hints at once.
+    pub fn context(mut self, config: ContextConfig) -> WindowBuilder<'a> {
+        self._context_config = config;
+        return self;
+    }
+
+    /// Requests a given OpenGL version, along with the profile. By default no version is
+    /// requested, and the platform picks one (on macOS a legacy 2.1 context, as its core contexts
+    /// must be asked for as 3.2 to 4.1).
+    pub fn gl_version(mut self, major: u32, minor: u32) -> WindowBuilder<'a> {
+        self._context_config._gl_version = Some((major, minor));
+        return self;
+    }
+
+    pub fn profile(mut self, profile: GlProfile) -> WindowBuilder<'a> {
+        self._context_config._profile = profile;
+        return self;
+    }
+
+    pub fn core_profile(mut self) -> WindowBuilder<'a> {
+        self._context_config._profile = GlProfile::Core;
         return self;
     }
 
-    pub fn build(mut self) -> Window {
-        let (mut window, events, glfw_inst) =
-            init_glfw(self._width, self._height, &self._title, self._mode);
-        init_gl(&mut window);
-        self._glfw_window = window;
-        self._events = events;
-        self._glfw_instance = glfw_inst;
+    pub fn compat_profile(mut self) -> WindowBuilder<'a> {
+        self._context_config._profile = GlProfile::Compatibility;
         return self;
     }
 
+    /// Requests a debug context, whose messages are forwarded to `log` once the window is built.
+    pub fn debug_context(mut self, debug: bool) -> WindowBuilder<'a> {
+        self._context_config._debug = debug;
+        return self;
+    }
+
+    /// Number of MSAA samples of the default framebuffer. Zero disables multisampling.
+    pub fn samples(mut self, samples: u32) -> WindowBuilder<'a> {
+        self._context_config._samples = Some(samples);
+        return self;
+    }
+
+    pub fn depth_bits(mut self, bits: Option<u32>) -> WindowBuilder<'a> {
+        self._context_config._depth_bits = bits;
+        return self;
+    }
+
+    pub fn stencil_bits(mut self, bits: Option<u32>) -> WindowBuilder<'a> {
+        self._context_config._stencil_bits = bits;
+        return self;
+    }
+
+    /// Requests an sRGB capable framebuffer and enables `GL_FRAMEBUFFER_SRGB` on it.
+    pub fn srgb(mut self, srgb: bool) -> WindowBuilder<'a> {
+        self._context_config._srgb = srgb;
+        return self;
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> WindowBuilder<'a> {
+        self._context_config._swap_interval = vsync as u32;
+        return self;
+    }
+
+    /// Number of screen updates to wait for before swapping the buffers.
+    pub fn swap_interval(mut self, interval: u32) -> WindowBuilder<'a> {
+        self._context_config._swap_interval = interval;
+        return self;
+    }
+
+    /// Runs `glGetError` after the OpenGL calls made by cuboid, logging the failing function.
+    pub fn error_checking(mut self, enabled: bool) -> WindowBuilder<'a> {
+        self._context_config._error_checking = enabled;
+        return self;
+    }
+
+    /// Makes the context of the new window share its objects (buffers, textures, shaders, ...)
+    /// with the context of another one.
+    pub fn shared_with(mut self, window: &'a Window) -> WindowBuilder<'a> {
+        self._shared = Some(window);
+        return self;
+    }
+
+    /// Creates the window and makes its context the current one.
+    pub fn build(self) -> Window {
+        let (mut glfw_window, events, mut glfw_inst) = init_glfw(
+            self._width,
+            self._height,
+            &self._title,
+            self._mode,
+            &self._context_config,
+            self._shared.map(|w| &w._glfw_window),
+        );
+        init_gl(&mut glfw_window);
+        glfw_inst.set_swap_interval(glfw::SwapInterval::Sync(
+            self._context_config._swap_interval,
+        ));
+        configure_gl(&self._context_config);
+        let context_info = query_context_info(&glfw_window);
+        if context_info._debug {
+            enable_debug_output();
+        }
+        set_error_checking(self._context_config._error_checking);
+        log::info!("{}", context_info);
+
+        let (x, y) = glfw_window.get_pos();
+        let mut window = Window {
+            _width: self._width,
+            _height: self._height,
+            _title: self._title,
+            _mode: self._mode,
+            _context_info: context_info,
+            _framebuffer_size: (self._width, self._height),
+            _resized: false,
+            _windowed_rect: (x, y, self._width, self._height),
+            _fullscreen_monitor: self._fullscreen_monitor,
+            _input: InputState::new(),
+            _gamepads: Gamepads::new(Box::new(GlfwGamepads::new(glfw_inst.clone()))),
+            _glfw_window: glfw_window,
+            _events: events,
+            _glfw_instance: glfw_inst,
+        };
+        window.update_size();
+        set_viewport(window._framebuffer_size);
+        return window;
+    }
+}
+
+impl Window {
+    /// Starts the configuration of a window, the same as `WindowBuilder::new`.
+    pub fn new<'a>() -> WindowBuilder<'a> {
+        return WindowBuilder::new();
+    }
+
+    /// Makes the context of this window the current one, so that the following OpenGL calls
+    /// act on it.
+    pub fn make_current(&mut self) {
+        self._glfw_window.make_current();
+    }
+
+    /// Properties of the context obtained when building the window.
+    pub fn context_info(&self) -> &ContextInfo {
+        return &self._context_info;
+    }
+
     // The following are functions delegated to the fields of the struct
 
     pub fn should_close(&self) -> bool {
@@ -99,9 +375,216 @@ impl Window {
         self._glfw_window.set_should_close(condition);
     }
 
-    pub fn poll_events(&mut self) -> &Receiver<(f64, glfw::WindowEvent)> {
+    /// Polls the events of the window, updating its input state and returning them along with
+    /// their time. If the framebuffer was resized, the viewport of this window's context is
+    /// updated to cover all of it and `was_resized` returns true until the next poll.
+    pub fn poll_events(&mut self) -> Vec<(f64, glfw::WindowEvent)> {
         self._glfw_instance.poll_events();
-        return &self._events;
+        let events: Vec<(f64, glfw::WindowEvent)> = glfw::flush_messages(&self._events).collect();
+        self._input.begin_frame();
+        for (_, event) in events.iter() {
+            self._input.handle_event(event);
+        }
+        self._gamepads.poll();
+        let primary = self._gamepads.primary();
+        self._input
+            .update_gamepad(primary.and_then(|id| self._gamepads.state(id)));
+
+        let previous = self._framebuffer_size;
+        self.update_size();
+        self._resized = self._framebuffer_size != previous;
+        if self._resized {
+            // The viewport belongs to the context, which may not be the current one when
+            // several windows are open
+            let previous = unsafe { glfw::ffi::glfwGetCurrentContext() };
+            self._glfw_window.make_current();
+            set_viewport(self._framebuffer_size);
+            unsafe {
+                glfw::ffi::glfwMakeContextCurrent(previous);
+            }
+        }
+        return events;
+    }
+
+    /// State of the keyboard, mouse and gamepad as of the last call to `poll_events`.
+    pub fn input(&self) -> &InputState {
+        return &self._input;
+    }
+
+    /// The gamepads, whose changes since the last poll are available through `events`.
+    pub fn gamepads(&self) -> &Gamepads {
+        return &self._gamepads;
+    }
+
+    pub fn gamepads_mut(&mut self) -> &mut Gamepads {
+        return &mut self._gamepads;
+    }
+
+    /// Replaces where the gamepads are read from, GLFW being the default.
+    pub fn set_gamepad_source(&mut self, source: Box<dyn GamepadSource>) {
+        self._gamepads.set_source(source);
+    }
+
+    /// Adds SDL_GameControllerDB mappings, for gamepads GLFW doesn't recognize.
+    pub fn update_gamepad_mappings(&self, mappings: &str) -> bool {
+        return self._glfw_instance.update_gamepad_mappings(mappings);
+    }
+
+    /// Whether the framebuffer changed its size during the last call to `poll_events`.
+    pub fn was_resized(&self) -> bool {
+        return self._resized;
+    }
+
+    /// Size of the window in screen coordinates.
+    pub fn get_size(&self) -> (u32, u32) {
+        return (self._width, self._height);
+    }
+
+    /// Size of the framebuffer in pixels, which differs from the size of the window on high DPI
+    /// screens.
+    pub fn get_framebuffer_size(&self) -> (u32, u32) {
+        return self._framebuffer_size;
+    }
+
+    /// Aspect ratio (width over height) of the framebuffer, to be given to the cameras.
+    pub fn aspect_ratio(&self) -> f32 {
+        let (width, height) = self._framebuffer_size;
+        return width as f32 / height.max(1) as f32;
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self._title = title.to_string();
+        self._glfw_window.set_title(title);
+    }
+
+    pub fn get_title(&self) -> &str {
+        return &self._title;
+    }
+
+    /// Resizes the window, in screen coordinates. The viewport follows on the next poll.
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self._glfw_window.set_size(width as i32, height as i32);
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self._glfw_window.set_pos(x, y);
+    }
+
+    /// Position of the upper-left corner of the content area, in screen coordinates.
+    pub fn get_position(&self) -> (i32, i32) {
+        return self._glfw_window.get_pos();
+    }
+
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) {
+        self._glfw_window.set_cursor_mode(match mode {
+            CursorMode::Normal => glfw::CursorMode::Normal,
+            CursorMode::Hidden => glfw::CursorMode::Hidden,
+            CursorMode::Disabled => glfw::CursorMode::Disabled,
+        });
+    }
+
+    pub fn get_cursor_mode(&self) -> CursorMode {
+        return match self._glfw_window.get_cursor_mode() {
+            glfw::CursorMode::Normal => CursorMode::Normal,
+            glfw::CursorMode::Hidden => CursorMode::Hidden,
+            glfw::CursorMode::Disabled => CursorMode::Disabled,
+        };
+    }
+
+    /// Lists the connected monitors, the primary one being the first.
+    pub fn monitors(&mut self) -> Vec<MonitorInfo> {
+        return self._glfw_instance.with_connected_monitors(|_, monitors| {
+            monitors
+                .iter()
+                .enumerate()
+                .map(|(index, monitor)| MonitorInfo {
+                    _index: index as u32,
+                    _name: monitor.get_name().unwrap_or_default(),
+                    _position: monitor.get_pos(),
+                    _physical_size: monitor.get_physical_size(),
+                    _current_mode: monitor.get_video_mode().map(VideoMode::from),
+                    _video_modes: monitor
+                        .get_video_modes()
+                        .into_iter()
+                        .map(VideoMode::from)
+                        .collect(),
+                })
+                .collect()
+        });
+    }
+
+    pub fn get_mode(&self) -> WindowMode {
+        return self._mode;
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        return matches!(self._mode, WindowMode::FullScreen(_));
+    }
+
+    /// Makes the window fullscreen on the given monitor, keeping its current video mode.
+    pub fn set_fullscreen(&mut self, monitor: u32) {
+        self.set_fullscreen_mode(monitor, None);
+    }
+
+    /// Makes the window fullscreen on the given monitor, switching it to a video mode. When no
+    /// mode is given, the current one of the monitor is kept.
+    pub fn set_fullscreen_mode(&mut self, monitor: u32, mode: Option<VideoMode>) {
+        if !self.is_fullscreen() {
+            let (x, y) = self._glfw_window.get_pos();
+            self._windowed_rect = (x, y, self._width, self._height);
+        }
+        let window = &mut self._glfw_window;
+        let found = self._glfw_instance.with_connected_monitors(|_, monitors| {
+            match monitors.get(monitor as usize) {
+                Some(m) => {
+                    let current = m.get_video_mode().map(VideoMode::from);
+                    let mode = mode.or(current).expect("Monitor has no video mode.");
+                    window.set_monitor(
+                        glfw::WindowMode::FullScreen(m),
+                        0,
+                        0,
+                        mode._width,
+                        mode._height,
+                        Some(mode._refresh_rate),
+                    );
+                    true
+                }
+                None => false,
+            }
+        });
+        if found {
+            self._mode = WindowMode::FullScreen(monitor);
+            self._fullscreen_monitor = monitor;
+        } else {
+            log::warn!("Monitor {monitor} not found, the window mode was not changed.");
+        }
+    }
+
+    /// Returns to windowed mode, restoring the position and size from before going fullscreen.
+    pub fn set_windowed(&mut self) {
+        let (x, y, width, height) = self._windowed_rect;
+        self._glfw_window
+            .set_monitor(glfw::WindowMode::Windowed, x, y, width, height, None);
+        self._mode = WindowMode::Windowed;
+    }
+
+    /// Switches between windowed mode and fullscreen on the primary monitor, or on the last
+    /// monitor used.
+    pub fn toggle_fullscreen(&mut self) {
+        match self._mode {
+            WindowMode::FullScreen(_) => self.set_windowed(),
+            WindowMode::Windowed => {
+                self.set_fullscreen(self._fullscreen_monitor);
+            }
+        }
+    }
+
+    fn update_size(&mut self) {
+        let (width, height) = self._glfw_window.get_size();
+        let (fb_width, fb_height) = self._glfw_window.get_framebuffer_size();
+        self._width = width.max(0) as u32;
+        self._height = height.max(0) as u32;
+        self._framebuffer_size = (fb_width.max(0) as u32, fb_height.max(0) as u32);
     }
 
     pub fn swap_buffers(&mut self) {
@@ -119,41 +602,75 @@ fn init_glfw(
     height: u32,
     title: &str,
     mode: WindowMode,
+    config: &ContextConfig,
+    share: Option<&glfw::Window>,
 ) -> (glfw::Window, Receiver<(f64, glfw::WindowEvent)>, glfw::Glfw) {
-    println!("{}", glfw::get_version_string());
-
-    let mut glfw_inst = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
+    // Shared windows reuse the instance of the window they share their context with
+    let mut glfw_inst = match share {
+        Some(window) => window.glfw.clone(),
+        None => {
+            log::info!("GLFW {}", glfw::get_version_string());
+            glfw::init(glfw::FAIL_ON_ERRORS).unwrap()
+        }
+    };
 
+    glfw_inst.default_window_hints();
     glfw_inst.window_hint(glfw::WindowHint::CenterCursor(true));
-    // glfw_inst.window_hint(glfw::WindowHint::ContextVersion(4, 2));
-    // glfw_inst.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
-    // glfw_inst.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
-
-    println!("{}", glfw::get_version_string());
+    if let Some((major, minor)) = config._gl_version {
+        glfw_inst.window_hint(glfw::WindowHint::ContextVersion(major, minor));
+    }
+    // Profiles only exist from OpenGL 3.2 onwards
+    if config._gl_version.is_some_and(|version| version >= (3, 2)) {
+        let profile = match config._profile {
+            GlProfile::Core => glfw::OpenGlProfileHint::Core,
+            GlProfile::Compatibility => glfw::OpenGlProfileHint::Compat,
+            GlProfile::Any => glfw::OpenGlProfileHint::Any,
+        };
+        glfw_inst.window_hint(glfw::WindowHint::OpenGlProfile(profile));
+        // Required by macOS for core contexts, and harmless elsewhere
+        glfw_inst.window_hint(glfw::WindowHint::OpenGlForwardCompat(
+            config._profile == GlProfile::Core,
+        ));
+    }
+    glfw_inst.window_hint(glfw::WindowHint::OpenGlDebugContext(config._debug));
+    glfw_inst.window_hint(glfw::WindowHint::Samples(config._samples));
+    glfw_inst.window_hint(glfw::WindowHint::DepthBits(config._depth_bits));
+    glfw_inst.window_hint(glfw::WindowHint::StencilBits(config._stencil_bits));
+    glfw_inst.window_hint(glfw::WindowHint::SRgbCapable(config._srgb));
 
+    let create = |temp_glfw: &mut glfw::Glfw, mode: glfw::WindowMode| match share {
+        Some(window) => window.create_shared(width, height, title, mode),
+        None => temp_glfw.create_window(width, height, title, mode),
+    };
     let (mut window, events) = match mode {
-        WindowMode::Windowed => glfw_inst
-            .create_window(width, height, title, glfw::WindowMode::Windowed)
-            .expect("Error creating GLFW window"),
-        WindowMode::FullScreen(_) => glfw_inst
-            .with_primary_monitor(|temp_glfw, m| {
-                temp_glfw.create_window(
-                    width,
-                    height,
-                    title,
-                    m.map_or(glfw::WindowMode::Windowed, |m| {
-                        glfw::WindowMode::FullScreen(m)
-                    }),
+        WindowMode::Windowed => create(&mut glfw_inst, glfw::WindowMode::Windowed),
+        WindowMode::FullScreen(index) => {
+            glfw_inst.with_connected_monitors(|temp_glfw, monitors| {
+                let monitor = monitors.get(index as usize);
+                if monitor.is_none() {
+                    log::warn!("Monitor {index} not found, opening the window as windowed.");
+                }
+                create(
+                    temp_glfw,
+                    monitor.map_or(glfw::WindowMode::Windowed, glfw::WindowMode::FullScreen),
                 )
             })
-            .expect("Error creating GLFW window"),
-    };
+        }
+    }
+    .expect("Error creating GLFW window");
 
     window.set_key_polling(true);
+    window.set_char_polling(true);
     window.set_cursor_mode(glfw::CursorMode::Normal);
     window.set_cursor_enter_polling(true);
     window.set_mouse_button_polling(true);
     window.set_cursor_pos_polling(true);
+    window.set_scroll_polling(true);
+    window.set_size_polling(true);
+    window.set_framebuffer_size_polling(true);
+    window.set_focus_polling(true);
+    window.set_close_polling(true);
+    window.set_drag_and_drop_polling(true);
     window.make_current();
 
     return (window, events, glfw_inst);
@@ -169,6 +686,103 @@ pub fn init_gl(window: &mut glfw::Window) {
     enable_depth_test();
 }
 
+/// Enables the capabilities matching the framebuffer options of the context.
+fn configure_gl(config: &ContextConfig) {
+    unsafe {
+        if config._samples.unwrap_or(0) > 0 {
+            gl::Enable(gl::MULTISAMPLE);
+        }
+        if config._srgb {
+            gl::Enable(gl::FRAMEBUFFER_SRGB);
+        }
+    }
+}
+
+/// Asks GLFW and OpenGL for the properties of the current context.
+fn query_context_info(window: &glfw::Window) -> ContextInfo {
+    let version = window.get_context_version();
+    let profile = match window.get_opengl_profile() {
+        glfw::ffi::OPENGL_CORE_PROFILE => Some(GlProfile::Core),
+        glfw::ffi::OPENGL_COMPAT_PROFILE => Some(GlProfile::Compatibility),
+        _ => None,
+    };
+
+    let mut samples = 0;
+    let mut depth_bits = 0;
+    let mut stencil_bits = 0;
+    let mut encoding = gl::LINEAR as i32;
+    unsafe {
+        gl::GetIntegerv(gl::SAMPLES, &mut samples);
+        // The sizes of the default framebuffer are queried per attachment, as GL_DEPTH_BITS
+        // and friends were removed from the core profile. Querying a buffer the framebuffer
+        // lacks is an error, so its type is checked first
+        let attachment = |name, parameter, value: &mut i32| {
+            let mut object_type = gl::NONE as i32;
+            gl::GetFramebufferAttachmentParameteriv(
+                gl::FRAMEBUFFER,
+                name,
+                gl::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+                &mut object_type,
+            );
+            if object_type as u32 != gl::NONE {
+                gl::GetFramebufferAttachmentParameteriv(gl::FRAMEBUFFER, name, parameter, value);
+            }
+        };
+        attachment(
+            gl::DEPTH,
+            gl::FRAMEBUFFER_ATTACHMENT_DEPTH_SIZE,
+            &mut depth_bits,
+        );
+        attachment(
+            gl::STENCIL,
+            gl::FRAMEBUFFER_ATTACHMENT_STENCIL_SIZE,
+            &mut stencil_bits,
+        );
+        attachment(
+            gl::BACK_LEFT,
+            gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING,
+            &mut encoding,
+        );
+    }
+
+    return ContextInfo {
+        _gl_version: (
+            version.major as u32,
+            version.minor as u32,
+            version.patch as u32,
+        ),
+        _profile: profile,
+        _debug: window.is_opengl_debug_context(),
+        _forward_compat: window.is_opengl_forward_compat(),
+        _samples: samples as u32,
+        _depth_bits: depth_bits as u32,
+        _stencil_bits: stencil_bits as u32,
+        _srgb: encoding as u32 == gl::SRGB,
+        _vendor: get_gl_string(gl::VENDOR),
+        _renderer: get_gl_string(gl::RENDERER),
+        _glsl_version: get_gl_string(gl::SHADING_LANGUAGE_VERSION),
+    };
+}
+
+fn get_gl_string(name: gl::types::GLenum) -> String {
+    unsafe {
+        let ptr = gl::GetString(name);
+        if ptr.is_null() {
+            return String::new();
+        }
+        return CStr::from_ptr(ptr as *const _)
+            .to_string_lossy()
+            .into_owned();
+    }
+}
+
+/// Makes the viewport cover the whole framebuffer.
+fn set_viewport(size: (u32, u32)) {
+    unsafe {
+        gl::Viewport(0, 0, size.0 as i32, size.1 as i32);
+    }
+}
+
 fn enable_depth_test() {
     unsafe {
         gl::Enable(gl::DEPTH_TEST);
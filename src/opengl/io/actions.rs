@@ -0,0 +1,627 @@
+use crate::opengl::io::InputState;
+use glfw;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Value above which a binding makes its action active, for the analog inputs.
+const ACTIVATION_THRESHOLD: f32 = 0.5;
+
+/// A single input that can be part of a binding.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InputSource {
+    Key(glfw::Key),
+    /// Either the left or the right key of a modifier.
+    Modifier(glfw::Modifiers),
+    MouseButton(glfw::MouseButton),
+    /// Movement of the cursor along the horizontal axis, in screen coordinates per frame.
+    MouseX,
+    MouseY,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+    GamepadButton(glfw::GamepadButton),
+    GamepadAxis(glfw::GamepadAxis),
+}
+
+impl std::fmt::Display for InputSource {
+    /// Writes the name of the input as read by `InputSource::parse`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match *self {
+            InputSource::Key(key) => write!(f, "{}", name_of(KEYS, key)),
+            InputSource::Modifier(glfw::Modifiers::Control) => write!(f, "Ctrl"),
+            InputSource::Modifier(glfw::Modifiers::Alt) => write!(f, "Alt"),
+            InputSource::Modifier(glfw::Modifiers::Super) => write!(f, "Super"),
+            InputSource::Modifier(_) => write!(f, "Shift"),
+            InputSource::MouseButton(button) => match button {
+                glfw::MouseButtonLeft => write!(f, "MouseLeft"),
+                glfw::MouseButtonRight => write!(f, "MouseRight"),
+                glfw::MouseButtonMiddle => write!(f, "MouseMiddle"),
+                _ => write!(f, "Mouse{}", button as i32 + 1),
+            },
+            InputSource::MouseX => write!(f, "MouseX"),
+            InputSource::MouseY => write!(f, "MouseY"),
+            InputSource::ScrollUp => write!(f, "ScrollUp"),
+            InputSource::ScrollDown => write!(f, "ScrollDown"),
+            InputSource::ScrollLeft => write!(f, "ScrollLeft"),
+            InputSource::ScrollRight => write!(f, "ScrollRight"),
+            InputSource::GamepadButton(button) => {
+                write!(f, "Gamepad{}", name_of(GAMEPAD_BUTTONS, button))
+            }
+            InputSource::GamepadAxis(axis) => write!(f, "Gamepad{}", name_of(GAMEPAD_AXES, axis)),
+        };
+    }
+}
+
+impl InputSource {
+    /// Current value of the input, being 0 or 1 for keys and buttons.
+    pub fn value(&self, input: &InputState) -> f32 {
+        let from_bool = |down: bool| if down { 1.0 } else { 0.0 };
+        let (scroll_x, scroll_y) = input.scroll_delta();
+        return match *self {
+            InputSource::Key(key) => from_bool(input.is_key_down(key)),
+            InputSource::Modifier(modifier) => {
+                let (left, right) = modifier_keys(modifier);
+                from_bool(input.is_key_down(left) || input.is_key_down(right))
+            }
+            InputSource::MouseButton(button) => from_bool(input.is_mouse_down(button)),
+            InputSource::MouseX => input.cursor_delta().0 as f32,
+            InputSource::MouseY => input.cursor_delta().1 as f32,
+            InputSource::ScrollUp => scroll_y.max(0.0) as f32,
+            InputSource::ScrollDown => (-scroll_y).max(0.0) as f32,
+            InputSource::ScrollLeft => (-scroll_x).max(0.0) as f32,
+            InputSource::ScrollRight => scroll_x.max(0.0) as f32,
+            InputSource::GamepadButton(button) => from_bool(input.is_gamepad_button_down(button)),
+            InputSource::GamepadAxis(axis) => input.gamepad_axis(axis),
+        };
+    }
+
+    /// Parses the name of an input, as used in the configuration files. The names of the keys
+    /// are the ones of `glfw::Key` (`W`, `Space`, `LeftShift`, `Num1`...), the mouse buttons are
+    /// `MouseLeft`, `MouseRight`, `MouseMiddle` or `Mouse1` to `Mouse8`, and the gamepad inputs
+    /// are prefixed by `Gamepad` (`GamepadA`, `GamepadDpadUp`, `GamepadLeftX`...). The names are
+    /// not case sensitive.
+    pub fn parse(name: &str) -> Option<InputSource> {
+        let lower = name.to_lowercase();
+        let source = match lower.as_str() {
+            "shift" => InputSource::Modifier(glfw::Modifiers::Shift),
+            "ctrl" | "control" => InputSource::Modifier(glfw::Modifiers::Control),
+            "alt" => InputSource::Modifier(glfw::Modifiers::Alt),
+            "super" => InputSource::Modifier(glfw::Modifiers::Super),
+            "mouseleft" => InputSource::MouseButton(glfw::MouseButtonLeft),
+            "mouseright" => InputSource::MouseButton(glfw::MouseButtonRight),
+            "mousemiddle" => InputSource::MouseButton(glfw::MouseButtonMiddle),
+            "mousex" => InputSource::MouseX,
+            "mousey" => InputSource::MouseY,
+            "scrollup" => InputSource::ScrollUp,
+            "scrolldown" => InputSource::ScrollDown,
+            "scrollleft" => InputSource::ScrollLeft,
+            "scrollright" => InputSource::ScrollRight,
+            _ => {
+                if let Some(number) = lower.strip_prefix("mouse") {
+                    let index = number.parse::<i32>().ok()?;
+                    return glfw::MouseButton::from_i32(index - 1).map(InputSource::MouseButton);
+                }
+                if let Some(gamepad) = lower.strip_prefix("gamepad") {
+                    return find(GAMEPAD_BUTTONS, gamepad)
+                        .map(InputSource::GamepadButton)
+                        .or_else(|| find(GAMEPAD_AXES, gamepad).map(InputSource::GamepadAxis));
+                }
+                // Digits can be written directly instead of `Num0` to `Num9`
+                let key_name = match lower.len() == 1 && lower.as_bytes()[0].is_ascii_digit() {
+                    true => format!("num{lower}"),
+                    false => lower,
+                };
+                return find(KEYS, &key_name).map(InputSource::Key);
+            }
+        };
+        return Some(source);
+    }
+}
+
+/// A combination of inputs triggering an action or moving an axis. All the inputs but the
+/// last one must be held, and the value of the binding is the one of the last input times the
+/// scale.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Binding {
+    pub _chord: Vec<InputSource>,
+    pub _scale: f32,
+}
+
+impl std::fmt::Display for Binding {
+    /// Writes the binding as read by `Binding::parse`.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self._scale < 0.0 {
+            write!(f, "-")?;
+        }
+        let chord: Vec<String> = self._chord.iter().map(|input| input.to_string()).collect();
+        write!(f, "{}", chord.join("+"))?;
+        if self._scale.abs() != 1.0 {
+            write!(f, "*{}", self._scale.abs())?;
+        }
+        return Ok(());
+    }
+}
+
+impl Binding {
+    pub fn new(input: InputSource) -> Binding {
+        return Binding {
+            _chord: vec![input],
+            _scale: 1.0,
+        };
+    }
+
+    /// Requires another input to be held along with the main one, such as a modifier.
+    pub fn with(mut self, input: InputSource) -> Binding {
+        self._chord.insert(self._chord.len() - 1, input);
+        return self;
+    }
+
+    pub fn scale(mut self, scale: f32) -> Binding {
+        self._scale = scale;
+        return self;
+    }
+
+    /// Parses a binding such as `W`, `Ctrl+S`, `-A` (negative, for axes) or `MouseX*0.2`.
+    pub fn parse(text: &str) -> Option<Binding> {
+        let mut text = text.trim();
+        let mut scale = 1.0;
+        if let Some(rest) = text.strip_prefix('-') {
+            scale = -1.0;
+            text = rest;
+        }
+        if let Some((inputs, factor)) = text.split_once('*') {
+            scale *= factor.trim().parse::<f32>().ok()?;
+            text = inputs;
+        }
+        let chord = text
+            .split('+')
+            .map(|name| InputSource::parse(name.trim()))
+            .collect::<Option<Vec<InputSource>>>()?;
+        if chord.is_empty() {
+            return None;
+        }
+        return Some(Binding {
+            _chord: chord,
+            _scale: scale,
+        });
+    }
+
+    pub fn value(&self, input: &InputState) -> f32 {
+        let (last, held) = self._chord.split_last().unwrap();
+        if held
+            .iter()
+            .any(|source| source.value(input) < ACTIVATION_THRESHOLD)
+        {
+            return 0.0;
+        }
+        return last.value(input) * self._scale;
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=======================================| Action map |========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Maps named actions and axes to inputs, so that controllers don't depend on specific keys.
+/// Actions are either active or not, while axes add up the values of their bindings. The map
+/// has to be updated once per frame, after polling the events of the window.
+#[derive(Clone, Debug)]
+pub struct ActionMap {
+    _actions: HashMap<String, Vec<Binding>>,
+    _axes: HashMap<String, Vec<Binding>>,
+    _active: HashSet<String>,
+    _previous: HashSet<String>,
+    _axis_values: HashMap<String, f32>,
+}
+
+impl ActionMap {
+    pub fn new() -> ActionMap {
+        return ActionMap {
+            _actions: HashMap::new(),
+            _axes: HashMap::new(),
+            _active: HashSet::new(),
+            _previous: HashSet::new(),
+            _axis_values: HashMap::new(),
+        };
+    }
+
+    /// Binds an action to the given inputs, written as in `Binding::parse`.
+    pub fn action(mut self, name: &str, bindings: &[&str]) -> ActionMap {
+        for binding in bindings {
+            self.bind_action(name, parse_binding(binding));
+        }
+        return self;
+    }
+
+    /// Binds an axis to the given inputs, written as in `Binding::parse`.
+    pub fn axis(mut self, name: &str, bindings: &[&str]) -> ActionMap {
+        for binding in bindings {
+            self.bind_axis(name, parse_binding(binding));
+        }
+        return self;
+    }
+
+    pub fn bind_action(&mut self, name: &str, binding: Binding) {
+        self._actions
+            .entry(name.to_string())
+            .or_default()
+            .push(binding);
+    }
+
+    pub fn bind_axis(&mut self, name: &str, binding: Binding) {
+        self._axes
+            .entry(name.to_string())
+            .or_default()
+            .push(binding);
+    }
+
+    /// Removes every binding of an action or axis, to remap it.
+    pub fn unbind(&mut self, name: &str) {
+        self._actions.remove(name);
+        self._axes.remove(name);
+    }
+
+    pub fn get_action_bindings(&self, name: &str) -> &[Binding] {
+        return self._actions.get(name).map_or(&[], |b| b);
+    }
+
+    pub fn get_axis_bindings(&self, name: &str) -> &[Binding] {
+        return self._axes.get(name).map_or(&[], |b| b);
+    }
+
+    /// Reads the bindings from a configuration file. See `parse_config` for the format.
+    pub fn from_config(path: &str) -> ActionMap {
+        let content = fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Error reading bindings file {path}."));
+        return ActionMap::parse_config(&content);
+    }
+
+    /// Parses bindings written in a subset of TOML, with an `[actions]` and an `[axes]` table
+    /// whose keys are the names and whose values are a binding or an array of them:
+    ///
+    /// ```toml
+    /// [actions]
+    /// move_forward = ["W", "Up", "GamepadDpadUp"]
+    /// save = "Ctrl+S"
+    ///
+    /// [axes]
+    /// move_x = ["D", "-A", "GamepadLeftX"]
+    /// look_x = "MouseX*0.1"
+    /// ```
+    pub fn parse_config(content: &str) -> ActionMap {
+        let mut map = ActionMap::new();
+        let mut section = String::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: &str| -> ! {
+                panic!("Bindings line {}: {message}.", number + 1);
+            };
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let (name, value) = match line.split_once('=') {
+                Some((name, value)) => (name.trim().trim_matches('"'), value.trim()),
+                None => error("expected 'name = value'"),
+            };
+            let values = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                Some(list) => list
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .collect(),
+                None => vec![value],
+            };
+            for value in values {
+                let text = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    Some(text) => text,
+                    None => error("bindings must be quoted strings"),
+                };
+                let binding = Binding::parse(text)
+                    .unwrap_or_else(|| error(&format!("invalid binding '{text}'")));
+                match section.as_str() {
+                    "actions" => map.bind_action(name, binding),
+                    "axes" => map.bind_axis(name, binding),
+                    _ => error(&format!("unknown section '{section}'")),
+                }
+            }
+        }
+        return map;
+    }
+
+    /// Writes the bindings in the format read by `parse_config`, sorted by name.
+    pub fn to_config(&self) -> String {
+        let mut config = String::new();
+        for (section, table) in [("actions", &self._actions), ("axes", &self._axes)] {
+            if table.is_empty() {
+                continue;
+            }
+            if !config.is_empty() {
+                config.push('\n');
+            }
+            config.push_str(&format!("[{section}]\n"));
+            let mut names: Vec<&String> = table.keys().collect();
+            names.sort();
+            for name in names {
+                let bindings: Vec<String> =
+                    table[name].iter().map(|b| format!("\"{b}\"")).collect();
+                config.push_str(&format!("{name} = [{}]\n", bindings.join(", ")));
+            }
+        }
+        return config;
+    }
+
+    /// Evaluates the bindings with the current state of the input.
+    pub fn update(&mut self, input: &InputState) {
+        self._previous = std::mem::take(&mut self._active);
+        for (name, bindings) in self._actions.iter() {
+            if bindings
+                .iter()
+                .any(|b| b.value(input) >= ACTIVATION_THRESHOLD)
+            {
+                self._active.insert(name.clone());
+            }
+        }
+        for (name, bindings) in self._axes.iter() {
+            let value = bindings.iter().map(|b| b.value(input)).sum();
+            self._axis_values.insert(name.clone(), value);
+        }
+    }
+
+    /// Whether any of the bindings of the action is held.
+    pub fn is_active(&self, name: &str) -> bool {
+        return self._active.contains(name);
+    }
+
+    /// Whether the action became active in the last update.
+    pub fn is_pressed(&self, name: &str) -> bool {
+        return self._active.contains(name) && !self._previous.contains(name);
+    }
+
+    /// Whether the action stopped being active in the last update.
+    pub fn is_released(&self, name: &str) -> bool {
+        return !self._active.contains(name) && self._previous.contains(name);
+    }
+
+    /// Sum of the values of the bindings of an axis, or 0 if it doesn't exist.
+    pub fn axis_value(&self, name: &str) -> f32 {
+        return *self._axis_values.get(name).unwrap_or(&0.0);
+    }
+}
+
+fn parse_binding(text: &str) -> Binding {
+    return Binding::parse(text).unwrap_or_else(|| panic!("Invalid binding '{text}'."));
+}
+
+fn strip_comment(line: &str) -> &str {
+    // Comments can't start inside a string, as bindings may not contain '#'
+    return line.split('#').next().unwrap_or("");
+}
+
+fn modifier_keys(modifier: glfw::Modifiers) -> (glfw::Key, glfw::Key) {
+    return match modifier {
+        glfw::Modifiers::Control => (glfw::Key::LeftControl, glfw::Key::RightControl),
+        glfw::Modifiers::Alt => (glfw::Key::LeftAlt, glfw::Key::RightAlt),
+        glfw::Modifiers::Super => (glfw::Key::LeftSuper, glfw::Key::RightSuper),
+        _ => (glfw::Key::LeftShift, glfw::Key::RightShift),
+    };
+}
+
+fn name_of<T: PartialEq>(table: &[(&'static str, T)], value: T) -> &'static str {
+    return table
+        .iter()
+        .find(|(_, entry)| *entry == value)
+        .map_or("", |(name, _)| name);
+}
+
+fn find<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    return table
+        .iter()
+        .find(|(entry, _)| entry.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|==========================================| Names |==========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! keys {
+    ($($variant:ident),* $(,)?) => {
+        &[$((stringify!($variant), glfw::Key::$variant)),*]
+    };
+}
+
+macro_rules! names {
+    ($kind:ident; $($name:literal => $variant:ident),* $(,)?) => {
+        &[$(($name, glfw::$kind::$variant)),*]
+    };
+}
+
+#[rustfmt::skip]
+const KEYS: &[(&str, glfw::Key)] = keys!(
+    Space, Apostrophe, Comma, Minus, Period, Slash, Semicolon, Equal,
+    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    LeftBracket, Backslash, RightBracket, GraveAccent, World1, World2,
+    Escape, Enter, Tab, Backspace, Insert, Delete, Right, Left, Down, Up,
+    PageUp, PageDown, Home, End, CapsLock, ScrollLock, NumLock, PrintScreen, Pause,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13,
+    F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, F25,
+    Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9,
+    KpDecimal, KpDivide, KpMultiply, KpSubtract, KpAdd, KpEnter, KpEqual,
+    LeftShift, LeftControl, LeftAlt, LeftSuper, RightShift, RightControl, RightAlt, RightSuper,
+    Menu,
+);
+
+#[rustfmt::skip]
+const GAMEPAD_BUTTONS: &[(&str, glfw::GamepadButton)] = names!(GamepadButton;
+    "A" => ButtonA, "B" => ButtonB, "X" => ButtonX, "Y" => ButtonY,
+    "LeftBumper" => ButtonLeftBumper, "RightBumper" => ButtonRightBumper,
+    "Back" => ButtonBack, "Start" => ButtonStart, "Guide" => ButtonGuide,
+    "LeftThumb" => ButtonLeftThumb, "RightThumb" => ButtonRightThumb,
+    "DpadUp" => ButtonDpadUp, "DpadRight" => ButtonDpadRight,
+    "DpadDown" => ButtonDpadDown, "DpadLeft" => ButtonDpadLeft,
+);
+
+#[rustfmt::skip]
+const GAMEPAD_AXES: &[(&str, glfw::GamepadAxis)] = names!(GamepadAxis;
+    "LeftX" => AxisLeftX, "LeftY" => AxisLeftY, "RightX" => AxisRightX, "RightY" => AxisRightY,
+    "LeftTrigger" => AxisLeftTrigger, "RightTrigger" => AxisRightTrigger,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_input_names() {
+        assert_eq!(
+            InputSource::parse("w"),
+            Some(InputSource::Key(glfw::Key::W))
+        );
+        assert_eq!(
+            InputSource::parse("1"),
+            Some(InputSource::Key(glfw::Key::Num1))
+        );
+        assert_eq!(
+            InputSource::parse("CTRL"),
+            Some(InputSource::Modifier(glfw::Modifiers::Control))
+        );
+        assert_eq!(
+            InputSource::parse("Mouse4"),
+            Some(InputSource::MouseButton(glfw::MouseButton::Button4))
+        );
+        assert_eq!(
+            InputSource::parse("gamepaddpadup"),
+            Some(InputSource::GamepadButton(
+                glfw::GamepadButton::ButtonDpadUp
+            ))
+        );
+        assert_eq!(
+            InputSource::parse("GamepadRightTrigger"),
+            Some(InputSource::GamepadAxis(
+                glfw::GamepadAxis::AxisRightTrigger
+            ))
+        );
+        assert_eq!(InputSource::parse("Nothing"), None);
+        assert_eq!(InputSource::parse("Mouse9"), None);
+    }
+
+    #[test]
+    fn parses_bindings() {
+        let binding = Binding::parse(" Ctrl + Shift + S ").unwrap();
+        assert_eq!(
+            binding,
+            Binding::new(InputSource::Key(glfw::Key::S))
+                .with(InputSource::Modifier(glfw::Modifiers::Control))
+                .with(InputSource::Modifier(glfw::Modifiers::Shift))
+        );
+        assert_eq!(Binding::parse("-A").unwrap()._scale, -1.0);
+        assert_eq!(Binding::parse("-MouseX*0.5").unwrap()._scale, -0.5);
+    }
+
+    #[test]
+    fn rejects_invalid_bindings() {
+        assert_eq!(Binding::parse(""), None);
+        assert_eq!(Binding::parse("Ctrl+"), None);
+        assert_eq!(Binding::parse("W*fast"), None);
+        assert_eq!(Binding::parse("Unknown"), None);
+    }
+
+    #[test]
+    fn bindings_round_trip() {
+        for text in [
+            "W",
+            "Ctrl+S",
+            "-A",
+            "MouseX*0.1",
+            "-MouseY*0.25",
+            "Shift+Mouse5",
+            "GamepadLeftThumb",
+            "GamepadLeftX",
+            "Num7",
+        ] {
+            let binding = Binding::parse(text).unwrap();
+            assert_eq!(binding.to_string(), text);
+            assert_eq!(Binding::parse(&binding.to_string()), Some(binding));
+        }
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let config = "
+            # Movement
+            [actions]
+            move_forward = [\"W\", \"Up\", \"GamepadDpadUp\"]
+            \"save\" = \"Ctrl+S\"  # quoted names are allowed
+
+            [axes]
+            move_x = [\"D\", \"-A\", \"GamepadLeftX\",]
+            look_x = \"MouseX*0.1\"
+        ";
+        let map = ActionMap::parse_config(config);
+        assert_eq!(map.get_action_bindings("move_forward").len(), 3);
+        assert_eq!(
+            map.get_action_bindings("save"),
+            &[Binding::parse("Ctrl+S").unwrap()]
+        );
+        assert_eq!(map.get_axis_bindings("move_x")[1]._scale, -1.0);
+
+        let written = map.to_config();
+        assert_eq!(
+            written,
+            "[actions]
+move_forward = [\"W\", \"Up\", \"GamepadDpadUp\"]
+save = [\"Ctrl+S\"]
+
+[axes]
+look_x = [\"MouseX*0.1\"]
+move_x = [\"D\", \"-A\", \"GamepadLeftX\"]
+"
+        );
+        let reread = ActionMap::parse_config(&written);
+        for name in ["move_forward", "save"] {
+            assert_eq!(
+                reread.get_action_bindings(name),
+                map.get_action_bindings(name)
+            );
+        }
+        for name in ["move_x", "look_x"] {
+            assert_eq!(reread.get_axis_bindings(name), map.get_axis_bindings(name));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Bindings line 2: expected 'name = value'")]
+    fn rejects_lines_without_value() {
+        ActionMap::parse_config("[actions]\njump");
+    }
+
+    #[test]
+    #[should_panic(expected = "Bindings line 2: bindings must be quoted strings")]
+    fn rejects_unquoted_bindings() {
+        ActionMap::parse_config("[actions]\njump = Space");
+    }
+
+    #[test]
+    #[should_panic(expected = "Bindings line 2: invalid binding 'Spaec'")]
+    fn rejects_unknown_inputs() {
+        ActionMap::parse_config("[actions]\njump = \"Spaec\"");
+    }
+
+    #[test]
+    #[should_panic(expected = "Bindings line 1: unknown section ''")]
+    fn rejects_bindings_outside_sections() {
+        ActionMap::parse_config("jump = \"Space\"");
+    }
+
+    #[test]
+    #[should_panic(expected = "Bindings line 4: unknown section 'buttons'")]
+    fn rejects_unknown_sections() {
+        ActionMap::parse_config("[actions]\n\n[buttons]\njump = \"Space\"");
+    }
+}
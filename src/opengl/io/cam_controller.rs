@@ -1,11 +1,19 @@
+use crate::opengl::components::Camera;
+use crate::opengl::io::{ActionMap, GamepadEvent, InputState};
 use crate::opengl::Window;
+use crate::utils::math::geometry::BoundingSphere;
+use crate::utils::math::linalg;
+use crate::utils::types::V3;
 use glfw;
 
 pub trait CameraController {
     fn poll_window_events(&mut self, window: &mut Window) {
-        for (_, event) in glfw::flush_messages(window.poll_events()) {
+        for (_, event) in window.poll_events() {
             self.handle_event(event);
         }
+        for event in window.gamepads().events() {
+            self.handle_gamepad_event(*event);
+        }
     }
 
     fn handle_event(&mut self, event: glfw::WindowEvent) {
@@ -18,6 +26,14 @@ pub trait CameraController {
             }
             glfw::WindowEvent::CursorPos(x, y) => self.handle_cursor_pos_event(x, y),
             glfw::WindowEvent::CursorEnter(enter) => self.handle_cursor_enter_event(enter),
+            glfw::WindowEvent::Scroll(x, y) => self.handle_scroll_event(x, y),
+            glfw::WindowEvent::Char(character) => self.handle_char_event(character),
+            glfw::WindowEvent::FramebufferSize(width, height) => {
+                self.handle_framebuffer_size_event(width, height)
+            }
+            glfw::WindowEvent::Focus(focused) => self.handle_focus_event(focused),
+            glfw::WindowEvent::Close => self.handle_close_event(),
+            glfw::WindowEvent::FileDrop(paths) => self.handle_file_drop_event(paths),
             _ => {}
         }
     }
@@ -42,8 +58,673 @@ pub trait CameraController {
     fn handle_cursor_pos_event(&mut self, _x: f64, _y: f64) {}
 
     fn handle_cursor_enter_event(&mut self, _enter: bool) {}
+
+    fn handle_scroll_event(&mut self, _x: f64, _y: f64) {}
+
+    fn handle_char_event(&mut self, _character: char) {}
+
+    /// Called when the framebuffer is resized. The viewport is already updated by the window,
+    /// so this is the place to update the aspect ratio of the cameras.
+    fn handle_framebuffer_size_event(&mut self, _width: i32, _height: i32) {}
+
+    fn handle_focus_event(&mut self, _focused: bool) {}
+
+    fn handle_close_event(&mut self) {}
+
+    fn handle_file_drop_event(&mut self, _paths: Vec<std::path::PathBuf>) {}
+
+    /// Called for gamepad connections and button changes. The axes are read from the input
+    /// state of the window.
+    fn handle_gamepad_event(&mut self, _event: GamepadEvent) {}
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=================================| Base Camera Controller |==================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A controller that moves a camera from the state of the input, once per frame. The camera is
+/// driven through `Camera::update`, so any camera can be used.
+pub trait CameraRig {
+    /// Applies the input of the frame and moves the camera. `delta` is the duration of the frame
+    /// in seconds, used for the keyboard and gamepad speeds and for the smoothing.
+    fn update<C: Camera + ?Sized>(&mut self, input: &InputState, delta: f32, camera: &mut C);
+
+    /// Skips the smoothing, so that the next update puts the camera right where it's headed.
+    fn snap(&mut self);
+}
+
+/// Fraction of the remaining way to cover in a frame, for an exponential smoothing in which
+/// `smoothing` is the time constant in seconds. No smoothing jumps straight to the goal.
+fn smoothing_factor(smoothing: f32, delta: f32) -> f32 {
+    if smoothing <= 0.0 {
+        return 1.0;
+    }
+    return 1.0 - (-delta / smoothing).exp();
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    return a + (b - a) * t;
+}
+
+/// Two horizontal axes completing the world up vector, from which the yaw is measured.
+fn horizontal_axes(up: &V3) -> (V3, V3) {
+    let reference = if up[2].abs() < 0.9 {
+        [0.0, 0.0, 1.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let back = linalg::sub_v3(
+        &reference,
+        &linalg::scale_v3(up, linalg::dot_v3(&reference, up)),
+    );
+    let back = linalg::normalize_v3(&back);
+    return (linalg::cross_v3(up, &back), back);
+}
+
+/// Unit vector with the given yaw and pitch in degrees. A yaw and pitch of 0 give the back axis.
+fn from_angles(up: &V3, yaw: f32, pitch: f32) -> V3 {
+    let (side, back) = horizontal_axes(up);
+    let (yaw, pitch) = (yaw.to_radians(), pitch.to_radians());
+    let horizontal = linalg::add_v3(
+        &linalg::scale_v3(&side, yaw.sin()),
+        &linalg::scale_v3(&back, yaw.cos()),
+    );
+    return linalg::add_v3(
+        &linalg::scale_v3(&horizontal, pitch.cos()),
+        &linalg::scale_v3(up, pitch.sin()),
+    );
+}
+
+/// Yaw and pitch in degrees of a vector, the inverse of `from_angles`.
+fn to_angles(up: &V3, vector: &V3) -> (f32, f32) {
+    let (side, back) = horizontal_axes(up);
+    let vector = linalg::normalize_v3(vector);
+    let yaw = linalg::dot_v3(&vector, &side).atan2(linalg::dot_v3(&vector, &back));
+    let pitch = linalg::dot_v3(&vector, up).clamp(-1.0, 1.0).asin();
+    return (yaw.to_degrees(), pitch.to_degrees());
+}
+
+/// Up and direction vectors to give to a camera whose direction (pointing backwards, as in
+/// `Camera`) is `direction`, keeping it upright with respect to the world up vector.
+fn camera_axes(world_up: &V3, direction: &V3) -> (V3, V3) {
+    let right = linalg::normalize_v3(&linalg::cross_v3(world_up, direction));
+    return (linalg::cross_v3(direction, &right), right);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|====================================| Orbit controller |=====================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Turns around a target point, keeping the camera upright. Dragging while the `rotate` action
+/// is active orbits, dragging while `pan` is active moves the target and the `zoom` axis changes
+/// the distance. By default they are bound to the left button, the right button and the wheel.
+pub struct OrbitController {
+    _target: V3,
+    _yaw: f32,
+    _pitch: f32,
+    _distance: f32,
+    _current_target: V3,
+    _current_yaw: f32,
+    _current_pitch: f32,
+    _current_distance: f32,
+    _world_up: V3,
+    _rotate_sensitivity: f32,
+    _pan_sensitivity: f32,
+    _zoom_speed: f32,
+    _min_distance: f32,
+    _max_distance: f32,
+    _max_pitch: f32,
+    _smoothing: f32,
+    _actions: ActionMap,
+}
+
+impl OrbitController {
+    pub fn new() -> OrbitController {
+        return OrbitController {
+            _target: [0.0, 0.0, 0.0],
+            _yaw: 0.0,
+            _pitch: 0.0,
+            _distance: 10.0,
+            _current_target: [0.0, 0.0, 0.0],
+            _current_yaw: 0.0,
+            _current_pitch: 0.0,
+            _current_distance: 10.0,
+            _world_up: [0.0, 1.0, 0.0],
+            _rotate_sensitivity: 0.3,
+            _pan_sensitivity: 0.002,
+            _zoom_speed: 0.1,
+            _min_distance: 0.01,
+            _max_distance: f32::MAX,
+            _max_pitch: 89.0,
+            _smoothing: 0.0,
+            _actions: ActionMap::new()
+                .action("rotate", &["MouseLeft"])
+                .action("pan", &["MouseRight"])
+                .axis("zoom", &["ScrollUp", "-ScrollDown"]),
+        };
+    }
+
+    /// Starts from the current placement of a camera, orbiting around the given target.
+    pub fn from_camera<C: Camera + ?Sized>(camera: &C, target: &V3) -> OrbitController {
+        let offset = linalg::sub_v3(&camera.get_position(), target);
+        return OrbitController::new()
+            .target(target)
+            .distance(linalg::norm_v3(&offset))
+            .direction(&offset);
+    }
+
+    pub fn target(mut self, target: &V3) -> OrbitController {
+        self.set_target(target);
+        self._current_target = *target;
+        return self;
+    }
+
+    pub fn distance(mut self, distance: f32) -> OrbitController {
+        self._distance = distance.clamp(self._min_distance, self._max_distance);
+        self._current_distance = self._distance;
+        return self;
+    }
+
+    /// Yaw and pitch in degrees, around the world up vector. At 0 and 0 the camera looks down
+    /// the -z axis (with the default up vector).
+    pub fn angles(mut self, yaw: f32, pitch: f32) -> OrbitController {
+        self._yaw = yaw;
+        self._pitch = pitch.clamp(-self._max_pitch, self._max_pitch);
+        self._current_yaw = self._yaw;
+        self._current_pitch = self._pitch;
+        return self;
+    }
+
+    /// Sets the angles so that the camera sits along `direction` from the target.
+    pub fn direction(self, direction: &V3) -> OrbitController {
+        let (yaw, pitch) = to_angles(&self._world_up, direction);
+        return self.angles(yaw, pitch);
+    }
+
+    /// The default up vector is +y.
+    pub fn world_up(mut self, up: &V3) -> OrbitController {
+        self._world_up = linalg::normalize_v3(up);
+        return self;
+    }
+
+    /// Degrees turned per pixel dragged.
+    pub fn rotate_sensitivity(mut self, sensitivity: f32) -> OrbitController {
+        self._rotate_sensitivity = sensitivity;
+        return self;
+    }
+
+    /// Distance panned per pixel dragged, relative to the distance to the target.
+    pub fn pan_sensitivity(mut self, sensitivity: f32) -> OrbitController {
+        self._pan_sensitivity = sensitivity;
+        return self;
+    }
+
+    /// Fraction of the distance gained or lost per step of the wheel.
+    pub fn zoom_speed(mut self, speed: f32) -> OrbitController {
+        self._zoom_speed = speed;
+        return self;
+    }
+
+    pub fn distance_limits(mut self, min: f32, max: f32) -> OrbitController {
+        assert!(
+            min <= max,
+            "The minimum distance must not exceed the maximum."
+        );
+        self._min_distance = min;
+        self._max_distance = max;
+        let distance = self._distance;
+        return self.distance(distance);
+    }
+
+    /// Highest angle in degrees the camera can reach above or below the target. It stays under
+    /// 90, where the camera would flip.
+    pub fn max_pitch(mut self, pitch: f32) -> OrbitController {
+        assert!(pitch >= 0.0, "The maximum pitch must not be negative.");
+        self._max_pitch = pitch.min(89.9);
+        return self;
+    }
+
+    /// Time constant of the smoothing in seconds. The default of 0 disables it.
+    pub fn smoothing(mut self, smoothing: f32) -> OrbitController {
+        self._smoothing = smoothing;
+        return self;
+    }
+
+    /// Replaces the bindings, which must have the `rotate` and `pan` actions and the `zoom` axis.
+    pub fn actions(mut self, actions: ActionMap) -> OrbitController {
+        self._actions = actions;
+        return self;
+    }
+
+    pub fn set_target(&mut self, target: &V3) {
+        self._target = *target;
+    }
+
+    pub fn get_target(&self) -> V3 {
+        return self._target;
+    }
+
+    /// Aims at the center of a sphere, such as the bounding sphere of the scene, and moves away
+    /// until it fits in the given vertical field of view in degrees. The orientation is kept.
+    pub fn frame(&mut self, sphere: &BoundingSphere, fov: f32) {
+        if sphere.is_empty() {
+            return;
+        }
+        self._target = sphere._center;
+        let distance = sphere._radius / (fov.to_radians() / 2.0).sin();
+        self._distance = distance.clamp(self._min_distance, self._max_distance);
+    }
+
+    pub fn get_distance(&self) -> f32 {
+        return self._distance;
+    }
+
+    pub fn get_angles(&self) -> (f32, f32) {
+        return (self._yaw, self._pitch);
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        self._actions.update(input);
+        let (dx, dy) = input.cursor_delta();
+        let (dx, dy) = (dx as f32, dy as f32);
+        if self._actions.is_active("rotate") {
+            self._yaw -= dx * self._rotate_sensitivity;
+            self._pitch += dy * self._rotate_sensitivity;
+            self._pitch = self._pitch.clamp(-self._max_pitch, self._max_pitch);
+        }
+        if self._actions.is_active("pan") {
+            let direction = from_angles(&self._world_up, self._yaw, self._pitch);
+            let (up, right) = camera_axes(&self._world_up, &direction);
+            let scale = self._pan_sensitivity * self._distance;
+            let pan = linalg::add_v3(
+                &linalg::scale_v3(&right, -dx * scale),
+                &linalg::scale_v3(&up, dy * scale),
+            );
+            self._target = linalg::add_v3(&self._target, &pan);
+        }
+        let zoom = self._actions.axis_value("zoom");
+        if zoom != 0.0 {
+            self._distance *= (1.0 + self._zoom_speed).powf(-zoom);
+            self._distance = self._distance.clamp(self._min_distance, self._max_distance);
+        }
+    }
+}
+
+impl CameraRig for OrbitController {
+    fn update<C: Camera + ?Sized>(&mut self, input: &InputState, delta: f32, camera: &mut C) {
+        self.handle_input(input);
+
+        let t = smoothing_factor(self._smoothing, delta);
+        self._current_target = linalg::lerp_v3(&self._current_target, &self._target, t);
+        self._current_yaw = lerp(self._current_yaw, self._yaw, t);
+        self._current_pitch = lerp(self._current_pitch, self._pitch, t);
+        self._current_distance = lerp(self._current_distance, self._distance, t);
+
+        let direction = from_angles(&self._world_up, self._current_yaw, self._current_pitch);
+        let (up, _) = camera_axes(&self._world_up, &direction);
+        let offset = linalg::scale_v3(&direction, self._current_distance);
+        camera.update(
+            &linalg::add_v3(&self._current_target, &offset),
+            &direction,
+            &up,
+        );
+    }
+
+    fn snap(&mut self) {
+        self._current_target = self._target;
+        self._current_yaw = self._yaw;
+        self._current_pitch = self._pitch;
+        self._current_distance = self._distance;
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-//|================================| Base Camera Controller |===================================|//
+//|=====================================| Fly controller |======================================|//
 ///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Free first-person camera, driven by the axes `move_forward`, `move_right` and `move_up`, with
+/// the `fast` action to go faster. The mouse looks around while the `look` action is active, and
+/// the `look_x` and `look_y` axes turn it at the gamepad look speed. By default, WASD with E and
+/// Q move it and shift goes faster, the right button looks, the left stick and the triggers of
+/// the gamepad move it too and the right stick looks around.
+pub struct FlyController {
+    _position: V3,
+    _yaw: f32,
+    _pitch: f32,
+    _current_position: V3,
+    _current_yaw: f32,
+    _current_pitch: f32,
+    _world_up: V3,
+    _move_speed: f32,
+    _fast_multiplier: f32,
+    _look_sensitivity: f32,
+    _gamepad_look_speed: f32,
+    _smoothing: f32,
+    _actions: ActionMap,
+}
+
+impl FlyController {
+    pub fn new() -> FlyController {
+        return FlyController {
+            _position: [0.0, 0.0, 0.0],
+            _yaw: 0.0,
+            _pitch: 0.0,
+            _current_position: [0.0, 0.0, 0.0],
+            _current_yaw: 0.0,
+            _current_pitch: 0.0,
+            _world_up: [0.0, 1.0, 0.0],
+            _move_speed: 5.0,
+            _fast_multiplier: 4.0,
+            _look_sensitivity: 0.1,
+            _gamepad_look_speed: 120.0,
+            _smoothing: 0.0,
+            _actions: ActionMap::new()
+                .axis("move_forward", &["W", "-S", "-GamepadLeftY"])
+                .axis("move_right", &["D", "-A", "GamepadLeftX"])
+                .axis(
+                    "move_up",
+                    &["E", "-Q", "GamepadRightTrigger", "-GamepadLeftTrigger"],
+                )
+                .axis("look_x", &["GamepadRightX"])
+                .axis("look_y", &["GamepadRightY"])
+                .action("look", &["MouseRight"])
+                .action("fast", &["Shift"]),
+        };
+    }
+
+    /// Starts from the current placement of a camera.
+    pub fn from_camera<C: Camera + ?Sized>(camera: &C) -> FlyController {
+        return FlyController::new()
+            .position(&camera.get_position())
+            .look_direction(&linalg::scale_v3(&camera.get_direction(), -1.0));
+    }
+
+    pub fn position(mut self, position: &V3) -> FlyController {
+        self._position = *position;
+        self._current_position = *position;
+        return self;
+    }
+
+    /// Yaw and pitch in degrees, around the world up vector. At 0 and 0 the camera looks down
+    /// the -z axis (with the default up vector), and a positive pitch looks up.
+    pub fn angles(mut self, yaw: f32, pitch: f32) -> FlyController {
+        self._yaw = yaw;
+        self._pitch = pitch.clamp(-89.0, 89.0);
+        self._current_yaw = self._yaw;
+        self._current_pitch = self._pitch;
+        return self;
+    }
+
+    /// Sets the angles so that the camera looks towards `direction`.
+    pub fn look_direction(self, direction: &V3) -> FlyController {
+        let back = linalg::scale_v3(direction, -1.0);
+        let (yaw, pitch) = to_angles(&self._world_up, &back);
+        return self.angles(yaw, -pitch);
+    }
+
+    /// The default up vector is +y.
+    pub fn world_up(mut self, up: &V3) -> FlyController {
+        self._world_up = linalg::normalize_v3(up);
+        return self;
+    }
+
+    /// Units per second.
+    pub fn move_speed(mut self, speed: f32) -> FlyController {
+        self._move_speed = speed;
+        return self;
+    }
+
+    /// Multiplies the speed while the `fast` action is active.
+    pub fn fast_multiplier(mut self, multiplier: f32) -> FlyController {
+        self._fast_multiplier = multiplier;
+        return self;
+    }
+
+    /// Degrees turned per pixel the mouse moves.
+    pub fn look_sensitivity(mut self, sensitivity: f32) -> FlyController {
+        self._look_sensitivity = sensitivity;
+        return self;
+    }
+
+    /// Degrees turned per second with the right stick fully pushed.
+    pub fn gamepad_look_speed(mut self, speed: f32) -> FlyController {
+        self._gamepad_look_speed = speed;
+        return self;
+    }
+
+    /// Time constant of the smoothing in seconds. The default of 0 disables it.
+    pub fn smoothing(mut self, smoothing: f32) -> FlyController {
+        self._smoothing = smoothing;
+        return self;
+    }
+
+    /// Replaces the bindings, which are looked up by the names given above. Without any `look`
+    /// binding the mouse always looks around, which is meant for a window whose cursor is
+    /// disabled.
+    pub fn actions(mut self, actions: ActionMap) -> FlyController {
+        self._actions = actions;
+        return self;
+    }
+
+    pub fn set_position(&mut self, position: &V3) {
+        self._position = *position;
+    }
+
+    pub fn get_position(&self) -> V3 {
+        return self._position;
+    }
+
+    pub fn get_angles(&self) -> (f32, f32) {
+        return (self._yaw, self._pitch);
+    }
+
+    /// Direction the camera points to, which is the opposite of its `Camera` direction.
+    fn forward(&self, yaw: f32, pitch: f32) -> V3 {
+        return linalg::scale_v3(&from_angles(&self._world_up, yaw, -pitch), -1.0);
+    }
+
+    fn handle_input(&mut self, input: &InputState, delta: f32) {
+        let actions = &mut self._actions;
+        actions.update(input);
+        if actions.is_active("look") || actions.get_action_bindings("look").is_empty() {
+            let (dx, dy) = input.cursor_delta();
+            self._yaw -= dx as f32 * self._look_sensitivity;
+            self._pitch -= dy as f32 * self._look_sensitivity;
+        }
+        self._yaw -= actions.axis_value("look_x") * self._gamepad_look_speed * delta;
+        self._pitch -= actions.axis_value("look_y") * self._gamepad_look_speed * delta;
+        self._pitch = self._pitch.clamp(-89.0, 89.0);
+
+        let forward_amount = actions.axis_value("move_forward");
+        let right_amount = actions.axis_value("move_right");
+        let up_amount = actions.axis_value("move_up");
+        let fast = actions.is_active("fast");
+
+        let forward = self.forward(self._yaw, self._pitch);
+        let (_, right) = camera_axes(&self._world_up, &linalg::scale_v3(&forward, -1.0));
+        let mut movement = linalg::scale_v3(&forward, forward_amount);
+        movement = linalg::add_v3(&movement, &linalg::scale_v3(&right, right_amount));
+        movement = linalg::add_v3(&movement, &linalg::scale_v3(&self._world_up, up_amount));
+        // Going diagonally isn't faster, but half-pushed sticks still go slower
+        let length = linalg::norm_v3(&movement);
+        if length > 1.0 {
+            movement = linalg::scale_v3(&movement, 1.0 / length);
+        }
+        let mut speed = self._move_speed * delta;
+        if fast {
+            speed *= self._fast_multiplier;
+        }
+        self._position = linalg::add_v3(&self._position, &linalg::scale_v3(&movement, speed));
+    }
+}
+
+impl CameraRig for FlyController {
+    fn update<C: Camera + ?Sized>(&mut self, input: &InputState, delta: f32, camera: &mut C) {
+        self.handle_input(input, delta);
+
+        let t = smoothing_factor(self._smoothing, delta);
+        self._current_position = linalg::lerp_v3(&self._current_position, &self._position, t);
+        self._current_yaw = lerp(self._current_yaw, self._yaw, t);
+        self._current_pitch = lerp(self._current_pitch, self._pitch, t);
+
+        let forward = self.forward(self._current_yaw, self._current_pitch);
+        let direction = linalg::scale_v3(&forward, -1.0);
+        let (up, _) = camera_axes(&self._world_up, &direction);
+        camera.update(&self._current_position, &direction, &up);
+    }
+
+    fn snap(&mut self) {
+        self._current_position = self._position;
+        self._current_yaw = self._yaw;
+        self._current_pitch = self._pitch;
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|===================================| Pan-zoom controller |===================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Two-dimensional navigation, meant for orthographic cameras. Dragging while the `pan` action is
+/// active moves the camera in its own plane and the `zoom` axis zooms through `Camera::set_zoom`,
+/// while the direction of the camera is kept. By default they are bound to the left button and
+/// the wheel.
+pub struct PanZoomController {
+    _position: V3,
+    _zoom: f32,
+    _current_position: V3,
+    _current_zoom: f32,
+    _direction: V3,
+    _up: V3,
+    _pan_sensitivity: f32,
+    _zoom_speed: f32,
+    _min_zoom: f32,
+    _max_zoom: f32,
+    _smoothing: f32,
+    _actions: ActionMap,
+}
+
+impl PanZoomController {
+    pub fn new() -> PanZoomController {
+        return PanZoomController {
+            _position: [0.0, 0.0, 1.0],
+            _zoom: 1.0,
+            _current_position: [0.0, 0.0, 1.0],
+            _current_zoom: 1.0,
+            _direction: [0.0, 0.0, 1.0],
+            _up: [0.0, 1.0, 0.0],
+            _pan_sensitivity: 0.01,
+            _zoom_speed: 0.1,
+            _min_zoom: 0.01,
+            _max_zoom: 100.0,
+            _smoothing: 0.0,
+            _actions: ActionMap::new()
+                .action("pan", &["MouseLeft"])
+                .axis("zoom", &["ScrollUp", "-ScrollDown"]),
+        };
+    }
+
+    /// Starts from the current placement and zoom of a camera.
+    pub fn from_camera<C: Camera + ?Sized>(camera: &C) -> PanZoomController {
+        let mut controller = PanZoomController::new()
+            .position(&camera.get_position())
+            .zoom(camera.get_zoom());
+        controller._direction = camera.get_direction();
+        controller._up = camera.get_up();
+        return controller;
+    }
+
+    pub fn position(mut self, position: &V3) -> PanZoomController {
+        self._position = *position;
+        self._current_position = *position;
+        return self;
+    }
+
+    pub fn zoom(mut self, zoom: f32) -> PanZoomController {
+        self._zoom = zoom.clamp(self._min_zoom, self._max_zoom);
+        self._current_zoom = self._zoom;
+        return self;
+    }
+
+    /// World units panned per pixel dragged at a zoom of 1. Setting it to the height of the view
+    /// over the height of the window in pixels makes the scene follow the cursor.
+    pub fn pan_sensitivity(mut self, sensitivity: f32) -> PanZoomController {
+        self._pan_sensitivity = sensitivity;
+        return self;
+    }
+
+    /// Fraction of the zoom gained or lost per step of the wheel.
+    pub fn zoom_speed(mut self, speed: f32) -> PanZoomController {
+        self._zoom_speed = speed;
+        return self;
+    }
+
+    pub fn zoom_limits(mut self, min: f32, max: f32) -> PanZoomController {
+        assert!(min <= max, "The minimum zoom must not exceed the maximum.");
+        self._min_zoom = min;
+        self._max_zoom = max;
+        let zoom = self._zoom;
+        return self.zoom(zoom);
+    }
+
+    /// Time constant of the smoothing in seconds. The default of 0 disables it.
+    pub fn smoothing(mut self, smoothing: f32) -> PanZoomController {
+        self._smoothing = smoothing;
+        return self;
+    }
+
+    /// Replaces the bindings, which must have the `pan` action and the `zoom` axis.
+    pub fn actions(mut self, actions: ActionMap) -> PanZoomController {
+        self._actions = actions;
+        return self;
+    }
+
+    pub fn set_position(&mut self, position: &V3) {
+        self._position = *position;
+    }
+
+    pub fn get_position(&self) -> V3 {
+        return self._position;
+    }
+
+    pub fn get_zoom(&self) -> f32 {
+        return self._zoom;
+    }
+
+    fn handle_input(&mut self, input: &InputState) {
+        self._actions.update(input);
+        if self._actions.is_active("pan") {
+            let (dx, dy) = input.cursor_delta();
+            let (up, right) = camera_axes(&self._up, &self._direction);
+            let scale = self._pan_sensitivity / self._zoom;
+            let pan = linalg::add_v3(
+                &linalg::scale_v3(&right, -dx as f32 * scale),
+                &linalg::scale_v3(&up, dy as f32 * scale),
+            );
+            self._position = linalg::add_v3(&self._position, &pan);
+        }
+        let zoom = self._actions.axis_value("zoom");
+        if zoom != 0.0 {
+            self._zoom *= (1.0 + self._zoom_speed).powf(zoom);
+            self._zoom = self._zoom.clamp(self._min_zoom, self._max_zoom);
+        }
+    }
+}
+
+impl CameraRig for PanZoomController {
+    fn update<C: Camera + ?Sized>(&mut self, input: &InputState, delta: f32, camera: &mut C) {
+        self.handle_input(input);
+
+        let t = smoothing_factor(self._smoothing, delta);
+        self._current_position = linalg::lerp_v3(&self._current_position, &self._position, t);
+        // The zoom is interpolated logarithmically, so that zooming in and out feel the same
+        self._current_zoom = lerp(self._current_zoom.ln(), self._zoom.ln(), t).exp();
+
+        camera.set_zoom(self._current_zoom);
+        camera.update(&self._current_position, &self._direction, &self._up);
+    }
+
+    fn snap(&mut self) {
+        self._current_position = self._position;
+        self._current_zoom = self._zoom;
+    }
+}
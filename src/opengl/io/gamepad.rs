@@ -0,0 +1,420 @@
+use glfw;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+pub const GAMEPAD_BUTTON_COUNT: usize = 15;
+pub const GAMEPAD_AXIS_COUNT: usize = 6;
+
+/// Buttons and axes of a gamepad at a given time, using the standard layout of GLFW.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GamepadSnapshot {
+    pub _buttons: [bool; GAMEPAD_BUTTON_COUNT],
+    pub _axes: [f32; GAMEPAD_AXIS_COUNT],
+}
+
+impl GamepadSnapshot {
+    /// State of an untouched gamepad as reported by GLFW, with the triggers at -1.
+    pub fn at_rest() -> GamepadSnapshot {
+        let mut snapshot = GamepadSnapshot::default();
+        snapshot._axes[glfw::GamepadAxis::AxisLeftTrigger as usize] = -1.0;
+        snapshot._axes[glfw::GamepadAxis::AxisRightTrigger as usize] = -1.0;
+        return snapshot;
+    }
+
+    pub fn button(&self, button: glfw::GamepadButton) -> bool {
+        return self._buttons[button as usize];
+    }
+
+    pub fn axis(&self, axis: glfw::GamepadAxis) -> f32 {
+        return self._axes[axis as usize];
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GamepadEvent {
+    Connected(u32),
+    Disconnected(u32),
+    ButtonPressed(u32, glfw::GamepadButton),
+    ButtonReleased(u32, glfw::GamepadButton),
+}
+
+/// Something the state of the gamepads can be read from. GLFW is used by default, but it can be
+/// replaced to feed the input system from elsewhere, such as a `MockGamepads` in tests.
+pub trait GamepadSource {
+    /// Returns the raw state of every connected gamepad, by id.
+    fn poll(&mut self) -> BTreeMap<u32, GamepadSnapshot>;
+    fn name(&self, id: u32) -> Option<String>;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=========================================| Sources |=========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the joysticks that GLFW recognizes as gamepads.
+pub struct GlfwGamepads {
+    _glfw: glfw::Glfw,
+}
+
+impl GlfwGamepads {
+    pub fn new(glfw: glfw::Glfw) -> GlfwGamepads {
+        return GlfwGamepads { _glfw: glfw };
+    }
+
+    fn joystick(&self, id: u32) -> Option<glfw::Joystick> {
+        return JOYSTICKS
+            .get(id as usize)
+            .map(|joystick_id| self._glfw.get_joystick(*joystick_id));
+    }
+}
+
+impl GamepadSource for GlfwGamepads {
+    fn poll(&mut self) -> BTreeMap<u32, GamepadSnapshot> {
+        let mut gamepads = BTreeMap::new();
+        for id in 0..JOYSTICKS.len() as u32 {
+            let joystick = self.joystick(id).unwrap();
+            if !joystick.is_gamepad() {
+                continue;
+            }
+            if let Some(state) = joystick.get_gamepad_state() {
+                let mut snapshot = GamepadSnapshot::default();
+                for (i, button) in snapshot._buttons.iter_mut().enumerate() {
+                    let b = glfw::GamepadButton::from_i32(i as i32).unwrap();
+                    *button = state.get_button_state(b) == glfw::Action::Press;
+                }
+                for (i, axis) in snapshot._axes.iter_mut().enumerate() {
+                    *axis = state.get_axis(glfw::GamepadAxis::from_i32(i as i32).unwrap());
+                }
+                gamepads.insert(id, snapshot);
+            }
+        }
+        return gamepads;
+    }
+
+    fn name(&self, id: u32) -> Option<String> {
+        return self.joystick(id)?.get_gamepad_name();
+    }
+}
+
+/// A source of gamepads whose state is set by hand. Gamepads are connected when first touched.
+/// Clones share the same gamepads, so one can be given to the window while the other one is
+/// driven by a test.
+#[derive(Clone, Debug, Default)]
+pub struct MockGamepads {
+    _gamepads: Rc<RefCell<BTreeMap<u32, GamepadSnapshot>>>,
+}
+
+impl MockGamepads {
+    pub fn new() -> MockGamepads {
+        return MockGamepads::default();
+    }
+
+    pub fn connect(&self, id: u32) {
+        self._gamepads
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(GamepadSnapshot::at_rest);
+    }
+
+    pub fn disconnect(&self, id: u32) {
+        self._gamepads.borrow_mut().remove(&id);
+    }
+
+    pub fn set_button(&self, id: u32, button: glfw::GamepadButton, down: bool) {
+        self.connect(id);
+        self._gamepads.borrow_mut().get_mut(&id).unwrap()._buttons[button as usize] = down;
+    }
+
+    /// Sets the raw value of an axis, from -1 to 1 as given by GLFW (triggers included).
+    pub fn set_axis(&self, id: u32, axis: glfw::GamepadAxis, value: f32) {
+        self.connect(id);
+        self._gamepads.borrow_mut().get_mut(&id).unwrap()._axes[axis as usize] = value;
+    }
+}
+
+impl GamepadSource for MockGamepads {
+    fn poll(&mut self) -> BTreeMap<u32, GamepadSnapshot> {
+        return self._gamepads.borrow().clone();
+    }
+
+    fn name(&self, id: u32) -> Option<String> {
+        return self
+            ._gamepads
+            .borrow()
+            .get(&id)
+            .map(|_| format!("Mock gamepad {id}"));
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|========================================| Gamepads |=========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Polls a gamepad source, filtering the sticks with a dead zone and turning the changes into
+/// events. The triggers are remapped from GLFW's -1 to 1 range to 0 to 1.
+pub struct Gamepads {
+    _source: Box<dyn GamepadSource>,
+    _dead_zone: f32,
+    _trigger_dead_zone: f32,
+    _states: BTreeMap<u32, GamepadSnapshot>,
+    _events: Vec<GamepadEvent>,
+}
+
+impl Gamepads {
+    pub fn new(source: Box<dyn GamepadSource>) -> Gamepads {
+        return Gamepads {
+            _source: source,
+            _dead_zone: 0.15,
+            _trigger_dead_zone: 0.05,
+            _states: BTreeMap::new(),
+            _events: vec![],
+        };
+    }
+
+    /// Radius of the stick dead zone, from 0 to 1. Inside it the stick reads as centered, and
+    /// outside it the values are rescaled to still reach 1.
+    pub fn dead_zone(mut self, dead_zone: f32) -> Gamepads {
+        self._dead_zone = dead_zone;
+        return self;
+    }
+
+    pub fn trigger_dead_zone(mut self, dead_zone: f32) -> Gamepads {
+        self._trigger_dead_zone = dead_zone;
+        return self;
+    }
+
+    pub fn set_dead_zone(&mut self, dead_zone: f32) {
+        self._dead_zone = dead_zone;
+    }
+
+    pub fn set_source(&mut self, source: Box<dyn GamepadSource>) {
+        self._source = source;
+    }
+
+    /// Reads the new state of the gamepads, returning what changed since the last poll.
+    pub fn poll(&mut self) -> &[GamepadEvent] {
+        self._events.clear();
+        let mut states = self._source.poll();
+        for state in states.values_mut() {
+            self.filter(state);
+        }
+
+        for id in self._states.keys() {
+            if !states.contains_key(id) {
+                self._events.push(GamepadEvent::Disconnected(*id));
+            }
+        }
+        for (id, state) in states.iter() {
+            let previous = match self._states.get(id) {
+                Some(previous) => *previous,
+                None => {
+                    self._events.push(GamepadEvent::Connected(*id));
+                    GamepadSnapshot::default()
+                }
+            };
+            for i in 0..GAMEPAD_BUTTON_COUNT {
+                let button = glfw::GamepadButton::from_i32(i as i32).unwrap();
+                match (previous._buttons[i], state._buttons[i]) {
+                    (false, true) => self._events.push(GamepadEvent::ButtonPressed(*id, button)),
+                    (true, false) => self._events.push(GamepadEvent::ButtonReleased(*id, button)),
+                    _ => {}
+                }
+            }
+        }
+        self._states = states;
+        return &self._events;
+    }
+
+    /// Events produced by the last poll.
+    pub fn events(&self) -> &[GamepadEvent] {
+        return &self._events;
+    }
+
+    pub fn is_connected(&self, id: u32) -> bool {
+        return self._states.contains_key(&id);
+    }
+
+    /// Ids of the connected gamepads, in increasing order.
+    pub fn connected(&self) -> Vec<u32> {
+        return self._states.keys().copied().collect();
+    }
+
+    /// The gamepad used by the input state, which is the connected one with the lowest id.
+    pub fn primary(&self) -> Option<u32> {
+        return self._states.keys().next().copied();
+    }
+
+    /// Filtered state of a gamepad, if connected.
+    pub fn state(&self, id: u32) -> Option<&GamepadSnapshot> {
+        return self._states.get(&id);
+    }
+
+    pub fn name(&self, id: u32) -> Option<String> {
+        return self._source.name(id);
+    }
+
+    fn filter(&self, state: &mut GamepadSnapshot) {
+        let sticks = [
+            (glfw::GamepadAxis::AxisLeftX, glfw::GamepadAxis::AxisLeftY),
+            (glfw::GamepadAxis::AxisRightX, glfw::GamepadAxis::AxisRightY),
+        ];
+        for (x, y) in sticks {
+            let (x, y) = (x as usize, y as usize);
+            let (vx, vy) = (state._axes[x], state._axes[y]);
+            let magnitude = (vx * vx + vy * vy).sqrt();
+            // Radial dead zone, so that diagonals aren't snapped to the axes
+            let scale = if magnitude <= self._dead_zone {
+                0.0
+            } else {
+                let rescaled = ((magnitude - self._dead_zone) / (1.0 - self._dead_zone)).min(1.0);
+                rescaled / magnitude
+            };
+            state._axes[x] = vx * scale;
+            state._axes[y] = vy * scale;
+        }
+        for trigger in [
+            glfw::GamepadAxis::AxisLeftTrigger,
+            glfw::GamepadAxis::AxisRightTrigger,
+        ] {
+            let value = (state._axes[trigger as usize] + 1.0) / 2.0;
+            state._axes[trigger as usize] = if value <= self._trigger_dead_zone {
+                0.0
+            } else {
+                ((value - self._trigger_dead_zone) / (1.0 - self._trigger_dead_zone)).min(1.0)
+            };
+        }
+    }
+}
+
+#[rustfmt::skip]
+const JOYSTICKS: [glfw::JoystickId; 16] = [
+    glfw::JoystickId::Joystick1, glfw::JoystickId::Joystick2, glfw::JoystickId::Joystick3,
+    glfw::JoystickId::Joystick4, glfw::JoystickId::Joystick5, glfw::JoystickId::Joystick6,
+    glfw::JoystickId::Joystick7, glfw::JoystickId::Joystick8, glfw::JoystickId::Joystick9,
+    glfw::JoystickId::Joystick10, glfw::JoystickId::Joystick11, glfw::JoystickId::Joystick12,
+    glfw::JoystickId::Joystick13, glfw::JoystickId::Joystick14, glfw::JoystickId::Joystick15,
+    glfw::JoystickId::Joystick16,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glfw::{GamepadAxis, GamepadButton};
+
+    fn gamepads() -> (MockGamepads, Gamepads) {
+        let mock = MockGamepads::new();
+        let gamepads = Gamepads::new(Box::new(mock.clone()))
+            .dead_zone(0.2)
+            .trigger_dead_zone(0.1);
+        return (mock, gamepads);
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{a} != {b}");
+    }
+
+    #[test]
+    fn sticks_use_a_radial_dead_zone() {
+        let (mock, mut gamepads) = gamepads();
+        mock.set_axis(0, GamepadAxis::AxisLeftX, 0.1);
+        mock.set_axis(0, GamepadAxis::AxisLeftY, 0.1);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        assert_eq!(state.axis(GamepadAxis::AxisLeftX), 0.0);
+        assert_eq!(state.axis(GamepadAxis::AxisLeftY), 0.0);
+
+        // Halfway between the dead zone and the edge, along a diagonal
+        let component = 0.6 / 2.0_f32.sqrt();
+        mock.set_axis(0, GamepadAxis::AxisLeftX, component);
+        mock.set_axis(0, GamepadAxis::AxisLeftY, -component);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        let expected = 0.5 / 2.0_f32.sqrt();
+        assert_close(state.axis(GamepadAxis::AxisLeftX), expected);
+        assert_close(state.axis(GamepadAxis::AxisLeftY), -expected);
+
+        mock.set_axis(0, GamepadAxis::AxisRightX, 1.0);
+        mock.set_axis(0, GamepadAxis::AxisRightY, 1.0);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        let edge = 1.0 / 2.0_f32.sqrt();
+        assert_close(state.axis(GamepadAxis::AxisRightX), edge);
+        assert_close(state.axis(GamepadAxis::AxisRightY), edge);
+    }
+
+    #[test]
+    fn triggers_are_remapped_to_zero_one() {
+        let (mock, mut gamepads) = gamepads();
+        mock.connect(0);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        assert_eq!(state.axis(GamepadAxis::AxisLeftTrigger), 0.0);
+        assert_eq!(state.axis(GamepadAxis::AxisRightTrigger), 0.0);
+
+        mock.set_axis(0, GamepadAxis::AxisLeftTrigger, 1.0);
+        mock.set_axis(0, GamepadAxis::AxisRightTrigger, 0.1);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        assert_close(state.axis(GamepadAxis::AxisLeftTrigger), 1.0);
+        // 0.1 is 0.55 of the way, rescaled past the dead zone of 0.1
+        assert_close(state.axis(GamepadAxis::AxisRightTrigger), 0.5);
+
+        mock.set_axis(0, GamepadAxis::AxisRightTrigger, -0.85);
+        gamepads.poll();
+        let state = gamepads.state(0).unwrap();
+        assert_eq!(state.axis(GamepadAxis::AxisRightTrigger), 0.0);
+    }
+
+    #[test]
+    fn polls_report_changes_as_events() {
+        let (mock, mut gamepads) = gamepads();
+        assert!(gamepads.poll().is_empty());
+
+        mock.connect(1);
+        assert_eq!(gamepads.poll(), &[GamepadEvent::Connected(1)]);
+        assert!(gamepads.is_connected(1));
+        assert_eq!(gamepads.name(1), Some("Mock gamepad 1".to_string()));
+
+        mock.set_button(1, GamepadButton::ButtonA, true);
+        assert_eq!(
+            gamepads.poll(),
+            &[GamepadEvent::ButtonPressed(1, GamepadButton::ButtonA)]
+        );
+        // Held buttons don't produce events again
+        assert!(gamepads.poll().is_empty());
+        assert!(gamepads.state(1).unwrap().button(GamepadButton::ButtonA));
+
+        mock.set_button(1, GamepadButton::ButtonA, false);
+        mock.set_button(1, GamepadButton::ButtonStart, true);
+        assert_eq!(
+            gamepads.poll(),
+            &[
+                GamepadEvent::ButtonReleased(1, GamepadButton::ButtonA),
+                GamepadEvent::ButtonPressed(1, GamepadButton::ButtonStart),
+            ]
+        );
+        assert_eq!(gamepads.events().len(), 2);
+
+        mock.disconnect(1);
+        assert_eq!(gamepads.poll(), &[GamepadEvent::Disconnected(1)]);
+        assert!(!gamepads.is_connected(1));
+        assert_eq!(gamepads.primary(), None);
+    }
+
+    #[test]
+    fn primary_is_the_lowest_connected_id() {
+        let (mock, mut gamepads) = gamepads();
+        mock.connect(3);
+        mock.set_button(2, GamepadButton::ButtonB, true);
+        assert_eq!(
+            gamepads.poll(),
+            &[
+                GamepadEvent::Connected(2),
+                GamepadEvent::ButtonPressed(2, GamepadButton::ButtonB),
+                GamepadEvent::Connected(3),
+            ]
+        );
+        assert_eq!(gamepads.connected(), vec![2, 3]);
+        assert_eq!(gamepads.primary(), Some(2));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::opengl::io::gamepad::{GamepadSnapshot, GAMEPAD_BUTTON_COUNT};
+use glfw;
+use std::collections::HashSet;
+
+/// State of the keyboard, mouse and primary gamepad, updated by the window every time its events
+/// are polled.
+/// Keys and buttons are "pressed" or "released" only during the frame in which they changed,
+/// while "down" lasts for as long as they are held.
+#[derive(Clone, Debug)]
+pub struct InputState {
+    _keys_down: HashSet<glfw::Key>,
+    _keys_pressed: HashSet<glfw::Key>,
+    _keys_released: HashSet<glfw::Key>,
+    _buttons_down: HashSet<glfw::MouseButton>,
+    _buttons_pressed: HashSet<glfw::MouseButton>,
+    _buttons_released: HashSet<glfw::MouseButton>,
+    _modifiers: glfw::Modifiers,
+    _cursor_position: (f64, f64),
+    _cursor_delta: (f64, f64),
+    _cursor_known: bool,
+    _cursor_inside: bool,
+    _scroll_delta: (f64, f64),
+    _text: String,
+    _gamepad_connected: bool,
+    _gamepad_buttons: HashSet<glfw::GamepadButton>,
+    _gamepad_pressed: HashSet<glfw::GamepadButton>,
+    _gamepad_released: HashSet<glfw::GamepadButton>,
+    _gamepad_axes: [f32; 6],
+}
+
+impl InputState {
+    pub fn new() -> InputState {
+        return InputState {
+            _keys_down: HashSet::new(),
+            _keys_pressed: HashSet::new(),
+            _keys_released: HashSet::new(),
+            _buttons_down: HashSet::new(),
+            _buttons_pressed: HashSet::new(),
+            _buttons_released: HashSet::new(),
+            _modifiers: glfw::Modifiers::empty(),
+            _cursor_position: (0.0, 0.0),
+            _cursor_delta: (0.0, 0.0),
+            _cursor_known: false,
+            _cursor_inside: false,
+            _scroll_delta: (0.0, 0.0),
+            _text: String::new(),
+            _gamepad_connected: false,
+            _gamepad_buttons: HashSet::new(),
+            _gamepad_pressed: HashSet::new(),
+            _gamepad_released: HashSet::new(),
+            _gamepad_axes: [0.0; 6],
+        };
+    }
+
+    /// Forgets the changes of the previous frame. Called before handling the new events.
+    pub fn begin_frame(&mut self) {
+        self._keys_pressed.clear();
+        self._keys_released.clear();
+        self._buttons_pressed.clear();
+        self._buttons_released.clear();
+        self._cursor_delta = (0.0, 0.0);
+        self._scroll_delta = (0.0, 0.0);
+        self._text.clear();
+        self._gamepad_pressed.clear();
+        self._gamepad_released.clear();
+    }
+
+    pub fn handle_event(&mut self, event: &glfw::WindowEvent) {
+        match *event {
+            glfw::WindowEvent::Key(key, _, action, modifiers) => {
+                self._modifiers = modifiers;
+                match action {
+                    glfw::Action::Press => {
+                        self._keys_down.insert(key);
+                        self._keys_pressed.insert(key);
+                    }
+                    glfw::Action::Release => {
+                        self._keys_down.remove(&key);
+                        self._keys_released.insert(key);
+                    }
+                    glfw::Action::Repeat => {}
+                }
+            }
+            glfw::WindowEvent::MouseButton(button, action, modifiers) => {
+                self._modifiers = modifiers;
+                match action {
+                    glfw::Action::Press => {
+                        self._buttons_down.insert(button);
+                        self._buttons_pressed.insert(button);
+                    }
+                    glfw::Action::Release => {
+                        self._buttons_down.remove(&button);
+                        self._buttons_released.insert(button);
+                    }
+                    glfw::Action::Repeat => {}
+                }
+            }
+            glfw::WindowEvent::CursorPos(x, y) => {
+                // The first position has nothing to compare to, so it doesn't make the cursor jump
+                if self._cursor_known {
+                    self._cursor_delta.0 += x - self._cursor_position.0;
+                    self._cursor_delta.1 += y - self._cursor_position.1;
+                }
+                self._cursor_position = (x, y);
+                self._cursor_known = true;
+            }
+            glfw::WindowEvent::CursorEnter(entered) => {
+                self._cursor_inside = entered;
+                if !entered {
+                    self._cursor_known = false;
+                }
+            }
+            glfw::WindowEvent::Scroll(x, y) => {
+                self._scroll_delta.0 += x;
+                self._scroll_delta.1 += y;
+            }
+            glfw::WindowEvent::Char(character) => self._text.push(character),
+            glfw::WindowEvent::Focus(false) => self.release_all(),
+            _ => {}
+        }
+    }
+
+    /// Releases every key and button being held. The window does it when it loses the focus,
+    /// as the release events would go to another window.
+    pub fn release_all(&mut self) {
+        self._keys_released.extend(self._keys_down.drain());
+        self._buttons_released.extend(self._buttons_down.drain());
+        self._modifiers = glfw::Modifiers::empty();
+    }
+
+    pub fn is_key_down(&self, key: glfw::Key) -> bool {
+        return self._keys_down.contains(&key);
+    }
+
+    pub fn is_key_pressed(&self, key: glfw::Key) -> bool {
+        return self._keys_pressed.contains(&key);
+    }
+
+    pub fn is_key_released(&self, key: glfw::Key) -> bool {
+        return self._keys_released.contains(&key);
+    }
+
+    pub fn is_mouse_down(&self, button: glfw::MouseButton) -> bool {
+        return self._buttons_down.contains(&button);
+    }
+
+    pub fn is_mouse_pressed(&self, button: glfw::MouseButton) -> bool {
+        return self._buttons_pressed.contains(&button);
+    }
+
+    pub fn is_mouse_released(&self, button: glfw::MouseButton) -> bool {
+        return self._buttons_released.contains(&button);
+    }
+
+    /// Modifiers held during the last key or mouse button event.
+    pub fn modifiers(&self) -> glfw::Modifiers {
+        return self._modifiers;
+    }
+
+    pub fn shift(&self) -> bool {
+        return self._modifiers.contains(glfw::Modifiers::Shift);
+    }
+
+    pub fn control(&self) -> bool {
+        return self._modifiers.contains(glfw::Modifiers::Control);
+    }
+
+    pub fn alt(&self) -> bool {
+        return self._modifiers.contains(glfw::Modifiers::Alt);
+    }
+
+    /// Position of the cursor in screen coordinates, from the upper-left corner of the window.
+    pub fn cursor_position(&self) -> (f64, f64) {
+        return self._cursor_position;
+    }
+
+    /// Movement of the cursor during the frame.
+    pub fn cursor_delta(&self) -> (f64, f64) {
+        return self._cursor_delta;
+    }
+
+    pub fn cursor_inside(&self) -> bool {
+        return self._cursor_inside;
+    }
+
+    /// Scrolling done during the frame. The vertical wheel is the second component.
+    pub fn scroll_delta(&self) -> (f64, f64) {
+        return self._scroll_delta;
+    }
+
+    /// Text typed during the frame.
+    pub fn text(&self) -> &str {
+        return &self._text;
+    }
+
+    /// Copies the state of the primary gamepad, or releases everything if there is none.
+    pub fn update_gamepad(&mut self, state: Option<&GamepadSnapshot>) {
+        let rest = GamepadSnapshot::default();
+        self._gamepad_connected = state.is_some();
+        let state = state.unwrap_or(&rest);
+        for i in 0..GAMEPAD_BUTTON_COUNT {
+            let button = glfw::GamepadButton::from_i32(i as i32).unwrap();
+            self.set_gamepad_button(button, state._buttons[i]);
+        }
+        self._gamepad_axes = state._axes;
+    }
+
+    pub fn gamepad_connected(&self) -> bool {
+        return self._gamepad_connected;
+    }
+
+    pub fn is_gamepad_button_down(&self, button: glfw::GamepadButton) -> bool {
+        return self._gamepad_buttons.contains(&button);
+    }
+
+    pub fn is_gamepad_button_pressed(&self, button: glfw::GamepadButton) -> bool {
+        return self._gamepad_pressed.contains(&button);
+    }
+
+    pub fn is_gamepad_button_released(&self, button: glfw::GamepadButton) -> bool {
+        return self._gamepad_released.contains(&button);
+    }
+
+    /// Value of a gamepad axis, between -1 and 1 for the sticks (down and right being positive)
+    /// and between 0 and 1 for the triggers.
+    pub fn gamepad_axis(&self, axis: glfw::GamepadAxis) -> f32 {
+        return self._gamepad_axes[axis as usize];
+    }
+
+    pub fn set_gamepad_button(&mut self, button: glfw::GamepadButton, down: bool) {
+        if down && self._gamepad_buttons.insert(button) {
+            self._gamepad_pressed.insert(button);
+        } else if !down && self._gamepad_buttons.remove(&button) {
+            self._gamepad_released.insert(button);
+        }
+    }
+
+    pub fn set_gamepad_axis(&mut self, axis: glfw::GamepadAxis, value: f32) {
+        self._gamepad_axes[axis as usize] = value;
+    }
+}
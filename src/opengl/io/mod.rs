@@ -1,3 +1,13 @@
+pub mod actions;
 pub mod cam_controller;
+pub mod gamepad;
+pub mod input;
 
-pub use cam_controller::CameraController;
+pub use actions::{ActionMap, Binding, InputSource};
+pub use cam_controller::{
+    CameraController, CameraRig, FlyController, OrbitController, PanZoomController,
+};
+pub use gamepad::{
+    GamepadEvent, GamepadSnapshot, GamepadSource, Gamepads, GlfwGamepads, MockGamepads,
+};
+pub use input::InputState;
@@ -17,6 +17,11 @@ pub fn v4_to_v3(v: &V4) -> V3 {
     return [v[0] / w, v[1] / w, v[2] / w];
 }
 
+/// Drops the W component of a direction, without dividing by it.
+pub fn v4_to_v3_dir(v: &V4) -> V3 {
+    return [v[0], v[1], v[2]];
+}
+
 pub fn mat3_to_mat4(mat3: &[V3; 3]) -> [V4; 4] {
     let mut result = [
         [1.0, 0.0, 0.0, 0.0],
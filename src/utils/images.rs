@@ -1,4 +1,5 @@
 use image::io::Reader;
+use std::io::Cursor;
 use std::path::Path;
 
 pub fn load(path: &str) -> (image::DynamicImage, i32, i32) {
@@ -9,3 +10,17 @@ pub fn load(path: &str) -> (image::DynamicImage, i32, i32) {
 
     return (img_data, width, height);
 }
+
+/// Decodes an encoded image (PNG, JPEG, ...) stored in memory.
+pub fn load_from_memory(bytes: &[u8]) -> (image::DynamicImage, i32, i32) {
+    let img_data = Reader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .unwrap()
+        .decode()
+        .unwrap();
+
+    let width = img_data.width().try_into().unwrap();
+    let height = img_data.height().try_into().unwrap();
+
+    return (img_data, width, height);
+}
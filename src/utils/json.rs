@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+/// A parsed JSON value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(HashMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    /// Returns the member of an object with the given key.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        return match self {
+            JsonValue::Object(members) => members.get(key),
+            _ => None,
+        };
+    }
+
+    /// Returns the element of an array at the given index.
+    pub fn at(&self, index: usize) -> Option<&JsonValue> {
+        return match self {
+            JsonValue::Array(elements) => elements.get(index),
+            _ => None,
+        };
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        return match self {
+            JsonValue::Number(n) => Some(*n),
+            _ => None,
+        };
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        return self.as_f64().map(|n| n as f32);
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        return self.as_f64().map(|n| n as usize);
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        return match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        };
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        return match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        };
+    }
+
+    /// Returns the elements of an array, or an empty slice for any other value.
+    pub fn as_array(&self) -> &[JsonValue] {
+        return match self {
+            JsonValue::Array(elements) => elements,
+            _ => &[],
+        };
+    }
+
+    /// Reads an array of numbers as `f32`.
+    pub fn as_f32_vec(&self) -> Vec<f32> {
+        return self.as_array().iter().filter_map(|v| v.as_f32()).collect();
+    }
+}
+
+/// Parses a JSON document, panicking with the position of the error if it is invalid.
+pub fn parse(content: &str) -> JsonValue {
+    let mut parser = Parser {
+        _chars: content.chars().collect(),
+        _pos: 0,
+    };
+    parser.skip_whitespace();
+    let value = parser.value();
+    parser.skip_whitespace();
+    if parser._pos != parser._chars.len() {
+        parser.error("unexpected trailing characters");
+    }
+    return value;
+}
+
+struct Parser {
+    _chars: Vec<char>,
+    _pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> ! {
+        panic!("JSON error at character {}: {message}.", self._pos);
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self._chars.get(self._pos).copied();
+    }
+
+    fn next(&mut self) -> char {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => self.error("unexpected end of input"),
+        };
+        self._pos += 1;
+        return c;
+    }
+
+    fn expect(&mut self, expected: char) {
+        if self.next() != expected {
+            self.error(&format!("expected '{expected}'"));
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self._pos += 1;
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> JsonValue {
+        for c in word.chars() {
+            self.expect(c);
+        }
+        return value;
+    }
+
+    fn value(&mut self) -> JsonValue {
+        return match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => JsonValue::String(self.string()),
+            Some('t') => self.literal("true", JsonValue::Bool(true)),
+            Some('f') => self.literal("false", JsonValue::Bool(false)),
+            Some('n') => self.literal("null", JsonValue::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => self.error("expected a value"),
+        };
+    }
+
+    fn object(&mut self) -> JsonValue {
+        let mut members = HashMap::new();
+        self.expect('{');
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self._pos += 1;
+            return JsonValue::Object(members);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string();
+            self.skip_whitespace();
+            self.expect(':');
+            self.skip_whitespace();
+            members.insert(key, self.value());
+            self.skip_whitespace();
+            match self.next() {
+                ',' => continue,
+                '}' => break,
+                _ => self.error("expected ',' or '}'"),
+            }
+        }
+        return JsonValue::Object(members);
+    }
+
+    fn array(&mut self) -> JsonValue {
+        let mut elements = vec![];
+        self.expect('[');
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self._pos += 1;
+            return JsonValue::Array(elements);
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value());
+            self.skip_whitespace();
+            match self.next() {
+                ',' => continue,
+                ']' => break,
+                _ => self.error("expected ',' or ']'"),
+            }
+        }
+        return JsonValue::Array(elements);
+    }
+
+    fn string(&mut self) -> String {
+        let mut result = String::new();
+        self.expect('"');
+        loop {
+            match self.next() {
+                '"' => break,
+                '\\' => match self.next() {
+                    '"' => result.push('"'),
+                    '\\' => result.push('\\'),
+                    '/' => result.push('/'),
+                    'b' => result.push('\u{8}'),
+                    'f' => result.push('\u{c}'),
+                    'n' => result.push('\n'),
+                    'r' => result.push('\r'),
+                    't' => result.push('\t'),
+                    'u' => {
+                        let mut code = self.hex4();
+                        // Characters outside the BMP are written as surrogate pairs, and a lone
+                        // high surrogate leaves what follows it to be read on its own
+                        if (0xD800..0xDC00).contains(&code) {
+                            if let Some(low) = self.low_surrogate() {
+                                self._pos += 6;
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                        }
+                        result.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    _ => self.error("invalid escape sequence"),
+                },
+                c => result.push(c),
+            }
+        }
+        return result;
+    }
+
+    /// The low surrogate escaped at the current position, without reading it.
+    fn low_surrogate(&self) -> Option<u32> {
+        let escape = self._chars.get(self._pos..self._pos + 6)?;
+        if escape[0] != '\\' || escape[1] != 'u' {
+            return None;
+        }
+        let mut code = 0;
+        for c in &escape[2..] {
+            code = code * 16 + c.to_digit(16)?;
+        }
+        return (0xDC00..0xE000).contains(&code).then_some(code);
+    }
+
+    fn hex4(&mut self) -> u32 {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = match self.next().to_digit(16) {
+                Some(d) => d,
+                None => self.error("invalid unicode escape"),
+            };
+            code = code * 16 + digit;
+        }
+        return code;
+    }
+
+    fn number(&mut self) -> JsonValue {
+        let start = self._pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || "+-.eE".contains(c) {
+                self._pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text: String = self._chars[start..self._pos].iter().collect();
+        return match text.parse::<f64>() {
+            Ok(n) => JsonValue::Number(n),
+            Err(_) => self.error("invalid number"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_literals_and_numbers() {
+        assert_eq!(parse("null"), JsonValue::Null);
+        assert_eq!(parse(" true "), JsonValue::Bool(true));
+        assert_eq!(parse("false"), JsonValue::Bool(false));
+        assert_eq!(parse("-12.5e1"), JsonValue::Number(-125.0));
+        assert_eq!(parse("0"), JsonValue::Number(0.0));
+    }
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(r#"{"a": [1, 2, {"b": null}], "c": {}, "d": []}"#);
+        assert_eq!(
+            value.get("a").and_then(|a| a.at(1)),
+            Some(&JsonValue::Number(2.0))
+        );
+        assert_eq!(
+            value
+                .get("a")
+                .and_then(|a| a.at(2))
+                .and_then(|o| o.get("b")),
+            Some(&JsonValue::Null)
+        );
+        assert_eq!(value.get("c"), Some(&JsonValue::Object(HashMap::new())));
+        assert_eq!(value.get("d").map(|d| d.as_array().len()), Some(0));
+        assert_eq!(value.get("a").unwrap().as_f32_vec(), vec![1.0, 2.0]);
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let value = parse(r#""a\"b\\c\/d\n\té""#);
+        assert_eq!(value.as_str(), Some("a\"b\\c/d\n\té"));
+    }
+
+    #[test]
+    fn parses_surrogate_pairs() {
+        assert_eq!(parse(r#""\uD83D\uDE00""#).as_str(), Some("\u{1F600}"));
+        assert_eq!(parse(r#""a\uD83D\uDE00b""#).as_str(), Some("a\u{1F600}b"));
+    }
+
+    #[test]
+    fn replaces_lone_surrogates() {
+        assert_eq!(parse(r#""\uD800A""#).as_str(), Some("\u{FFFD}A"));
+        assert_eq!(parse(r#""\uD800x""#).as_str(), Some("\u{FFFD}x"));
+        assert_eq!(parse(r#""\uDC00""#).as_str(), Some("\u{FFFD}"));
+        assert_eq!(parse(r#""\uD800\n""#).as_str(), Some("\u{FFFD}\n"));
+        assert_eq!(parse(r#""\uD800\u0041""#).as_str(), Some("\u{FFFD}A"));
+        assert_eq!(
+            parse(r#""\uD800\uD800\uDC00""#).as_str(),
+            Some("\u{FFFD}\u{10000}")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected trailing characters")]
+    fn rejects_trailing_characters() {
+        parse("[1] 2");
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected end of input")]
+    fn rejects_unclosed_objects() {
+        parse(r#"{"a": 1"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected ',' or '}'")]
+    fn rejects_mismatched_brackets() {
+        parse(r#"{"a": 1]"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid escape sequence")]
+    fn rejects_invalid_escapes() {
+        parse(r#""\q""#);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid number")]
+    fn rejects_invalid_numbers() {
+        parse("1.2.3");
+    }
+}
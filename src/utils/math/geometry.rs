@@ -0,0 +1,504 @@
+use crate::utils::math::linalg;
+use crate::utils::types::{V3, V4};
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|===========================================| Ray |===========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A half-line starting at `_origin` and going along `_direction`, which is normalized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub _origin: V3,
+    pub _direction: V3,
+}
+
+impl Ray {
+    pub fn new(origin: &V3, direction: &V3) -> Ray {
+        return Ray {
+            _origin: *origin,
+            _direction: linalg::normalize_v3(direction),
+        };
+    }
+
+    /// Point at the given distance from the origin.
+    pub fn at(&self, distance: f32) -> V3 {
+        return linalg::add_v3(&self._origin, &linalg::scale_v3(&self._direction, distance));
+    }
+
+    /// Distance along the ray of the point closest to `point`, which can be negative if it is
+    /// behind the origin.
+    pub fn closest_distance(&self, point: &V3) -> f32 {
+        return linalg::dot_v3(&linalg::sub_v3(point, &self._origin), &self._direction);
+    }
+
+    /// Intersects the ray with a triangle, from either side (Möller-Trumbore). Returns the
+    /// distance to the hit and its barycentric coordinates relative to `b` and `c`.
+    pub fn intersect_triangle(&self, a: &V3, b: &V3, c: &V3) -> Option<(f32, f32, f32)> {
+        let edge1 = linalg::sub_v3(b, a);
+        let edge2 = linalg::sub_v3(c, a);
+        let p = linalg::cross_v3(&self._direction, &edge2);
+        let determinant = linalg::dot_v3(&edge1, &p);
+        // The ray is parallel to the plane of the triangle
+        if determinant.abs() < 1e-8 {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let s = linalg::sub_v3(&self._origin, a);
+        let u = linalg::dot_v3(&s, &p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = linalg::cross_v3(&s, &edge1);
+        let v = linalg::dot_v3(&self._direction, &q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = linalg::dot_v3(&edge2, &q) * inverse;
+        if distance < 0.0 {
+            return None;
+        }
+        return Some((distance, u, v));
+    }
+
+    /// Distance at which the ray enters a sphere, or 0 if it starts inside it.
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<f32> {
+        let to_center = linalg::sub_v3(&sphere._center, &self._origin);
+        let along = linalg::dot_v3(&to_center, &self._direction);
+        let squared_distance = linalg::dot_v3(&to_center, &to_center) - along * along;
+        let squared_radius = sphere._radius * sphere._radius;
+        if squared_distance > squared_radius {
+            return None;
+        }
+        let half_chord = (squared_radius - squared_distance).sqrt();
+        if along + half_chord < 0.0 {
+            return None;
+        }
+        return Some((along - half_chord).max(0.0));
+    }
+
+    /// Distance at which the ray enters a box, or 0 if it starts inside it.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut near = 0.0_f32;
+        let mut far = f32::INFINITY;
+        for i in 0..3 {
+            // Dividing by a null direction gives infinities, which the comparisons handle
+            let inverse = 1.0 / self._direction[i];
+            let t1 = (aabb._min[i] - self._origin[i]) * inverse;
+            let t2 = (aabb._max[i] - self._origin[i]) * inverse;
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if near > far {
+                return None;
+            }
+        }
+        return Some(near);
+    }
+}
+
+/// Where a ray hit the triangles of an object.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// Distance from the origin of the ray.
+    pub _distance: f32,
+    pub _point: V3,
+    /// Index of the triangle, counted as OpenGL does for `gl_PrimitiveID`.
+    pub _triangle: usize,
+    /// Index of the vertex of the triangle closest to the hit.
+    pub _vertex: u32,
+    /// Barycentric coordinates of the hit, relative to the three vertices of the triangle.
+    pub _barycentric: V3,
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|====================================| Bounding volumes |=====================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub _min: V3,
+    pub _max: V3,
+}
+
+impl Aabb {
+    pub fn new(min: &V3, max: &V3) -> Aabb {
+        return Aabb {
+            _min: *min,
+            _max: *max,
+        };
+    }
+
+    /// A box containing nothing, which grows to the first point added to it.
+    pub fn empty() -> Aabb {
+        return Aabb {
+            _min: [f32::INFINITY; 3],
+            _max: [f32::NEG_INFINITY; 3],
+        };
+    }
+
+    pub fn from_points(points: &[V3]) -> Aabb {
+        let mut aabb = Aabb::empty();
+        for point in points {
+            aabb.add_point(point);
+        }
+        return aabb;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return (0..3).any(|i| self._min[i] > self._max[i]);
+    }
+
+    pub fn add_point(&mut self, point: &V3) {
+        for i in 0..3 {
+            self._min[i] = self._min[i].min(point[i]);
+            self._max[i] = self._max[i].max(point[i]);
+        }
+    }
+
+    pub fn center(&self) -> V3 {
+        return linalg::scale_v3(&linalg::add_v3(&self._min, &self._max), 0.5);
+    }
+
+    pub fn size(&self) -> V3 {
+        return linalg::sub_v3(&self._max, &self._min);
+    }
+
+    pub fn contains(&self, point: &V3) -> bool {
+        return (0..3).all(|i| self._min[i] <= point[i] && point[i] <= self._max[i]);
+    }
+
+    /// Grows the box to contain another one.
+    pub fn merge(&mut self, other: &Aabb) {
+        if other.is_empty() {
+            return;
+        }
+        self.add_point(&other._min);
+        self.add_point(&other._max);
+    }
+
+    pub fn corners(&self) -> [V3; 8] {
+        let (min, max) = (self._min, self._max);
+        return [
+            [min[0], min[1], min[2]],
+            [max[0], min[1], min[2]],
+            [min[0], max[1], min[2]],
+            [max[0], max[1], min[2]],
+            [min[0], min[1], max[2]],
+            [max[0], min[1], max[2]],
+            [min[0], max[1], max[2]],
+            [max[0], max[1], max[2]],
+        ];
+    }
+
+    /// Box containing this one once transformed, which is larger than needed for rotations.
+    pub fn transform(&self, matrix: &[V4; 4]) -> Aabb {
+        if self.is_empty() {
+            return *self;
+        }
+        let corners = self
+            .corners()
+            .map(|corner| linalg::transform_point(matrix, &corner));
+        return Aabb::from_points(&corners);
+    }
+
+    /// Sphere passing through the corners of the box.
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        if self.is_empty() {
+            return BoundingSphere::empty();
+        }
+        return BoundingSphere::new(&self.center(), linalg::norm_v3(&self.size()) / 2.0);
+    }
+}
+
+/// Oriented bounding box, made of a center, three orthonormal axes and the half of its size
+/// along each of them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Obb {
+    pub _center: V3,
+    pub _axes: [V3; 3],
+    pub _half_extents: V3,
+}
+
+impl Obb {
+    /// Places a box given in local coordinates with a transform made of a rotation, a scale
+    /// along the local axes and a translation. Shears are not kept.
+    pub fn from_aabb(aabb: &Aabb, matrix: &[V4; 4]) -> Obb {
+        if aabb.is_empty() {
+            return Obb {
+                _center: [0.0; 3],
+                _axes: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                _half_extents: [-1.0; 3],
+            };
+        }
+        let local_half = linalg::scale_v3(&aabb.size(), 0.5);
+        let mut axes = [[0.0; 3]; 3];
+        let mut half_extents = [0.0; 3];
+        for i in 0..3 {
+            let mut axis = [0.0; 3];
+            axis[i] = 1.0;
+            let axis = linalg::transform_vector(matrix, &axis);
+            let length = linalg::norm_v3(&axis);
+            axes[i] = if length > 0.0 {
+                linalg::scale_v3(&axis, 1.0 / length)
+            } else {
+                axis
+            };
+            half_extents[i] = local_half[i] * length;
+        }
+        return Obb {
+            _center: linalg::transform_point(matrix, &aabb.center()),
+            _axes: axes,
+            _half_extents: half_extents,
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self._half_extents.iter().any(|h| *h < 0.0);
+    }
+
+    pub fn corners(&self) -> [V3; 8] {
+        let mut corners = [self._center; 8];
+        for (c, corner) in corners.iter_mut().enumerate() {
+            for i in 0..3 {
+                let sign = if c & (1 << i) == 0 { -1.0 } else { 1.0 };
+                let offset = linalg::scale_v3(&self._axes[i], sign * self._half_extents[i]);
+                *corner = linalg::add_v3(corner, &offset);
+            }
+        }
+        return corners;
+    }
+
+    pub fn contains(&self, point: &V3) -> bool {
+        let offset = linalg::sub_v3(point, &self._center);
+        return (0..3)
+            .all(|i| linalg::dot_v3(&offset, &self._axes[i]).abs() <= self._half_extents[i]);
+    }
+
+    pub fn to_aabb(&self) -> Aabb {
+        if self.is_empty() {
+            return Aabb::empty();
+        }
+        return Aabb::from_points(&self.corners());
+    }
+}
+
+/// Sphere containing an object, with a negative radius when there is nothing to contain.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub _center: V3,
+    pub _radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: &V3, radius: f32) -> BoundingSphere {
+        return BoundingSphere {
+            _center: *center,
+            _radius: radius,
+        };
+    }
+
+    pub fn empty() -> BoundingSphere {
+        return BoundingSphere::new(&[0.0; 3], -1.0);
+    }
+
+    /// Sphere around the points, found with Ritter's algorithm. It is at most a few percent
+    /// larger than the smallest one.
+    pub fn from_points(points: &[V3]) -> BoundingSphere {
+        if points.is_empty() {
+            return BoundingSphere::empty();
+        }
+        let farthest_from = |from: &V3| -> V3 {
+            let mut farthest = points[0];
+            let mut distance = 0.0;
+            for point in points {
+                let d = linalg::norm_v3(&linalg::sub_v3(point, from));
+                if d > distance {
+                    distance = d;
+                    farthest = *point;
+                }
+            }
+            return farthest;
+        };
+        let a = farthest_from(&points[0]);
+        let b = farthest_from(&a);
+        let mut sphere = BoundingSphere::new(
+            &linalg::lerp_v3(&a, &b, 0.5),
+            linalg::norm_v3(&linalg::sub_v3(&b, &a)) / 2.0,
+        );
+        for point in points {
+            sphere.add_point(point);
+        }
+        return sphere;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self._radius < 0.0;
+    }
+
+    /// Grows the sphere just enough to contain the point.
+    pub fn add_point(&mut self, point: &V3) {
+        if self.is_empty() {
+            *self = BoundingSphere::new(point, 0.0);
+            return;
+        }
+        let offset = linalg::sub_v3(point, &self._center);
+        let distance = linalg::norm_v3(&offset);
+        if distance <= self._radius {
+            return;
+        }
+        let radius = (self._radius + distance) / 2.0;
+        let shift = linalg::scale_v3(&offset, (radius - self._radius) / distance);
+        self._center = linalg::add_v3(&self._center, &shift);
+        self._radius = radius;
+    }
+
+    /// Grows the sphere to contain another one.
+    pub fn merge(&mut self, other: &BoundingSphere) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = *other;
+            return;
+        }
+        let offset = linalg::sub_v3(&other._center, &self._center);
+        let distance = linalg::norm_v3(&offset);
+        if distance + other._radius <= self._radius {
+            return;
+        }
+        if distance + self._radius <= other._radius {
+            *self = *other;
+            return;
+        }
+        let radius = (distance + self._radius + other._radius) / 2.0;
+        let shift = linalg::scale_v3(&offset, (radius - self._radius) / distance);
+        self._center = linalg::add_v3(&self._center, &shift);
+        self._radius = radius;
+    }
+
+    pub fn contains(&self, point: &V3) -> bool {
+        return linalg::norm_v3(&linalg::sub_v3(point, &self._center)) <= self._radius;
+    }
+
+    /// Sphere containing this one once transformed, scaled by the largest scale of the matrix.
+    pub fn transform(&self, matrix: &[V4; 4]) -> BoundingSphere {
+        if self.is_empty() {
+            return *self;
+        }
+        let scale = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            .iter()
+            .map(|axis| linalg::norm_v3(&linalg::transform_vector(matrix, axis)))
+            .fold(0.0, f32::max);
+        return BoundingSphere::new(
+            &linalg::transform_point(matrix, &self._center),
+            self._radius * scale,
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=========================================| Frustum |=========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Plane made of the points `p` for which `dot(_normal, p) + _distance` is zero. Points on the
+/// side the normal points to are in front of it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane {
+    pub _normal: V3,
+    pub _distance: f32,
+}
+
+impl Plane {
+    /// Plane from its coefficients (a, b, c, d), normalized so that `signed_distance` is a
+    /// distance. Degenerate planes, with no normal, are kept as they are.
+    pub fn from_coefficients(coefficients: &V4) -> Plane {
+        let normal = [coefficients[0], coefficients[1], coefficients[2]];
+        let length = linalg::norm_v3(&normal);
+        if length <= f32::EPSILON {
+            return Plane {
+                _normal: normal,
+                _distance: coefficients[3],
+            };
+        }
+        return Plane {
+            _normal: linalg::scale_v3(&normal, 1.0 / length),
+            _distance: coefficients[3] / length,
+        };
+    }
+
+    /// Distance from the plane to the point, negative behind it.
+    pub fn signed_distance(&self, point: &V3) -> f32 {
+        return linalg::dot_v3(&self._normal, point) + self._distance;
+    }
+}
+
+/// The region of space seen by a camera, bounded by six planes facing inwards, in the order
+/// left, right, bottom, top, near and far.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frustum {
+    pub _planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a view-projection matrix (Gribb-Hartmann), assuming the clip range
+    /// of OpenGL. With a [0, 1] clip range, as used by reversed depth, the far plane is pushed
+    /// back, which keeps the tests conservative. An infinite far plane never rejects anything.
+    pub fn from_matrix(matrix: &[V4; 4]) -> Frustum {
+        let row = |sign: f32, i: usize| -> Plane {
+            let mut coefficients = [0.0; 4];
+            for (j, coefficient) in coefficients.iter_mut().enumerate() {
+                *coefficient = matrix[3][j] + sign * matrix[i][j];
+            }
+            return Plane::from_coefficients(&coefficients);
+        };
+        return Frustum {
+            _planes: [
+                row(1.0, 0),
+                row(-1.0, 0),
+                row(1.0, 1),
+                row(-1.0, 1),
+                row(1.0, 2),
+                row(-1.0, 2),
+            ],
+        };
+    }
+
+    pub fn contains_point(&self, point: &V3) -> bool {
+        return self
+            ._planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0);
+    }
+
+    /// Whether the box may be visible. Boxes close to the edges of the frustum can be kept while
+    /// being outside, but a box partly inside is never rejected.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        if aabb.is_empty() {
+            return false;
+        }
+        for plane in self._planes.iter() {
+            // The corner furthest along the normal is the last one to leave the plane
+            let mut corner = aabb._min;
+            for i in 0..3 {
+                if plane._normal[i] >= 0.0 {
+                    corner[i] = aabb._max[i];
+                }
+            }
+            if plane.signed_distance(&corner) < 0.0 {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /// Whether the sphere may be visible, with the same tolerance as `intersects_aabb`.
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        if sphere.is_empty() {
+            return false;
+        }
+        return self
+            ._planes
+            .iter()
+            .all(|plane| plane.signed_distance(&sphere._center) >= -sphere._radius);
+    }
+}
@@ -39,6 +39,23 @@ pub fn cross_v3(a: &V3, b: &V3) -> V3 {
     return [ya * zb - za * yb, za * xb - xa * zb, xa * yb - ya * xb];
 }
 
+pub fn add_v3(a: &V3, b: &V3) -> V3 {
+    return [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+}
+
+pub fn scale_v3(a: &V3, factor: f32) -> V3 {
+    return [a[0] * factor, a[1] * factor, a[2] * factor];
+}
+
+pub fn dot_v3(a: &V3, b: &V3) -> f32 {
+    return a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+}
+
+/// Linear interpolation, giving `a` for t = 0 and `b` for t = 1.
+pub fn lerp_v3(a: &V3, b: &V3, t: f32) -> V3 {
+    return add_v3(a, &scale_v3(&sub_v3(b, a), t));
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 //|===============================| Matrix multiplication |=====================================|//
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -70,6 +87,18 @@ pub fn mat4_mul_v4(m1: &[V4; 4], v: &V4) -> V4 {
     return result;
 }
 
+/// Applies a 4x4 transform to a point, dividing by the resulting W.
+pub fn transform_point(m1: &[V4; 4], point: &V3) -> V3 {
+    let v = mat4_mul_v4(m1, &[point[0], point[1], point[2], 1.0]);
+    return [v[0] / v[3], v[1] / v[3], v[2] / v[3]];
+}
+
+/// Applies a 4x4 transform to a direction, leaving out the translation.
+pub fn transform_vector(m1: &[V4; 4], vector: &V3) -> V3 {
+    let v = mat4_mul_v4(m1, &[vector[0], vector[1], vector[2], 0.0]);
+    return [v[0], v[1], v[2]];
+}
+
 pub fn mat6_mul3(m1: &[V6], m2: &[V3; 3]) -> Vec<V6> {
     let mut result = Vec::<V6>::new();
     for i in 0..m1.len() {
@@ -128,6 +157,50 @@ pub fn transpose4x4(m1: &Vec<V4>) -> Vec<V4> {
     return result;
 }
 
+/// Inverse of a 4x4 matrix by Gauss-Jordan elimination, or `None` if it is singular. A pivot is
+/// taken as zero relative to the largest entry of its column, so that matrices with very small
+/// or very large entries, such as projections with a distant far plane, can still be inverted.
+pub fn inverse4(m1: &[V4; 4]) -> Option<[V4; 4]> {
+    let mut m = *m1;
+    let mut result = eye4();
+    let scales: [f32; 4] = [0, 1, 2, 3].map(|col| {
+        return m1
+            .iter()
+            .fold(0.0_f32, |scale, row| scale.max(row[col].abs()));
+    });
+    for col in 0..4 {
+        // The largest pivot keeps the elimination stable
+        let mut pivot = col;
+        for row in col + 1..4 {
+            if m[row][col].abs() > m[pivot][col].abs() {
+                pivot = row;
+            }
+        }
+        if m[pivot][col].abs() <= scales[col] * 4.0 * f32::EPSILON {
+            return None;
+        }
+        m.swap(col, pivot);
+        result.swap(col, pivot);
+
+        let factor = 1.0 / m[col][col];
+        for j in 0..4 {
+            m[col][j] *= factor;
+            result[col][j] *= factor;
+        }
+        for row in 0..4 {
+            if row == col {
+                continue;
+            }
+            let factor = m[row][col];
+            for j in 0..4 {
+                m[row][j] -= factor * m[col][j];
+                result[row][j] -= factor * result[col][j];
+            }
+        }
+    }
+    return Some(result);
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 //|================================| Important matrices |=======================================|//
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -262,9 +335,96 @@ pub fn perspective(xmin: f32, xmax: f32, ymin: f32, ymax: f32, zmin: f32, zmax:
     let tpb = ymax + ymin;
     let fpn = zmax + zmin;
     return [
-        [2.0 * zmin / rml, 0.0, 0.0, 0.0],
-        [0.0, 2.0 * zmin / tmb, 0.0, 0.0],
-        [rpl / rml, tpb / tmb, -fpn / fmn, -1.0],
-        [0.0, 0.0, -2.0 * zmax * zmin / fmn, 0.0],
+        [2.0 * zmin / rml, 0.0, rpl / rml, 0.0],
+        [0.0, 2.0 * zmin / tmb, tpb / tmb, 0.0],
+        [0.0, 0.0, -fpn / fmn, -2.0 * zmax * zmin / fmn],
+        [0.0, 0.0, -1.0, 0.0],
+    ];
+}
+
+/// Symmetric perspective projection from the vertical field of view in degrees and the aspect
+/// ratio (width over height). Depth goes from -1 at the near plane to 1 at the far one, which
+/// can be at infinity.
+pub fn perspective_fov(fov_y: f32, aspect: f32, near: f32, far: Option<f32>) -> [V4; 4] {
+    let f = 1.0 / (fov_y.to_radians() / 2.0).tan();
+    let (a, b) = match far {
+        Some(far) => ((far + near) / (near - far), 2.0 * far * near / (near - far)),
+        None => (-1.0, -2.0 * near),
+    };
+    return [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, a, b],
+        [0.0, 0.0, -1.0, 0.0],
+    ];
+}
+
+/// Same as `perspective_fov`, but with a reversed depth going from 1 at the near plane to 0 at
+/// the far one. It is meant for a [0, 1] clip range (`glClipControl`), where the precision of
+/// floating point depth is spread evenly over the distance.
+pub fn perspective_reverse_z(fov_y: f32, aspect: f32, near: f32, far: Option<f32>) -> [V4; 4] {
+    let f = 1.0 / (fov_y.to_radians() / 2.0).tan();
+    let (a, b) = match far {
+        Some(far) => (near / (far - near), far * near / (far - near)),
+        None => (0.0, near),
+    };
+    return [
+        [f / aspect, 0.0, 0.0, 0.0],
+        [0.0, f, 0.0, 0.0],
+        [0.0, 0.0, a, b],
+        [0.0, 0.0, -1.0, 0.0],
     ];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_identity(m: &[V4; 4]) {
+        for (i, row) in m.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!(
+                    (value - expected).abs() < 1e-4,
+                    "Entry ({i}, {j}) is {value} in {m:?}."
+                );
+            }
+        }
+    }
+
+    fn assert_inverts(m: &[V4; 4]) {
+        let inverse = inverse4(m).expect("The matrix should be invertible.");
+        assert_identity(&mat4_mul4(m, &inverse));
+        assert_identity(&mat4_mul4(&inverse, m));
+    }
+
+    #[test]
+    fn inverts_matrices() {
+        assert_inverts(&eye4());
+        assert_inverts(&look_at(
+            &[1.0, 2.0, 3.0],
+            &[0.0, 1.0, 0.0],
+            &normalize_v3(&[1.0, 0.5, 2.0]),
+        ));
+        assert_inverts(&ortho(-4.0, 2.0, -1.0, 3.0, 0.5, 50.0));
+        assert_inverts(&perspective_fov(60.0, 1.5, 0.1, Some(1000.0)));
+        assert_inverts(&perspective_reverse_z(60.0, 1.5, 0.1, Some(1000.0)));
+    }
+
+    #[test]
+    fn inverts_infinite_projections() {
+        assert_inverts(&perspective_fov(60.0, 1.5, 0.1, None));
+        assert_inverts(&perspective_reverse_z(60.0, 1.5, 0.1, None));
+        // A very distant far plane is almost the same matrix
+        assert_inverts(&perspective_fov(60.0, 1.5, 0.01, Some(1e7)));
+    }
+
+    #[test]
+    fn rejects_singular_matrices() {
+        let mut m = eye4();
+        m[2] = [1.0, 2.0, 0.0, 0.0];
+        m[3] = [2.0, 4.0, 0.0, 0.0];
+        assert_eq!(inverse4(&m), None);
+        assert_eq!(inverse4(&[[0.0; 4]; 4]), None);
+    }
+}
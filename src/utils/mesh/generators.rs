@@ -0,0 +1,398 @@
+use crate::utils::math::linalg;
+use crate::utils::mesh::Mesh;
+use crate::utils::types::V3;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/*
+Every generator returns a mesh whose vertices are laid out as position (3), normal (3) and
+texture coordinates (2), matching the default layouts of a `Shape`. The shapes are centered at
+the origin with the Y axis pointing up, and the triangles are wound counter-clockwise when seen
+from the outside.
+*/
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|==================================| Flat primitives |========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A cube with the given side length, each face split into `subdivisions` x `subdivisions` quads.
+pub fn cube(size: f32, subdivisions: u32) -> Mesh {
+    let h = size / 2.0;
+    let n = subdivisions.max(1);
+    let mut mesh = Mesh::new_pnt();
+    // (origin, u axis, v axis, normal) for each face, with u x v pointing outwards
+    let faces: [(V3, V3, V3, V3); 6] = [
+        ([-h, -h, h], [size, 0.0, 0.0], [0.0, size, 0.0], [0.0, 0.0, 1.0]),
+        ([h, -h, -h], [-size, 0.0, 0.0], [0.0, size, 0.0], [0.0, 0.0, -1.0]),
+        ([h, -h, h], [0.0, 0.0, -size], [0.0, size, 0.0], [1.0, 0.0, 0.0]),
+        ([-h, -h, -h], [0.0, 0.0, size], [0.0, size, 0.0], [-1.0, 0.0, 0.0]),
+        ([-h, h, h], [size, 0.0, 0.0], [0.0, 0.0, -size], [0.0, 1.0, 0.0]),
+        ([-h, -h, -h], [size, 0.0, 0.0], [0.0, 0.0, size], [0.0, -1.0, 0.0]),
+    ];
+    for (origin, u, v, normal) in faces.iter() {
+        grid(&mut mesh, origin, u, v, normal, n, n);
+    }
+    return mesh;
+}
+
+/// A flat grid on the XZ plane facing +Y.
+pub fn plane(width: f32, depth: f32, segments_x: u32, segments_z: u32) -> Mesh {
+    let mut mesh = Mesh::new_pnt();
+    grid(
+        &mut mesh,
+        &[-width / 2.0, 0.0, depth / 2.0],
+        &[width, 0.0, 0.0],
+        &[0.0, 0.0, -depth],
+        &[0.0, 1.0, 0.0],
+        segments_x.max(1),
+        segments_z.max(1),
+    );
+    return mesh;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|===================================| Round primitives |======================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A sphere made of `segments` meridians and `rings` parallels.
+pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Mesh {
+    let rings = rings.max(2);
+    let mut profile = vec![];
+    for j in 0..=rings {
+        let theta = PI * (1.0 - j as f32 / rings as f32);
+        let (s, c) = theta.sin_cos();
+        profile.push(ProfilePoint::new(radius * s, radius * c, s, c, j as f32 / rings as f32));
+    }
+    let mut mesh = Mesh::new_pnt();
+    revolve(&mut mesh, &profile, segments.max(3));
+    return mesh;
+}
+
+/// A sphere made by recursively subdividing an icosahedron. Each subdivision multiplies the
+/// amount of triangles by four.
+pub fn icosphere(radius: f32, subdivisions: u32) -> Mesh {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut positions: Vec<V3> = vec![
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|p| linalg::normalize_v3(p))
+    .collect();
+    let mut faces: Vec<[u32; 3]> = vec![
+        [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+        [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+        [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+        [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::<(u32, u32), u32>::new();
+        let mut midpoint = |a: u32, b: u32, positions: &mut Vec<V3>| -> u32 {
+            let key = (a.min(b), a.max(b));
+            if let Some(index) = midpoints.get(&key) {
+                return *index;
+            }
+            let pa = positions[a as usize];
+            let pb = positions[b as usize];
+            let middle = [(pa[0] + pb[0]) / 2.0, (pa[1] + pb[1]) / 2.0, (pa[2] + pb[2]) / 2.0];
+            positions.push(linalg::normalize_v3(&middle));
+            let index = (positions.len() - 1) as u32;
+            midpoints.insert(key, index);
+            return index;
+        };
+        let mut new_faces = Vec::with_capacity(faces.len() * 4);
+        for [a, b, c] in faces.iter().copied() {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+            new_faces.push([a, ab, ca]);
+            new_faces.push([b, bc, ab]);
+            new_faces.push([c, ca, bc]);
+            new_faces.push([ab, bc, ca]);
+        }
+        faces = new_faces;
+    }
+
+    let mut mesh = Mesh::new_pnt();
+    for p in positions.iter() {
+        let (u, v) = spherical_uv(p);
+        mesh.push_vertex(&[p[0] * radius, p[1] * radius, p[2] * radius, p[0], p[1], p[2], u, v]);
+    }
+
+    // Triangles crossing the texture seam get their own copies of the vertices on the wrong side,
+    // so that the texture coordinates do not wrap around the whole sphere.
+    let mut seam_copies = HashMap::<u32, u32>::new();
+    for face in faces.iter() {
+        let us: Vec<f32> = face.iter().map(|i| mesh._vertices[*i as usize * 8 + 6]).collect();
+        let crosses_seam = us.iter().cloned().fold(0.0, f32::max)
+            - us.iter().cloned().fold(1.0, f32::min)
+            > 0.5;
+        let mut indices = *face;
+        if crosses_seam {
+            for k in 0..3 {
+                if us[k] < 0.5 {
+                    let original = indices[k];
+                    let copy = match seam_copies.get(&original) {
+                        Some(copy) => *copy,
+                        None => {
+                            let start = original as usize * 8;
+                            let mut vertex = mesh._vertices[start..start + 8].to_vec();
+                            vertex[6] += 1.0;
+                            let copy = mesh.push_vertex(&vertex);
+                            seam_copies.insert(original, copy);
+                            copy
+                        }
+                    };
+                    indices[k] = copy;
+                }
+            }
+        }
+        mesh.push_triangle(indices[0], indices[1], indices[2]);
+    }
+    return mesh;
+}
+
+/// An open or closed cylinder along the Y axis.
+pub fn cylinder(radius: f32, height: f32, segments: u32, height_segments: u32, caps: bool) -> Mesh {
+    let h = height / 2.0;
+    let stacks = height_segments.max(1);
+    let mut profile = vec![];
+    for j in 0..=stacks {
+        let v = j as f32 / stacks as f32;
+        profile.push(ProfilePoint::new(radius, -h + height * v, 1.0, 0.0, v));
+    }
+    let mut mesh = Mesh::new_pnt();
+    revolve(&mut mesh, &profile, segments.max(3));
+    if caps {
+        disc(&mut mesh, radius, -h, false, segments.max(3));
+        disc(&mut mesh, radius, h, true, segments.max(3));
+    }
+    return mesh;
+}
+
+/// A cone along the Y axis with its base at `-height / 2` and its apex at `height / 2`.
+pub fn cone(radius: f32, height: f32, segments: u32, height_segments: u32) -> Mesh {
+    let mut mesh = Mesh::new_pnt();
+    cone_side(&mut mesh, radius, -height / 2.0, height, segments.max(3), height_segments.max(1));
+    disc(&mut mesh, radius, -height / 2.0, false, segments.max(3));
+    return mesh;
+}
+
+/// A torus lying on the XZ plane. `major_radius` goes from the center to the middle of the
+/// tube, and `minor_radius` is the radius of the tube.
+pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> Mesh {
+    let minor_segments = minor_segments.max(3);
+    let mut profile = vec![];
+    for j in 0..=minor_segments {
+        let v = j as f32 / minor_segments as f32;
+        let (s, c) = (2.0 * PI * v).sin_cos();
+        profile.push(ProfilePoint::new(
+            major_radius + minor_radius * c,
+            minor_radius * s,
+            c,
+            s,
+            v,
+        ));
+    }
+    let mut mesh = Mesh::new_pnt();
+    revolve(&mut mesh, &profile, major_segments.max(3));
+    return mesh;
+}
+
+/// A cylinder of the given height capped with two hemispheres, so the total height is
+/// `height + 2 * radius`. Each hemisphere is made of `rings` parallels.
+pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Mesh {
+    let rings = rings.max(1);
+    let h = height / 2.0;
+    let total = PI * radius + height;
+    let mut profile = vec![];
+    for j in 0..=rings {
+        let theta = PI - (PI / 2.0) * j as f32 / rings as f32;
+        let (s, c) = theta.sin_cos();
+        let arc = radius * (PI - theta);
+        profile.push(ProfilePoint::new(radius * s, -h + radius * c, s, c, arc / total));
+    }
+    for j in 0..=rings {
+        let theta = (PI / 2.0) * (1.0 - j as f32 / rings as f32);
+        let (s, c) = theta.sin_cos();
+        let arc = radius * (PI - theta) + height;
+        profile.push(ProfilePoint::new(radius * s, h + radius * c, s, c, arc / total));
+    }
+    let mut mesh = Mesh::new_pnt();
+    revolve(&mut mesh, &profile, segments.max(3));
+    return mesh;
+}
+
+/// An arrow pointing along +Y with its tail at the origin, made of a cylindrical shaft and a
+/// conical head.
+pub fn arrow(
+    shaft_radius: f32,
+    shaft_length: f32,
+    head_radius: f32,
+    head_length: f32,
+    segments: u32,
+) -> Mesh {
+    let segments = segments.max(3);
+    let mut mesh = Mesh::new_pnt();
+    let shaft = [
+        ProfilePoint::new(shaft_radius, 0.0, 1.0, 0.0, 0.0),
+        ProfilePoint::new(shaft_radius, shaft_length, 1.0, 0.0, 1.0),
+    ];
+    disc(&mut mesh, shaft_radius, 0.0, false, segments);
+    revolve(&mut mesh, &shaft, segments);
+    disc(&mut mesh, head_radius, shaft_length, false, segments);
+    cone_side(&mut mesh, head_radius, shaft_length, head_length, segments, 1);
+    return mesh;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=====================================| Helpers |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A point of a profile that gets revolved around the Y axis, given by its radius and height,
+/// the radial and vertical components of its normal, and its V texture coordinate.
+struct ProfilePoint {
+    _r: f32,
+    _y: f32,
+    _nr: f32,
+    _ny: f32,
+    _v: f32,
+}
+
+impl ProfilePoint {
+    fn new(r: f32, y: f32, nr: f32, ny: f32, v: f32) -> ProfilePoint {
+        return ProfilePoint {
+            _r: r,
+            _y: y,
+            _nr: nr,
+            _ny: ny,
+            _v: v,
+        };
+    }
+}
+
+/// Adds a grid of quads spanning `origin + s * u + t * v`, for `s` and `t` in `[0, 1]`.
+fn grid(mesh: &mut Mesh, origin: &V3, u: &V3, v: &V3, normal: &V3, seg_u: u32, seg_v: u32) {
+    let base = mesh.vertex_count() as u32;
+    for j in 0..=seg_v {
+        let t = j as f32 / seg_v as f32;
+        for i in 0..=seg_u {
+            let s = i as f32 / seg_u as f32;
+            mesh.push_vertex(&[
+                origin[0] + s * u[0] + t * v[0],
+                origin[1] + s * u[1] + t * v[1],
+                origin[2] + s * u[2] + t * v[2],
+                normal[0],
+                normal[1],
+                normal[2],
+                s,
+                t,
+            ]);
+        }
+    }
+    quads(mesh, base, seg_u + 1, seg_u, seg_v);
+}
+
+/// Revolves the profile around the Y axis. The profile must go upwards on the side facing
+/// away from the axis for the triangles to face outwards.
+fn revolve(mesh: &mut Mesh, profile: &[ProfilePoint], segments: u32) {
+    let base = mesh.vertex_count() as u32;
+    let points = profile.len() as u32;
+    for i in 0..=segments {
+        let u = i as f32 / segments as f32;
+        let (s, c) = (2.0 * PI * u).sin_cos();
+        for p in profile.iter() {
+            mesh.push_vertex(&[
+                p._r * s,
+                p._y,
+                p._r * c,
+                p._nr * s,
+                p._ny,
+                p._nr * c,
+                u,
+                p._v,
+            ]);
+        }
+    }
+    // Here the profile runs along the rows, so the grid is transposed
+    for i in 0..segments {
+        for j in 0..points - 1 {
+            let a = base + i * points + j;
+            let b = base + (i + 1) * points + j;
+            mesh.push_triangle(a, b, b + 1);
+            mesh.push_triangle(a, b + 1, a + 1);
+        }
+    }
+}
+
+/// Adds the triangles of a grid of vertices stored row by row.
+fn quads(mesh: &mut Mesh, base: u32, row_length: u32, columns: u32, rows: u32) {
+    for j in 0..rows {
+        for i in 0..columns {
+            let a = base + j * row_length + i;
+            let d = a + row_length;
+            mesh.push_triangle(a, a + 1, d + 1);
+            mesh.push_triangle(a, d + 1, d);
+        }
+    }
+}
+
+/// Adds a disc on the XZ plane at the given height, facing either up or down.
+fn disc(mesh: &mut Mesh, radius: f32, y: f32, up: bool, segments: u32) {
+    let ny = if up { 1.0 } else { -1.0 };
+    let center = mesh.push_vertex(&[0.0, y, 0.0, 0.0, ny, 0.0, 0.5, 0.5]);
+    for i in 0..=segments {
+        let (s, c) = (2.0 * PI * i as f32 / segments as f32).sin_cos();
+        mesh.push_vertex(&[
+            radius * s,
+            y,
+            radius * c,
+            0.0,
+            ny,
+            0.0,
+            0.5 + 0.5 * s,
+            0.5 + 0.5 * c,
+        ]);
+    }
+    for i in 0..segments {
+        let a = center + 1 + i;
+        if up {
+            mesh.push_triangle(center, a, a + 1);
+        } else {
+            mesh.push_triangle(center, a + 1, a);
+        }
+    }
+}
+
+/// Adds the slanted side of a cone with its base at height `y`.
+fn cone_side(mesh: &mut Mesh, radius: f32, y: f32, height: f32, segments: u32, stacks: u32) {
+    let slant = (radius * radius + height * height).sqrt();
+    let (nr, ny) = (height / slant, radius / slant);
+    let mut profile = vec![];
+    for j in 0..=stacks {
+        let v = j as f32 / stacks as f32;
+        profile.push(ProfilePoint::new(radius * (1.0 - v), y + height * v, nr, ny, v));
+    }
+    revolve(mesh, &profile, segments);
+}
+
+/// Texture coordinates of a point on the unit sphere, consistent with `uv_sphere`.
+fn spherical_uv(p: &V3) -> (f32, f32) {
+    let mut u = p[0].atan2(p[2]) / (2.0 * PI);
+    if u < 0.0 {
+        u += 1.0;
+    }
+    let v = 1.0 - p[1].clamp(-1.0, 1.0).acos() / PI;
+    return (u, v);
+}
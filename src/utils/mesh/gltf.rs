@@ -0,0 +1,752 @@
+use crate::utils::json::{self, JsonValue};
+use crate::utils::math::linalg;
+use crate::utils::mesh::{Mesh, VertexAttribute};
+use crate::utils::str::decode_base64;
+use crate::utils::types::{V3, V4};
+use std::fs;
+use std::path::Path;
+
+const GLB_MAGIC: u32 = 0x46546C67;
+const GLB_CHUNK_JSON: u32 = 0x4E4F534A;
+const GLB_CHUNK_BIN: u32 = 0x004E4942;
+const MODE_TRIANGLES: u32 = 4;
+
+/// An image used by a texture, either stored in a file or embedded in the document.
+#[derive(Clone)]
+pub enum GltfImage {
+    Path(String),
+    Bytes(Vec<u8>),
+}
+
+/// A metallic-roughness material. The texture is the index of the image it uses.
+#[derive(Clone)]
+pub struct GltfMaterial {
+    pub _name: String,
+    pub _base_color: V4,
+    pub _base_color_texture: Option<usize>,
+    pub _metallic: f32,
+    pub _roughness: f32,
+    pub _emissive: V3,
+    pub _double_sided: bool,
+}
+
+/// A part of a mesh drawn with a single material. The mode is an OpenGL primitive type.
+pub struct GltfPrimitive {
+    pub _mesh: Mesh,
+    pub _material: Option<usize>,
+    pub _mode: u32,
+}
+
+pub struct GltfMesh {
+    pub _name: String,
+    pub _primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Copy, Clone)]
+pub enum GltfProjection {
+    /// Vertical field of view in radians. Without a far plane the projection is infinite.
+    Perspective {
+        _yfov: f32,
+        _aspect_ratio: Option<f32>,
+        _znear: f32,
+        _zfar: Option<f32>,
+    },
+    /// Half of the width and height of the view volume.
+    Orthographic {
+        _xmag: f32,
+        _ymag: f32,
+        _znear: f32,
+        _zfar: f32,
+    },
+}
+
+pub struct GltfCamera {
+    pub _name: String,
+    pub _projection: GltfProjection,
+}
+
+/// A node of the scene graph. The local transform is given both as translation, rotation
+/// (a quaternion `[x, y, z, w]`) and scale, and as the matrix they make.
+pub struct GltfNode {
+    pub _name: String,
+    pub _translation: V3,
+    pub _rotation: V4,
+    pub _scale: V3,
+    pub _matrix: [V4; 4],
+    pub _children: Vec<usize>,
+    pub _mesh: Option<usize>,
+    pub _camera: Option<usize>,
+}
+
+/// The contents of a glTF 2.0 file (`.gltf` or `.glb`).
+pub struct GltfDocument {
+    pub _meshes: Vec<GltfMesh>,
+    pub _materials: Vec<GltfMaterial>,
+    pub _images: Vec<GltfImage>,
+    pub _cameras: Vec<GltfCamera>,
+    pub _nodes: Vec<GltfNode>,
+    pub _scenes: Vec<Vec<usize>>,
+    pub _scene: usize,
+}
+
+impl GltfDocument {
+    /// Root nodes of the default scene. Without scenes, every node without a parent is a root.
+    pub fn roots(&self) -> Vec<usize> {
+        if let Some(roots) = self._scenes.get(self._scene) {
+            return roots.clone();
+        }
+        return (0..self._nodes.len())
+            .filter(|i| !self._nodes.iter().any(|n| n._children.contains(i)))
+            .collect();
+    }
+
+    /// Computes the world matrix of every node reachable from the default scene. Nodes outside
+    /// of it keep their local matrix.
+    pub fn world_matrices(&self) -> Vec<[V4; 4]> {
+        let mut result: Vec<[V4; 4]> = self._nodes.iter().map(|n| n._matrix).collect();
+        let mut stack: Vec<(usize, [V4; 4])> =
+            self.roots().iter().map(|r| (*r, linalg::eye4())).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let world = linalg::mat4_mul4(&parent, &self._nodes[node]._matrix);
+            result[node] = world;
+            for child in self._nodes[node]._children.iter() {
+                stack.push((*child, world));
+            }
+        }
+        return result;
+    }
+}
+
+/// Loads a glTF 2.0 file, either in its JSON form or as a binary GLB. External buffers and
+/// images are looked up relative to the file.
+pub fn load(path: &str) -> GltfDocument {
+    let bytes = fs::read(path).unwrap_or_else(|_| panic!("Error reading glTF file {path}."));
+    let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+    if bytes.len() >= 4 && read_u32(&bytes, 0) == GLB_MAGIC {
+        return parse_glb(&bytes, directory);
+    }
+    let content = String::from_utf8(bytes).expect("glTF file is not valid UTF-8.");
+    return parse(&content, None, directory);
+}
+
+/// Parses a binary GLB container.
+pub fn parse_glb(bytes: &[u8], directory: &Path) -> GltfDocument {
+    if bytes.len() < 12 || read_u32(bytes, 0) != GLB_MAGIC {
+        panic!("Invalid GLB header.");
+    }
+    let version = read_u32(bytes, 4);
+    if version != 2 {
+        panic!("Unsupported GLB version {version}.");
+    }
+    let mut json_chunk: Option<&[u8]> = None;
+    let mut bin_chunk: Option<&[u8]> = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let length = read_u32(bytes, offset) as usize;
+        let chunk_type = read_u32(bytes, offset + 4);
+        let data = &bytes[offset + 8..offset + 8 + length];
+        match chunk_type {
+            GLB_CHUNK_JSON => json_chunk = Some(data),
+            GLB_CHUNK_BIN => bin_chunk = Some(data),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    let content = std::str::from_utf8(json_chunk.expect("GLB file has no JSON chunk."))
+        .expect("GLB JSON chunk is not valid UTF-8.");
+    return parse(content, bin_chunk, directory);
+}
+
+/// Parses the JSON part of a glTF document. `bin` is the binary chunk of a GLB container.
+pub fn parse(content: &str, bin: Option<&[u8]>, directory: &Path) -> GltfDocument {
+    let root = json::parse(content);
+    let version = root
+        .get("asset")
+        .and_then(|a| a.get("version"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("2.0");
+    if !version.starts_with('2') {
+        panic!("Unsupported glTF version {version}.");
+    }
+
+    let buffers: Vec<Vec<u8>> = array(&root, "buffers")
+        .iter()
+        .map(|b| match b.get("uri").and_then(|u| u.as_str()) {
+            Some(uri) => read_uri(uri, directory),
+            None => bin
+                .expect("glTF buffer without uri outside of a GLB file.")
+                .to_vec(),
+        })
+        .collect();
+
+    let images = array(&root, "images")
+        .iter()
+        .map(|image| {
+            if let Some(uri) = image.get("uri").and_then(|u| u.as_str()) {
+                if uri.starts_with("data:") {
+                    return GltfImage::Bytes(read_uri(uri, directory));
+                }
+                let path = directory.join(percent_decode(uri));
+                return GltfImage::Path(path.to_string_lossy().into_owned());
+            }
+            let view =
+                usize_of(image, "bufferView").expect("glTF image without uri or bufferView.");
+            let (data, _) = buffer_view(&root, &buffers, view);
+            return GltfImage::Bytes(data.to_vec());
+        })
+        .collect();
+
+    let texture_images: Vec<Option<usize>> = array(&root, "textures")
+        .iter()
+        .map(|t| usize_of(t, "source"))
+        .collect();
+
+    let materials = array(&root, "materials")
+        .iter()
+        .map(|m| {
+            let pbr = m.get("pbrMetallicRoughness");
+            let base_color = pbr
+                .and_then(|p| p.get("baseColorFactor"))
+                .map(|c| c.as_f32_vec())
+                .filter(|c| c.len() == 4)
+                .map_or([1.0, 1.0, 1.0, 1.0], |c| [c[0], c[1], c[2], c[3]]);
+            let emissive = m
+                .get("emissiveFactor")
+                .map(|c| c.as_f32_vec())
+                .filter(|c| c.len() == 3)
+                .map_or([0.0, 0.0, 0.0], |c| [c[0], c[1], c[2]]);
+            return GltfMaterial {
+                _name: name_of(m),
+                _base_color: base_color,
+                _base_color_texture: pbr
+                    .and_then(|p| p.get("baseColorTexture"))
+                    .and_then(|t| usize_of(t, "index"))
+                    .and_then(|t| *texture_images.get(t).unwrap_or(&None)),
+                _metallic: pbr.and_then(|p| f32_of(p, "metallicFactor")).unwrap_or(1.0),
+                _roughness: pbr
+                    .and_then(|p| f32_of(p, "roughnessFactor"))
+                    .unwrap_or(1.0),
+                _emissive: emissive,
+                _double_sided: m
+                    .get("doubleSided")
+                    .and_then(|d| d.as_bool())
+                    .unwrap_or(false),
+            };
+        })
+        .collect();
+
+    let meshes = array(&root, "meshes")
+        .iter()
+        .map(|m| GltfMesh {
+            _name: name_of(m),
+            _primitives: array(m, "primitives")
+                .iter()
+                .map(|p| read_primitive(&root, &buffers, p))
+                .collect(),
+        })
+        .collect();
+
+    let cameras = array(&root, "cameras")
+        .iter()
+        .map(|c| {
+            let projection = match c.get("type").and_then(|t| t.as_str()) {
+                Some("orthographic") => {
+                    let o = c
+                        .get("orthographic")
+                        .expect("glTF camera without parameters.");
+                    GltfProjection::Orthographic {
+                        _xmag: f32_of(o, "xmag").unwrap_or(1.0),
+                        _ymag: f32_of(o, "ymag").unwrap_or(1.0),
+                        _znear: f32_of(o, "znear").unwrap_or(0.0),
+                        _zfar: f32_of(o, "zfar").unwrap_or(1.0),
+                    }
+                }
+                _ => {
+                    let p = c
+                        .get("perspective")
+                        .expect("glTF camera without parameters.");
+                    GltfProjection::Perspective {
+                        _yfov: f32_of(p, "yfov").unwrap_or(std::f32::consts::FRAC_PI_4),
+                        _aspect_ratio: f32_of(p, "aspectRatio"),
+                        _znear: f32_of(p, "znear").unwrap_or(0.01),
+                        _zfar: f32_of(p, "zfar"),
+                    }
+                }
+            };
+            return GltfCamera {
+                _name: name_of(c),
+                _projection: projection,
+            };
+        })
+        .collect();
+
+    let nodes = array(&root, "nodes").iter().map(read_node).collect();
+
+    let scenes = array(&root, "scenes")
+        .iter()
+        .map(|s| {
+            array(s, "nodes")
+                .iter()
+                .filter_map(|n| n.as_usize())
+                .collect()
+        })
+        .collect();
+
+    return GltfDocument {
+        _meshes: meshes,
+        _materials: materials,
+        _images: images,
+        _cameras: cameras,
+        _nodes: nodes,
+        _scenes: scenes,
+        _scene: usize_of(&root, "scene").unwrap_or(0),
+    };
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=====================================| Geometry |============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads a primitive into a mesh laid out as position, normal and, when present, texture
+/// coordinates and color. Missing normals are computed for triangle lists.
+fn read_primitive(root: &JsonValue, buffers: &[Vec<u8>], primitive: &JsonValue) -> GltfPrimitive {
+    let attributes = primitive
+        .get("attributes")
+        .expect("glTF primitive without attributes.");
+    let positions = usize_of(attributes, "POSITION")
+        .map(|a| read_accessor(root, buffers, a))
+        .expect("glTF primitive without positions.");
+    let count = positions.len() / 3;
+    let normals = usize_of(attributes, "NORMAL").map(|a| read_accessor(root, buffers, a));
+    let tex_coords = usize_of(attributes, "TEXCOORD_0").map(|a| read_accessor(root, buffers, a));
+    let colors = usize_of(attributes, "COLOR_0").map(|a| {
+        let values = read_accessor(root, buffers, a);
+        // Colors may be RGB or RGBA, and are always stored here as RGBA
+        if values.len() == count * 3 {
+            return values
+                .chunks_exact(3)
+                .flat_map(|c| [c[0], c[1], c[2], 1.0])
+                .collect();
+        }
+        return values;
+    });
+
+    let mut mesh = Mesh::new()
+        .attribute(VertexAttribute::Position, 3)
+        .attribute(VertexAttribute::Normal, 3);
+    if tex_coords.is_some() {
+        mesh = mesh.attribute(VertexAttribute::TexCoord, 2);
+    }
+    if colors.is_some() {
+        mesh = mesh.attribute(VertexAttribute::Color, 4);
+    }
+    let mut vertex = Vec::with_capacity(mesh.vertex_size());
+    for i in 0..count {
+        vertex.clear();
+        vertex.extend_from_slice(&positions[i * 3..i * 3 + 3]);
+        match &normals {
+            Some(n) => vertex.extend_from_slice(&n[i * 3..i * 3 + 3]),
+            None => vertex.extend_from_slice(&[0.0, 0.0, 0.0]),
+        }
+        if let Some(t) = &tex_coords {
+            vertex.extend_from_slice(&t[i * 2..i * 2 + 2]);
+        }
+        if let Some(c) = &colors {
+            vertex.extend_from_slice(&c[i * 4..i * 4 + 4]);
+        }
+        mesh.push_vertex(&vertex);
+    }
+    mesh._indices = match usize_of(primitive, "indices") {
+        Some(a) => read_indices(root, buffers, a),
+        None => (0..count as u32).collect(),
+    };
+
+    let mode = usize_of(primitive, "mode").map_or(MODE_TRIANGLES, |m| m as u32);
+    if normals.is_none() && mode == MODE_TRIANGLES {
+        mesh.compute_normals();
+    }
+    return GltfPrimitive {
+        _mesh: mesh,
+        _material: usize_of(primitive, "material"),
+        _mode: mode,
+    };
+}
+
+/// Returns the bytes of a buffer view along with its stride, if it has one.
+fn buffer_view<'a>(
+    root: &JsonValue,
+    buffers: &'a [Vec<u8>],
+    index: usize,
+) -> (&'a [u8], Option<usize>) {
+    let view = root
+        .get("bufferViews")
+        .and_then(|v| v.at(index))
+        .unwrap_or_else(|| panic!("glTF buffer view {index} does not exist."));
+    let buffer = &buffers[usize_of(view, "buffer").expect("glTF buffer view without buffer.")];
+    let offset = usize_of(view, "byteOffset").unwrap_or(0);
+    let length = usize_of(view, "byteLength").expect("glTF buffer view without length.");
+    return (
+        &buffer[offset..offset + length],
+        usize_of(view, "byteStride"),
+    );
+}
+
+fn accessor(root: &JsonValue, index: usize) -> &JsonValue {
+    let accessor = root
+        .get("accessors")
+        .and_then(|a| a.at(index))
+        .unwrap_or_else(|| panic!("glTF accessor {index} does not exist."));
+    if accessor.get("sparse").is_some() {
+        panic!("glTF sparse accessors are not supported.");
+    }
+    return accessor;
+}
+
+/// Reads an accessor of indices as integers, since the large ones don't fit in an `f32`.
+fn read_indices(root: &JsonValue, buffers: &[Vec<u8>], index: usize) -> Vec<u32> {
+    let accessor = accessor(root, index);
+    let count = usize_of(accessor, "count").expect("glTF accessor without count.");
+    let component_type = usize_of(accessor, "componentType").unwrap_or(5125);
+    let component_size = match component_type {
+        5121 => 1,
+        5123 => 2,
+        5125 => 4,
+        _ => panic!("glTF index accessor {index} must hold unsigned integers."),
+    };
+
+    let view = match usize_of(accessor, "bufferView") {
+        Some(v) => v,
+        None => return vec![0; count],
+    };
+    let (data, stride) = buffer_view(root, buffers, view);
+    let offset = usize_of(accessor, "byteOffset").unwrap_or(0);
+    let stride = stride.unwrap_or(component_size);
+    return (0..count)
+        .map(|i| {
+            let at = offset + i * stride;
+            match component_type {
+                5121 => data[at] as u32,
+                5123 => u16::from_le_bytes([data[at], data[at + 1]]) as u32,
+                _ => read_u32(data, at),
+            }
+        })
+        .collect();
+}
+
+/// Reads every component of an accessor as `f32`, applying the normalization of integer
+/// components when the accessor asks for it.
+fn read_accessor(root: &JsonValue, buffers: &[Vec<u8>], index: usize) -> Vec<f32> {
+    let accessor = accessor(root, index);
+    let count = usize_of(accessor, "count").expect("glTF accessor without count.");
+    let components = match accessor.get("type").and_then(|t| t.as_str()) {
+        Some("SCALAR") => 1,
+        Some("VEC2") => 2,
+        Some("VEC3") => 3,
+        Some("VEC4") | Some("MAT2") => 4,
+        Some("MAT3") => 9,
+        Some("MAT4") => 16,
+        _ => panic!("glTF accessor {index} has an invalid type."),
+    };
+    let component_type = usize_of(accessor, "componentType").unwrap_or(5126);
+    let component_size = match component_type {
+        5120 | 5121 => 1,
+        5122 | 5123 => 2,
+        5125 | 5126 => 4,
+        _ => panic!("glTF accessor {index} has an invalid component type."),
+    };
+    let normalized = accessor
+        .get("normalized")
+        .and_then(|n| n.as_bool())
+        .unwrap_or(false);
+
+    // Accessors without a buffer view are filled with zeros
+    let view = match usize_of(accessor, "bufferView") {
+        Some(v) => v,
+        None => return vec![0.0; count * components],
+    };
+    let (data, stride) = buffer_view(root, buffers, view);
+    let offset = usize_of(accessor, "byteOffset").unwrap_or(0);
+    let stride = stride.unwrap_or(components * component_size);
+
+    let mut result = Vec::with_capacity(count * components);
+    for i in 0..count {
+        for c in 0..components {
+            let at = offset + i * stride + c * component_size;
+            let value = match component_type {
+                5120 => {
+                    let v = data[at] as i8 as f32;
+                    if normalized {
+                        (v / 127.0).max(-1.0)
+                    } else {
+                        v
+                    }
+                }
+                5121 => {
+                    let v = data[at] as f32;
+                    if normalized {
+                        v / 255.0
+                    } else {
+                        v
+                    }
+                }
+                5122 => {
+                    let v = i16::from_le_bytes([data[at], data[at + 1]]) as f32;
+                    if normalized {
+                        (v / 32767.0).max(-1.0)
+                    } else {
+                        v
+                    }
+                }
+                5123 => {
+                    let v = u16::from_le_bytes([data[at], data[at + 1]]) as f32;
+                    if normalized {
+                        v / 65535.0
+                    } else {
+                        v
+                    }
+                }
+                5125 => read_u32(data, at) as f32,
+                _ => f32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]),
+            };
+            result.push(value);
+        }
+    }
+    return result;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|====================================| Scene graph |==========================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn read_node(node: &JsonValue) -> GltfNode {
+    let (translation, rotation, scale, matrix);
+    match node
+        .get("matrix")
+        .map(|m| m.as_f32_vec())
+        .filter(|m| m.len() == 16)
+    {
+        Some(m) => {
+            // glTF matrices are stored in column-major order
+            let mut rows = [[0.0; 4]; 4];
+            for row in 0..4 {
+                for col in 0..4 {
+                    rows[row][col] = m[col * 4 + row];
+                }
+            }
+            matrix = rows;
+            (translation, rotation, scale) = decompose(&rows);
+        }
+        None => {
+            let t = node.get("translation").map(|t| t.as_f32_vec());
+            let r = node.get("rotation").map(|r| r.as_f32_vec());
+            let s = node.get("scale").map(|s| s.as_f32_vec());
+            translation = t
+                .filter(|t| t.len() == 3)
+                .map_or([0.0; 3], |t| [t[0], t[1], t[2]]);
+            rotation = r
+                .filter(|r| r.len() == 4)
+                .map_or([0.0, 0.0, 0.0, 1.0], |r| [r[0], r[1], r[2], r[3]]);
+            scale = s
+                .filter(|s| s.len() == 3)
+                .map_or([1.0; 3], |s| [s[0], s[1], s[2]]);
+            matrix = compose(&translation, &rotation, &scale);
+        }
+    }
+    return GltfNode {
+        _name: name_of(node),
+        _translation: translation,
+        _rotation: rotation,
+        _scale: scale,
+        _matrix: matrix,
+        _children: array(node, "children")
+            .iter()
+            .filter_map(|c| c.as_usize())
+            .collect(),
+        _mesh: usize_of(node, "mesh"),
+        _camera: usize_of(node, "camera"),
+    };
+}
+
+/// Rotation matrix of a unit quaternion `[x, y, z, w]`.
+pub fn quat_to_mat3(q: &V4) -> [V3; 3] {
+    let (x, y, z, w) = (q[0], q[1], q[2], q[3]);
+    return [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - z * w),
+            2.0 * (x * z + y * w),
+        ],
+        [
+            2.0 * (x * y + z * w),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - x * w),
+        ],
+        [
+            2.0 * (x * z - y * w),
+            2.0 * (y * z + x * w),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ];
+}
+
+/// Builds the matrix `T * R * S`.
+fn compose(translation: &V3, rotation: &V4, scale: &V3) -> [V4; 4] {
+    let r = quat_to_mat3(rotation);
+    let mut result = linalg::eye4();
+    for row in 0..3 {
+        for col in 0..3 {
+            result[row][col] = r[row][col] * scale[col];
+        }
+        result[row][3] = translation[row];
+    }
+    return result;
+}
+
+/// Splits an affine matrix into translation, rotation and scale.
+fn decompose(m: &[V4; 4]) -> (V3, V4, V3) {
+    let translation = [m[0][3], m[1][3], m[2][3]];
+    let column = |c: usize| [m[0][c], m[1][c], m[2][c]];
+    let mut scale = [
+        linalg::norm_v3(&column(0)),
+        linalg::norm_v3(&column(1)),
+        linalg::norm_v3(&column(2)),
+    ];
+    // A negative determinant means the matrix mirrors, which is folded into the scale
+    let determinant = linalg::cross_v3(&column(0), &column(1))
+        .iter()
+        .zip(column(2).iter())
+        .map(|(a, b)| a * b)
+        .sum::<f32>();
+    if determinant < 0.0 {
+        scale[0] = -scale[0];
+    }
+    let mut r = [[0.0; 3]; 3];
+    for row in 0..3 {
+        for col in 0..3 {
+            r[row][col] = if scale[col] != 0.0 {
+                m[row][col] / scale[col]
+            } else {
+                0.0
+            };
+        }
+    }
+
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    let rotation = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+            0.25 * s,
+        ]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[2][1] - r[1][2]) / s,
+        ]
+    } else if r[1][1] > r[2][2] {
+        let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[0][1] + r[1][0]) / s,
+            0.25 * s,
+            (r[1][2] + r[2][1]) / s,
+            (r[0][2] - r[2][0]) / s,
+        ]
+    } else {
+        let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+        [
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            0.25 * s,
+            (r[1][0] - r[0][1]) / s,
+        ]
+    };
+    return (translation, rotation, scale);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=====================================| Helpers |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn array<'a>(value: &'a JsonValue, key: &str) -> &'a [JsonValue] {
+    return value.get(key).map_or(&[], |v| v.as_array());
+}
+
+fn usize_of(value: &JsonValue, key: &str) -> Option<usize> {
+    return value.get(key).and_then(|v| v.as_usize());
+}
+
+fn f32_of(value: &JsonValue, key: &str) -> Option<f32> {
+    return value.get(key).and_then(|v| v.as_f32());
+}
+
+fn name_of(value: &JsonValue) -> String {
+    return value
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or("")
+        .to_string();
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    return u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ]);
+}
+
+/// Reads the contents of a `data:` URI or of a file relative to the given directory.
+fn read_uri(uri: &str, directory: &Path) -> Vec<u8> {
+    if uri.starts_with("data:") {
+        let (header, data) = uri
+            .split_once(',')
+            .unwrap_or_else(|| panic!("Invalid data URI in glTF file."));
+        if !header.ends_with(";base64") {
+            panic!("Only base64 data URIs are supported in glTF files.");
+        }
+        return decode_base64(data);
+    }
+    let path = directory.join(percent_decode(uri));
+    return fs::read(&path)
+        .unwrap_or_else(|_| panic!("Error reading glTF resource {}.", path.display()));
+}
+
+/// Decodes the `%XX` escapes of a relative URI.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 3 <= bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                result.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    return String::from_utf8_lossy(&result).into_owned();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_percent_escapes() {
+        assert_eq!(percent_decode("a%20b.bin"), "a b.bin");
+        assert_eq!(percent_decode("a%20"), "a ");
+        assert_eq!(percent_decode("%C3%A9.png"), "\u{e9}.png");
+        assert_eq!(percent_decode("a%2"), "a%2");
+        assert_eq!(percent_decode("a%zz"), "a%zz");
+    }
+}
@@ -0,0 +1,205 @@
+pub mod generators;
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+use crate::utils::math::linalg;
+use crate::utils::types::V3;
+
+/// The meaning of each attribute of a vertex, in the order they are laid out.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VertexAttribute {
+    Position,
+    Normal,
+    TexCoord,
+    Color,
+    Custom(String),
+}
+
+/// CPU-side geometry, stored as interleaved `f32` vertices along with the indices and the
+/// layout of each vertex. It can be handed to a `Shape` through `Shape::from_mesh`.
+#[derive(Clone)]
+pub struct Mesh {
+    pub _vertices: Vec<f32>,
+    pub _indices: Vec<u32>,
+    pub _attributes: Vec<VertexAttribute>,
+    pub _vert_sizes: Vec<u32>,
+    pub _layouts: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        return Mesh {
+            _vertices: vec![],
+            _indices: vec![],
+            _attributes: vec![],
+            _vert_sizes: vec![],
+            _layouts: vec![],
+        };
+    }
+
+    /// Makes an empty mesh whose vertices contain a position, a normal and texture coordinates.
+    pub fn new_pnt() -> Mesh {
+        return Mesh::new()
+            .attribute(VertexAttribute::Position, 3)
+            .attribute(VertexAttribute::Normal, 3)
+            .attribute(VertexAttribute::TexCoord, 2);
+    }
+
+    /// Appends an attribute to the vertex layout, bound to the next layout index.
+    pub fn attribute(mut self, attribute: VertexAttribute, size: u32) -> Mesh {
+        self._layouts.push(self._attributes.len() as u32);
+        self._attributes.push(attribute);
+        self._vert_sizes.push(size);
+        return self;
+    }
+
+    /// Number of floats that make up a single vertex.
+    pub fn vertex_size(&self) -> usize {
+        return self._vert_sizes.iter().sum::<u32>() as usize;
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        let size = self.vertex_size();
+        if size == 0 {
+            return 0;
+        }
+        return self._vertices.len() / size;
+    }
+
+    /// Size in bytes of a single vertex.
+    pub fn stride(&self) -> u32 {
+        return (self.vertex_size() * std::mem::size_of::<f32>()) as u32;
+    }
+
+    /// Offset (in floats) of the given attribute inside a vertex, if it is present.
+    pub fn attribute_offset(&self, attribute: &VertexAttribute) -> Option<usize> {
+        let mut offset = 0;
+        for i in 0..self._attributes.len() {
+            if self._attributes[i] == *attribute {
+                return Some(offset);
+            }
+            offset += self._vert_sizes[i] as usize;
+        }
+        return None;
+    }
+
+    /// Layout index the given attribute is bound to, if it is present.
+    pub fn layout_of(&self, attribute: &VertexAttribute) -> Option<u32> {
+        let position = self._attributes.iter().position(|a| a == attribute)?;
+        return Some(self._layouts[position]);
+    }
+
+    /// Returns the values of the given attribute for the vertex at `index`.
+    pub fn get_attribute(&self, index: usize, attribute: &VertexAttribute) -> Option<&[f32]> {
+        let position = self._attributes.iter().position(|a| a == attribute)?;
+        let offset = self.attribute_offset(attribute)?;
+        let start = index * self.vertex_size() + offset;
+        return Some(&self._vertices[start..start + self._vert_sizes[position] as usize]);
+    }
+
+    /// Positions of all the vertices, or none if the mesh has no position attribute.
+    pub fn positions(&self) -> Vec<V3> {
+        let offset = match self.attribute_offset(&VertexAttribute::Position) {
+            Some(offset) => offset,
+            None => return vec![],
+        };
+        let size = self.vertex_size();
+        return (0..self.vertex_count())
+            .map(|v| {
+                let start = v * size + offset;
+                [
+                    self._vertices[start],
+                    self._vertices[start + 1],
+                    self._vertices[start + 2],
+                ]
+            })
+            .collect();
+    }
+
+    /// Pushes a vertex, returning its index. The vertex must follow the mesh layout.
+    pub fn push_vertex(&mut self, vertex: &[f32]) -> u32 {
+        assert_eq!(vertex.len(), self.vertex_size());
+        let index = self.vertex_count() as u32;
+        self._vertices.extend_from_slice(vertex);
+        return index;
+    }
+
+    pub fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self._indices.extend_from_slice(&[a, b, c]);
+    }
+
+    /// Appends the geometry of another mesh with the same layout.
+    pub fn append(&mut self, other: &Mesh) {
+        assert_eq!(self._attributes, other._attributes);
+        assert_eq!(self._vert_sizes, other._vert_sizes);
+        let base = self.vertex_count() as u32;
+        self._vertices.extend_from_slice(&other._vertices);
+        self._indices.extend(other._indices.iter().map(|i| i + base));
+    }
+
+    /// Moves every vertex position by the given offset.
+    pub fn translate(&mut self, offset: &V3) {
+        let position = match self.attribute_offset(&VertexAttribute::Position) {
+            Some(p) => p,
+            None => return,
+        };
+        let size = self.vertex_size();
+        for v in 0..self.vertex_count() {
+            for k in 0..3 {
+                self._vertices[v * size + position + k] += offset[k];
+            }
+        }
+    }
+
+    /// Recomputes the normals as the area-weighted average of the faces around each vertex.
+    pub fn compute_normals(&mut self) {
+        let vertices: Vec<u32> = (0..self.vertex_count() as u32).collect();
+        self.compute_normals_of(&vertices);
+    }
+
+    /// Like `compute_normals`, but only for the given vertices, keeping the normals of the
+    /// others.
+    pub fn compute_normals_of(&mut self, vertices: &[u32]) {
+        let (position, normal) = match (
+            self.attribute_offset(&VertexAttribute::Position),
+            self.attribute_offset(&VertexAttribute::Normal),
+        ) {
+            (Some(p), Some(n)) => (p, n),
+            _ => return,
+        };
+        let size = self.vertex_size();
+        let mut normals = vec![[0.0_f32; 3]; self.vertex_count()];
+        for triangle in self._indices.chunks_exact(3) {
+            let p: Vec<V3> = triangle
+                .iter()
+                .map(|i| {
+                    let start = *i as usize * size + position;
+                    [
+                        self._vertices[start],
+                        self._vertices[start + 1],
+                        self._vertices[start + 2],
+                    ]
+                })
+                .collect();
+            let face =
+                linalg::cross_v3(&linalg::sub_v3(&p[1], &p[0]), &linalg::sub_v3(&p[2], &p[0]));
+            for i in triangle {
+                for k in 0..3 {
+                    normals[*i as usize][k] += face[k];
+                }
+            }
+        }
+        for v in vertices.iter().map(|v| *v as usize) {
+            let n = if linalg::norm_v3(&normals[v]) > 0.0 {
+                linalg::normalize_v3(&normals[v])
+            } else {
+                [0.0, 0.0, 0.0]
+            };
+            for k in 0..3 {
+                self._vertices[v * size + normal + k] = n[k];
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::utils::mesh::{Mesh, VertexAttribute};
+use crate::utils::types::V3;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// A material read from an MTL file. Texture paths are resolved relative to the MTL file.
+#[derive(Clone)]
+pub struct ObjMaterial {
+    pub _name: String,
+    pub _ambient: V3,
+    pub _diffuse: V3,
+    pub _specular: V3,
+    pub _shininess: f32,
+    pub _opacity: f32,
+    pub _ambient_map: Option<String>,
+    pub _diffuse_map: Option<String>,
+    pub _specular_map: Option<String>,
+    pub _normal_map: Option<String>,
+}
+
+/// A group of faces sharing the same object or group name and the same material.
+pub struct ObjObject {
+    pub _name: String,
+    pub _material: Option<usize>,
+    pub _mesh: Mesh,
+}
+
+/// The contents of an OBJ file, along with the materials of its MTL libraries.
+pub struct ObjModel {
+    pub _objects: Vec<ObjObject>,
+    pub _materials: Vec<ObjMaterial>,
+}
+
+impl ObjMaterial {
+    pub fn new(name: &str) -> ObjMaterial {
+        return ObjMaterial {
+            _name: name.to_string(),
+            _ambient: [0.0, 0.0, 0.0],
+            _diffuse: [1.0, 1.0, 1.0],
+            _specular: [0.0, 0.0, 0.0],
+            _shininess: 0.0,
+            _opacity: 1.0,
+            _ambient_map: None,
+            _diffuse_map: None,
+            _specular_map: None,
+            _normal_map: None,
+        };
+    }
+}
+
+/// Loads an OBJ file along with the MTL libraries it references. Polygons are triangulated as
+/// fans, and every object gets indexed vertices laid out as position, normal and texture
+/// coordinates. Normals are computed for the vertices the file gives none. Missing MTL
+/// libraries are skipped with a warning, leaving their faces with the default material.
+pub fn load(path: &str) -> ObjModel {
+    let content =
+        fs::read_to_string(path).unwrap_or_else(|_| panic!("Error reading OBJ file {path}."));
+    return parse(&content, Path::new(path).parent().unwrap_or(Path::new("")));
+}
+
+/// Parses the contents of an OBJ file. MTL libraries are looked up in the given directory.
+pub fn parse(content: &str, directory: &Path) -> ObjModel {
+    let mut positions: Vec<V3> = vec![];
+    let mut normals: Vec<V3> = vec![];
+    let mut tex_coords: Vec<[f32; 2]> = vec![];
+    let mut materials: Vec<ObjMaterial> = vec![];
+    let mut builders: Vec<ObjectBuilder> = vec![];
+
+    let mut name = String::from("default");
+    let mut material: Option<usize> = None;
+
+    for (number, raw_line) in content.lines().enumerate() {
+        let line = raw_line.split('#').next().unwrap().trim();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(k) => k,
+            None => continue,
+        };
+        let rest: Vec<&str> = tokens.collect();
+        match keyword {
+            "v" => positions.push(parse_v3(&rest, number)),
+            "vn" => normals.push(parse_v3(&rest, number)),
+            "vt" => {
+                let uv = parse_floats(&rest, number);
+                tex_coords.push([*uv.get(0).unwrap_or(&0.0), *uv.get(1).unwrap_or(&0.0)]);
+            }
+            "o" | "g" => name = rest.join(" "),
+            "mtllib" => {
+                for library in rest.iter() {
+                    let path = directory.join(library);
+                    match fs::read_to_string(&path) {
+                        Ok(content) => {
+                            let parent = path.parent().unwrap_or(Path::new(""));
+                            materials.extend(parse_mtl(&content, parent));
+                        }
+                        Err(_) => log::warn!("MTL file {} not found, skipping it.", path.display()),
+                    }
+                }
+            }
+            "usemtl" => {
+                let material_name = rest.join(" ");
+                material = materials.iter().position(|m| m._name == material_name);
+            }
+            "f" => {
+                if rest.len() < 3 {
+                    panic!(
+                        "OBJ line {}: a face needs at least three vertices.",
+                        number + 1
+                    );
+                }
+                let corners: Vec<Corner> = rest
+                    .iter()
+                    .map(|c| {
+                        parse_corner(c, positions.len(), tex_coords.len(), normals.len(), number)
+                    })
+                    .collect();
+                let builder = match builders
+                    .iter()
+                    .position(|b| b._name == name && b._material == material)
+                {
+                    Some(i) => &mut builders[i],
+                    None => {
+                        builders.push(ObjectBuilder::new(&name, material));
+                        builders.last_mut().unwrap()
+                    }
+                };
+                for i in 1..corners.len() - 1 {
+                    for corner in [corners[0], corners[i], corners[i + 1]] {
+                        let index = builder.vertex(corner, &positions, &tex_coords, &normals);
+                        builder._mesh._indices.push(index);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let objects = builders
+        .into_iter()
+        .map(|mut b| {
+            if !b._missing_normals.is_empty() {
+                b._mesh.compute_normals_of(&b._missing_normals);
+            }
+            return ObjObject {
+                _name: b._name,
+                _material: b._material,
+                _mesh: b._mesh,
+            };
+        })
+        .collect();
+
+    return ObjModel {
+        _objects: objects,
+        _materials: materials,
+    };
+}
+
+/// Loads the materials of an MTL file.
+pub fn load_mtl(path: &Path) -> Vec<ObjMaterial> {
+    let content = fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("Error reading MTL file {}.", path.display()));
+    return parse_mtl(&content, path.parent().unwrap_or(Path::new("")));
+}
+
+/// Parses the contents of an MTL file. Texture paths are joined to the given directory.
+pub fn parse_mtl(content: &str, directory: &Path) -> Vec<ObjMaterial> {
+    let mut materials: Vec<ObjMaterial> = vec![];
+    for (number, raw_line) in content.lines().enumerate() {
+        let line = raw_line.split('#').next().unwrap().trim();
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(k) => k,
+            None => continue,
+        };
+        let rest: Vec<&str> = tokens.collect();
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(&rest.join(" ")));
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some(m) => m,
+            None => continue,
+        };
+        // Texture statements may have options before the file name, which always comes last
+        let texture = || {
+            rest.last()
+                .map(|f| directory.join(f).to_string_lossy().into_owned())
+        };
+        match keyword {
+            "Ka" => material._ambient = parse_v3(&rest, number),
+            "Kd" => material._diffuse = parse_v3(&rest, number),
+            "Ks" => material._specular = parse_v3(&rest, number),
+            "Ns" => material._shininess = parse_floats(&rest, number)[0],
+            "d" => material._opacity = parse_floats(&rest, number)[0],
+            "Tr" => material._opacity = 1.0 - parse_floats(&rest, number)[0],
+            "map_Ka" => material._ambient_map = texture(),
+            "map_Kd" => material._diffuse_map = texture(),
+            "map_Ks" => material._specular_map = texture(),
+            "map_Bump" | "map_bump" | "bump" | "norm" => material._normal_map = texture(),
+            _ => {}
+        }
+    }
+    return materials;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Export |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Saves a mesh as an OBJ file. See `write` for the details.
+pub fn save(mesh: &Mesh, path: &str) {
+    fs::write(path, write(mesh, "mesh"))
+        .unwrap_or_else(|_| panic!("Error writing OBJ file {path}."));
+}
+
+/// Writes a mesh as the contents of an OBJ file with a single object. The position, normal,
+/// texture coordinates and color attributes are written when the mesh has them, colors being
+/// appended to the positions as most tools expect. The indices are written as triangles.
+pub fn write(mesh: &Mesh, name: &str) -> String {
+    let position = mesh.attribute_offset(&VertexAttribute::Position);
+    let normal = mesh.attribute_offset(&VertexAttribute::Normal);
+    let tex_coord = mesh.attribute_offset(&VertexAttribute::TexCoord);
+    let color = mesh.attribute_offset(&VertexAttribute::Color);
+    let position = position.expect("Cannot export a mesh without positions.");
+    let size = mesh.vertex_size();
+
+    let mut out = String::new();
+    writeln!(out, "o {name}").unwrap();
+    for vertex in mesh._vertices.chunks_exact(size) {
+        let p = &vertex[position..position + 3];
+        write!(out, "v {} {} {}", p[0], p[1], p[2]).unwrap();
+        if let Some(c) = color {
+            write!(out, " {} {} {}", vertex[c], vertex[c + 1], vertex[c + 2]).unwrap();
+        }
+        out.push('\n');
+    }
+    if let Some(t) = tex_coord {
+        for vertex in mesh._vertices.chunks_exact(size) {
+            writeln!(out, "vt {} {}", vertex[t], vertex[t + 1]).unwrap();
+        }
+    }
+    if let Some(n) = normal {
+        for vertex in mesh._vertices.chunks_exact(size) {
+            writeln!(out, "vn {} {} {}", vertex[n], vertex[n + 1], vertex[n + 2]).unwrap();
+        }
+    }
+    // Every vertex has all of its attributes, so the three indices of a corner are the same
+    for triangle in mesh._indices.chunks_exact(3) {
+        out.push('f');
+        for i in triangle {
+            let i = i + 1;
+            match (tex_coord.is_some(), normal.is_some()) {
+                (true, true) => write!(out, " {i}/{i}/{i}").unwrap(),
+                (true, false) => write!(out, " {i}/{i}").unwrap(),
+                (false, true) => write!(out, " {i}//{i}").unwrap(),
+                (false, false) => write!(out, " {i}").unwrap(),
+            }
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|=====================================| Helpers |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Zero-based indices of the position, texture coordinates and normal of a face corner.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct Corner {
+    _position: usize,
+    _tex_coord: Option<usize>,
+    _normal: Option<usize>,
+}
+
+/// Accumulates the faces of an object, deduplicating the vertices they share.
+struct ObjectBuilder {
+    _name: String,
+    _material: Option<usize>,
+    _mesh: Mesh,
+    _cache: HashMap<Corner, u32>,
+    /// Vertices whose corner gives no normal, which are computed once the object is complete.
+    _missing_normals: Vec<u32>,
+}
+
+impl ObjectBuilder {
+    fn new(name: &str, material: Option<usize>) -> ObjectBuilder {
+        return ObjectBuilder {
+            _name: name.to_string(),
+            _material: material,
+            _mesh: Mesh::new_pnt(),
+            _cache: HashMap::new(),
+            _missing_normals: vec![],
+        };
+    }
+
+    fn vertex(
+        &mut self,
+        corner: Corner,
+        positions: &[V3],
+        tex_coords: &[[f32; 2]],
+        normals: &[V3],
+    ) -> u32 {
+        if let Some(index) = self._cache.get(&corner) {
+            return *index;
+        }
+        let p = positions[corner._position];
+        let uv = corner._tex_coord.map_or([0.0, 0.0], |i| tex_coords[i]);
+        let n = corner._normal.map_or([0.0, 0.0, 0.0], |i| normals[i]);
+        let index = self
+            ._mesh
+            .push_vertex(&[p[0], p[1], p[2], n[0], n[1], n[2], uv[0], uv[1]]);
+        if corner._normal.is_none() {
+            self._missing_normals.push(index);
+        }
+        self._cache.insert(corner, index);
+        return index;
+    }
+}
+
+fn parse_floats(tokens: &[&str], number: usize) -> Vec<f32> {
+    return tokens
+        .iter()
+        .map(|t| {
+            t.parse::<f32>()
+                .unwrap_or_else(|_| panic!("OBJ line {}: invalid number '{t}'.", number + 1))
+        })
+        .collect();
+}
+
+fn parse_v3(tokens: &[&str], number: usize) -> V3 {
+    let values = parse_floats(tokens, number);
+    if values.len() < 3 {
+        panic!("OBJ line {}: expected three values.", number + 1);
+    }
+    return [values[0], values[1], values[2]];
+}
+
+/// Converts a one-based (or negative, relative) OBJ index into a zero-based one.
+fn resolve_index(token: &str, count: usize, number: usize) -> usize {
+    let index = token
+        .parse::<i64>()
+        .unwrap_or_else(|_| panic!("OBJ line {}: invalid index '{token}'.", number + 1));
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        panic!("OBJ line {}: index {index} is out of range.", number + 1);
+    }
+    return resolved as usize;
+}
+
+/// Parses a face corner in any of the forms `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+fn parse_corner(
+    token: &str,
+    positions: usize,
+    tex_coords: usize,
+    normals: usize,
+    number: usize,
+) -> Corner {
+    let mut parts = token.split('/');
+    let position = resolve_index(parts.next().unwrap(), positions, number);
+    let tex_coord = match parts.next() {
+        Some(t) if !t.is_empty() => Some(resolve_index(t, tex_coords, number)),
+        _ => None,
+    };
+    let normal = match parts.next() {
+        Some(n) if !n.is_empty() => Some(resolve_index(n, normals, number)),
+        _ => None,
+    };
+    return Corner {
+        _position: position,
+        _tex_coord: tex_coord,
+        _normal: normal,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_back_written_meshes() {
+        let mut mesh = Mesh::new_pnt();
+        mesh.push_vertex(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        mesh.push_vertex(&[1.5, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+        mesh.push_vertex(&[1.5, 2.0, 0.0, 0.0, 0.6, 0.8, 1.0, 1.0]);
+        mesh.push_vertex(&[0.0, 2.0, -0.1, 0.0, 0.6, 0.8, 0.0, 1.0]);
+        mesh.push_triangle(0, 1, 2);
+        mesh.push_triangle(0, 2, 3);
+
+        let model = parse(&write(&mesh, "quad"), Path::new(""));
+        assert_eq!(model._objects.len(), 1);
+        let object = &model._objects[0];
+        assert_eq!(object._name, "quad");
+        assert_eq!(object._mesh._attributes, mesh._attributes);
+        assert_eq!(object._mesh._vertices, mesh._vertices);
+        assert_eq!(object._mesh._indices, mesh._indices);
+    }
+}
@@ -0,0 +1,610 @@
+use crate::utils::mesh::{Mesh, VertexAttribute};
+use std::fs;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum ScalarType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+enum PropertyType {
+    Scalar(ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+struct Property {
+    _name: String,
+    _type: PropertyType,
+}
+
+struct Element {
+    _name: String,
+    _count: usize,
+    _properties: Vec<Property>,
+}
+
+/// Loads an ASCII or binary PLY file. Vertices are laid out as position, normal and then any of
+/// texture coordinates (`u`/`v` or `s`/`t`) and color (`red`/`green`/`blue`/`alpha`) that the
+/// file has. Every other scalar vertex property becomes a `VertexAttribute::Custom` of size 1,
+/// whose layout can be found with `Mesh::layout_of`. Normals are computed when the file has
+/// faces but no normals, and a file without faces gets one index per vertex, so it can be drawn
+/// as points.
+pub fn load(path: &str) -> Mesh {
+    let bytes = fs::read(path).unwrap_or_else(|_| panic!("Error reading PLY file {path}."));
+    return parse(&bytes);
+}
+
+/// Parses the contents of a PLY file.
+pub fn parse(bytes: &[u8]) -> Mesh {
+    let (format, elements, body_start) = parse_header(bytes);
+    let mut reader = Reader {
+        _bytes: bytes,
+        _pos: body_start,
+        _format: format,
+        _tokens: vec![],
+    };
+    if format == Format::Ascii {
+        let body = String::from_utf8_lossy(&bytes[body_start..]);
+        reader._tokens = body.split_whitespace().rev().map(String::from).collect();
+    }
+
+    let mut mesh: Option<Mesh> = None;
+    let mut has_normals = false;
+    let mut has_faces = false;
+    for element in elements.iter() {
+        match element._name.as_str() {
+            "vertex" => {
+                let (vertices, normals) = read_vertices(&mut reader, element);
+                mesh = Some(vertices);
+                has_normals = normals;
+            }
+            "face" => {
+                let mesh = mesh
+                    .as_mut()
+                    .expect("PLY faces must come after the vertices.");
+                read_faces(&mut reader, element, mesh);
+                has_faces = true;
+            }
+            _ => {
+                // Other elements are read and discarded
+                for _ in 0..element._count {
+                    for property in element._properties.iter() {
+                        reader.read_property(&property._type);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut mesh = mesh.expect("PLY file has no vertices.");
+    if !has_faces {
+        mesh._indices = (0..mesh.vertex_count() as u32).collect();
+    } else if !has_normals {
+        mesh.compute_normals();
+    }
+    return mesh;
+}
+
+/// Reads the vertices, returning them along with whether they had normals.
+fn read_vertices(reader: &mut Reader, element: &Element) -> (Mesh, bool) {
+    let find = |name: &str| element._properties.iter().position(|p| p._name == name);
+    let find_any = |names: &[&str]| names.iter().find_map(|n| find(n));
+
+    let position = [find("x"), find("y"), find("z")];
+    let normal = [find("nx"), find("ny"), find("nz")];
+    let tex_coord = [
+        find_any(&["u", "s", "texture_u", "texture_s"]),
+        find_any(&["v", "t", "texture_v", "texture_t"]),
+    ];
+    let color = [
+        find_any(&["red", "r", "diffuse_red"]),
+        find_any(&["green", "g", "diffuse_green"]),
+        find_any(&["blue", "b", "diffuse_blue"]),
+    ];
+    let alpha = find_any(&["alpha", "a", "diffuse_alpha"]);
+    if position.iter().any(|p| p.is_none()) {
+        panic!("PLY vertices must have x, y and z properties.");
+    }
+    let has_normal = normal.iter().all(|n| n.is_some());
+    let has_tex_coord = tex_coord.iter().all(|t| t.is_some());
+    let has_color = color.iter().all(|c| c.is_some());
+
+    let mut used: Vec<usize> = position.iter().flatten().copied().collect();
+    if has_normal {
+        used.extend(normal.iter().flatten());
+    }
+    if has_tex_coord {
+        used.extend(tex_coord.iter().flatten());
+    }
+    if has_color {
+        used.extend(color.iter().flatten());
+        used.extend(alpha.iter());
+    }
+    let custom: Vec<usize> = (0..element._properties.len())
+        .filter(|i| !used.contains(i))
+        .filter(|i| matches!(element._properties[*i]._type, PropertyType::Scalar(_)))
+        .collect();
+
+    let mut mesh = Mesh::new()
+        .attribute(VertexAttribute::Position, 3)
+        .attribute(VertexAttribute::Normal, 3);
+    if has_tex_coord {
+        mesh = mesh.attribute(VertexAttribute::TexCoord, 2);
+    }
+    if has_color {
+        mesh = mesh.attribute(VertexAttribute::Color, 4);
+    }
+    for i in custom.iter() {
+        let name = element._properties[*i]._name.clone();
+        mesh = mesh.attribute(VertexAttribute::Custom(name), 1);
+    }
+
+    // Integer colors go up to the largest value of their type (255 for bytes, 65535 for
+    // unsigned shorts), while floating point ones go from 0 to 1
+    let color_scale = |i: usize| match element._properties[i]._type {
+        PropertyType::Scalar(ScalarType::Float32) | PropertyType::Scalar(ScalarType::Float64) => {
+            1.0
+        }
+        PropertyType::Scalar(ScalarType::UInt16) => 1.0 / 65535.0,
+        _ => 1.0 / 255.0,
+    };
+
+    let mut values = vec![0.0; element._properties.len()];
+    let mut vertex = Vec::with_capacity(mesh.vertex_size());
+    mesh._vertices.reserve(element._count * mesh.vertex_size());
+    for _ in 0..element._count {
+        for (i, property) in element._properties.iter().enumerate() {
+            values[i] = reader.read_property(&property._type).unwrap_or(0.0);
+        }
+        vertex.clear();
+        vertex.extend(position.iter().map(|p| values[p.unwrap()] as f32));
+        if has_normal {
+            vertex.extend(normal.iter().map(|n| values[n.unwrap()] as f32));
+        } else {
+            vertex.extend_from_slice(&[0.0, 0.0, 0.0]);
+        }
+        if has_tex_coord {
+            vertex.extend(tex_coord.iter().map(|t| values[t.unwrap()] as f32));
+        }
+        if has_color {
+            for c in color.iter() {
+                let c = c.unwrap();
+                vertex.push((values[c] * color_scale(c)) as f32);
+            }
+            vertex.push(alpha.map_or(1.0, |a| (values[a] * color_scale(a)) as f32));
+        }
+        vertex.extend(custom.iter().map(|c| values[*c] as f32));
+        mesh.push_vertex(&vertex);
+    }
+    return (mesh, has_normal);
+}
+
+/// Reads the faces, triangulating them as fans.
+fn read_faces(reader: &mut Reader, element: &Element, mesh: &mut Mesh) {
+    let indices_property = element
+        ._properties
+        .iter()
+        .position(|p| p._name == "vertex_indices" || p._name == "vertex_index")
+        .expect("PLY faces must have a vertex_indices property.");
+    for _ in 0..element._count {
+        for (i, property) in element._properties.iter().enumerate() {
+            if i != indices_property {
+                reader.read_property(&property._type);
+                continue;
+            }
+            let (count_type, item_type) = match property._type {
+                PropertyType::List(count_type, item_type) => (count_type, item_type),
+                PropertyType::Scalar(_) => panic!("PLY vertex_indices must be a list."),
+            };
+            let count = reader.read_scalar(count_type) as usize;
+            let vertex_count = mesh.vertex_count();
+            let face: Vec<u32> = (0..count)
+                .map(|_| {
+                    let index = reader.read_scalar(item_type);
+                    if index < 0.0 || index >= vertex_count as f64 {
+                        panic!("PLY face index {index} is out of range ({vertex_count} vertices).");
+                    }
+                    index as u32
+                })
+                .collect();
+            for k in 1..count.saturating_sub(1) {
+                mesh.push_triangle(face[0], face[k], face[k + 1]);
+            }
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Export |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Saves a mesh as a PLY file, either binary (little endian) or ASCII. See `write` for details.
+pub fn save(mesh: &Mesh, path: &str, binary: bool) {
+    fs::write(path, write(mesh, binary))
+        .unwrap_or_else(|_| panic!("Error writing PLY file {path}."));
+}
+
+/// Writes a mesh as the contents of a PLY file. Every attribute becomes vertex properties:
+/// `x`/`y`/`z` for the position, `nx`/`ny`/`nz` for the normal, `u`/`v` for the texture
+/// coordinates, `red`/`green`/`blue`/`alpha` (as bytes) for the color, and the name of the
+/// attribute for custom ones. The indices are written as triangular faces.
+pub fn write(mesh: &Mesh, binary: bool) -> Vec<u8> {
+    // Name of each float of a vertex, and whether it is a color stored as a byte
+    let mut properties: Vec<(String, bool)> = vec![];
+    for (attribute, size) in mesh._attributes.iter().zip(mesh._vert_sizes.iter()) {
+        let names: Vec<String> = match attribute {
+            VertexAttribute::Position => vec!["x".into(), "y".into(), "z".into()],
+            VertexAttribute::Normal => vec!["nx".into(), "ny".into(), "nz".into()],
+            VertexAttribute::TexCoord => vec!["u".into(), "v".into()],
+            VertexAttribute::Color => {
+                vec!["red".into(), "green".into(), "blue".into(), "alpha".into()]
+            }
+            VertexAttribute::Custom(name) if *size == 1 => vec![name.clone()],
+            VertexAttribute::Custom(name) => (0..*size).map(|k| format!("{name}_{k}")).collect(),
+        };
+        let is_color = *attribute == VertexAttribute::Color;
+        for k in 0..*size as usize {
+            let name = names
+                .get(k)
+                .cloned()
+                .unwrap_or_else(|| format!("{}_{k}", names[0]));
+            properties.push((name, is_color));
+        }
+    }
+
+    let format = if binary {
+        "binary_little_endian"
+    } else {
+        "ascii"
+    };
+    let mut out = format!("ply\nformat {format} 1.0\ncomment exported by cuboid\n");
+    out += &format!("element vertex {}\n", mesh.vertex_count());
+    for (name, is_color) in properties.iter() {
+        let kind = if *is_color { "uchar" } else { "float" };
+        out += &format!("property {kind} {name}\n");
+    }
+    out += &format!("element face {}\n", mesh._indices.len() / 3);
+    out += "property list uchar uint vertex_indices\nend_header\n";
+
+    let mut bytes = out.into_bytes();
+    let to_byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    for vertex in mesh._vertices.chunks_exact(mesh.vertex_size()) {
+        if binary {
+            for (value, (_, is_color)) in vertex.iter().zip(properties.iter()) {
+                if *is_color {
+                    bytes.push(to_byte(*value));
+                } else {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        } else {
+            let line: Vec<String> = vertex
+                .iter()
+                .zip(properties.iter())
+                .map(|(value, (_, is_color))| match is_color {
+                    true => to_byte(*value).to_string(),
+                    false => value.to_string(),
+                })
+                .collect();
+            bytes.extend_from_slice(line.join(" ").as_bytes());
+            bytes.push(b'\n');
+        }
+    }
+    for triangle in mesh._indices.chunks_exact(3) {
+        if binary {
+            bytes.push(3);
+            for i in triangle {
+                bytes.extend_from_slice(&i.to_le_bytes());
+            }
+        } else {
+            bytes.extend_from_slice(
+                format!("3 {} {} {}\n", triangle[0], triangle[1], triangle[2]).as_bytes(),
+            );
+        }
+    }
+    return bytes;
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Header |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Parses the header, returning the format, the elements and where the body starts.
+fn parse_header(bytes: &[u8]) -> (Format, Vec<Element>, usize) {
+    let end_marker = b"end_header";
+    let end = bytes
+        .windows(end_marker.len())
+        .position(|w| w == end_marker)
+        .expect("PLY file has no end_header.");
+    let mut body_start = end + end_marker.len();
+    // The header ends with a single line break, either "\n" or "\r\n"
+    if bytes.get(body_start) == Some(&b'\r') {
+        body_start += 1;
+    }
+    if bytes.get(body_start) == Some(&b'\n') {
+        body_start += 1;
+    }
+
+    let header = String::from_utf8_lossy(&bytes[..end]);
+    let mut lines = header.lines();
+    if lines.next().map(|l| l.trim()) != Some("ply") {
+        panic!("Not a PLY file.");
+    }
+    let mut format = Format::Ascii;
+    let mut elements: Vec<Element> = vec![];
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first() {
+            Some(&"format") => {
+                format = match tokens.get(1) {
+                    Some(&"ascii") => Format::Ascii,
+                    Some(&"binary_little_endian") => Format::BinaryLittleEndian,
+                    Some(&"binary_big_endian") => Format::BinaryBigEndian,
+                    _ => panic!("Unknown PLY format '{line}'."),
+                }
+            }
+            Some(&"element") => {
+                if tokens.len() < 3 {
+                    panic!("Invalid PLY element '{line}'.");
+                }
+                elements.push(Element {
+                    _name: tokens[1].to_string(),
+                    _count: tokens[2]
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid PLY element '{line}'.")),
+                    _properties: vec![],
+                });
+            }
+            Some(&"property") => {
+                let element = elements
+                    .last_mut()
+                    .unwrap_or_else(|| panic!("PLY property outside of an element '{line}'."));
+                let property = if tokens.get(1) == Some(&"list") {
+                    if tokens.len() < 5 {
+                        panic!("Invalid PLY property '{line}'.");
+                    }
+                    Property {
+                        _name: tokens[4].to_string(),
+                        _type: PropertyType::List(scalar_type(tokens[2]), scalar_type(tokens[3])),
+                    }
+                } else {
+                    if tokens.len() < 3 {
+                        panic!("Invalid PLY property '{line}'.");
+                    }
+                    Property {
+                        _name: tokens[2].to_string(),
+                        _type: PropertyType::Scalar(scalar_type(tokens[1])),
+                    }
+                };
+                element._properties.push(property);
+            }
+            _ => {}
+        }
+    }
+    return (format, elements, body_start);
+}
+
+fn scalar_type(name: &str) -> ScalarType {
+    return match name {
+        "char" | "int8" => ScalarType::Int8,
+        "uchar" | "uint8" => ScalarType::UInt8,
+        "short" | "int16" => ScalarType::Int16,
+        "ushort" | "uint16" => ScalarType::UInt16,
+        "int" | "int32" => ScalarType::Int32,
+        "uint" | "uint32" => ScalarType::UInt32,
+        "float" | "float32" => ScalarType::Float32,
+        "double" | "float64" => ScalarType::Float64,
+        _ => panic!("Unknown PLY type '{name}'."),
+    };
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+//|======================================| Reader |=============================================|//
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads values from the body, either as text tokens or as binary data.
+struct Reader<'a> {
+    _bytes: &'a [u8],
+    _pos: usize,
+    _format: Format,
+    /// Remaining tokens of an ASCII body, in reverse order
+    _tokens: Vec<String>,
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a property, returning its value if it is a scalar. Lists are skipped.
+    fn read_property(&mut self, property_type: &PropertyType) -> Option<f64> {
+        return match property_type {
+            PropertyType::Scalar(t) => Some(self.read_scalar(*t)),
+            PropertyType::List(count_type, item_type) => {
+                let count = self.read_scalar(*count_type) as usize;
+                for _ in 0..count {
+                    self.read_scalar(*item_type);
+                }
+                None
+            }
+        };
+    }
+
+    fn read_scalar(&mut self, scalar_type: ScalarType) -> f64 {
+        if self._format == Format::Ascii {
+            let token = self._tokens.pop().expect("Unexpected end of PLY data.");
+            return token
+                .parse::<f64>()
+                .unwrap_or_else(|_| panic!("Invalid PLY value '{token}'."));
+        }
+        let size = match scalar_type {
+            ScalarType::Int8 | ScalarType::UInt8 => 1,
+            ScalarType::Int16 | ScalarType::UInt16 => 2,
+            ScalarType::Int32 | ScalarType::UInt32 | ScalarType::Float32 => 4,
+            ScalarType::Float64 => 8,
+        };
+        if self._pos + size > self._bytes.len() {
+            panic!("Unexpected end of PLY data.");
+        }
+        let mut raw = [0_u8; 8];
+        raw[..size].copy_from_slice(&self._bytes[self._pos..self._pos + size]);
+        if self._format == Format::BinaryBigEndian {
+            raw[..size].reverse();
+        }
+        self._pos += size;
+        return match scalar_type {
+            ScalarType::Int8 => raw[0] as i8 as f64,
+            ScalarType::UInt8 => raw[0] as f64,
+            ScalarType::Int16 => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+            ScalarType::UInt16 => u16::from_le_bytes([raw[0], raw[1]]) as f64,
+            ScalarType::Int32 => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::UInt32 => u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::Float32 => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::Float64 => f64::from_le_bytes(raw),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCII_QUAD: &str = "ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+property ushort red
+property ushort green
+property ushort blue
+property float quality
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 65535 0 0 1
+1 0 0 0 65535 0 2
+1 1 0 0 0 65535 3
+0 1 0 0 0 0 4
+4 0 1 2 3
+";
+
+    #[test]
+    fn parses_ascii_files() {
+        let mesh = parse(ASCII_QUAD.as_bytes());
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh._indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(
+            mesh.positions(),
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0]
+            ]
+        );
+        assert_eq!(
+            mesh.get_attribute(1, &VertexAttribute::Color),
+            Some(&[0.0, 1.0, 0.0, 1.0][..])
+        );
+        let quality = VertexAttribute::Custom("quality".into());
+        assert_eq!(mesh.get_attribute(3, &quality), Some(&[4.0][..]));
+        // The normals are computed from the face
+        assert_eq!(
+            mesh.get_attribute(0, &VertexAttribute::Normal),
+            Some(&[0.0, 0.0, 1.0][..])
+        );
+    }
+
+    #[test]
+    fn parses_binary_files() {
+        let header = "ply
+format binary_big_endian 1.0
+element vertex 3
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar ushort vertex_indices
+end_header
+";
+        let mut bytes = header.as_bytes().to_vec();
+        for (p, c) in [
+            ([0.0_f32, 0.0, 0.0], 255_u8),
+            ([1.0, 0.0, 0.0], 0),
+            ([0.0, 1.0, 0.0], 51),
+        ] {
+            for value in p {
+                bytes.extend_from_slice(&value.to_be_bytes());
+            }
+            bytes.extend_from_slice(&[c, c, c]);
+        }
+        bytes.push(3);
+        for i in [0_u16, 1, 2] {
+            bytes.extend_from_slice(&i.to_be_bytes());
+        }
+
+        let mesh = parse(&bytes);
+        assert_eq!(mesh._indices, vec![0, 1, 2]);
+        assert_eq!(mesh.positions()[1], [1.0, 0.0, 0.0]);
+        assert_eq!(
+            mesh.get_attribute(2, &VertexAttribute::Color),
+            Some(&[0.2, 0.2, 0.2, 1.0][..])
+        );
+    }
+
+    #[test]
+    fn adds_indices_to_point_clouds() {
+        let mesh = parse(b"ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty float y\nproperty float z\nend_header\n0 0 0\n1 2 3\n");
+        assert_eq!(mesh._indices, vec![0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "PLY face index 4 is out of range (4 vertices).")]
+    fn rejects_out_of_range_indices() {
+        parse(ASCII_QUAD.replace("4 0 1 2 3", "3 0 1 4").as_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "PLY face index -1 is out of range (4 vertices).")]
+    fn rejects_negative_indices() {
+        parse(ASCII_QUAD.replace("4 0 1 2 3", "3 0 1 -1").as_bytes());
+    }
+
+    #[test]
+    fn reads_back_written_meshes() {
+        let mut mesh = Mesh::new_pnt()
+            .attribute(VertexAttribute::Color, 4)
+            .attribute(VertexAttribute::Custom("quality".into()), 1);
+        mesh.push_vertex(&[
+            0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5,
+        ]);
+        mesh.push_vertex(&[
+            1.5, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 2.0,
+        ]);
+        mesh.push_vertex(&[
+            1.5, 2.0, -0.1, 0.0, 0.6, 0.8, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0, -3.0,
+        ]);
+        mesh.push_triangle(0, 1, 2);
+
+        for binary in [false, true] {
+            let read = parse(&write(&mesh, binary));
+            assert_eq!(read._attributes, mesh._attributes);
+            assert_eq!(read._vert_sizes, mesh._vert_sizes);
+            assert_eq!(read._vertices, mesh._vertices);
+            assert_eq!(read._indices, mesh._indices);
+        }
+    }
+}
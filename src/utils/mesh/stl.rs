@@ -0,0 +1,295 @@
+use crate::utils::math::linalg;
+use crate::utils::mesh::{Mesh, VertexAttribute};
+use crate::utils::types::V3;
+use std::collections::HashMap;
+use std::fs;
+
+/// Loads an ASCII or binary STL file into a mesh laid out as position and normal. The normals
+/// stored in the file are often missing or wrong, so they are always computed from the
+/// triangles. A flat mesh keeps three vertices per triangle with the normal of its face, while
+/// a smooth one merges the vertices at the same position and averages the normals around them.
+pub fn load(path: &str, smooth: bool) -> Mesh {
+    let bytes = fs::read(path).unwrap_or_else(|_| panic!("Error reading STL file {path}."));
+    return parse(&bytes, smooth);
+}
+
+/// Parses the contents of an STL file.
+pub fn parse(bytes: &[u8], smooth: bool) -> Mesh {
+    let triangles = if is_binary(bytes) {
+        parse_binary(bytes)
+    } else {
+        parse_ascii(&String::from_utf8_lossy(bytes))
+    };
+
+    let mut mesh = Mesh::new()
+        .attribute(VertexAttribute::Position, 3)
+        .attribute(VertexAttribute::Normal, 3);
+    if smooth {
+        let mut merged = HashMap::<[u32; 3], u32>::new();
+        for triangle in triangles.iter() {
+            for p in triangle.iter() {
+                let key = [p[0].to_bits(), p[1].to_bits(), p[2].to_bits()];
+                let index = *merged
+                    .entry(key)
+                    .or_insert_with(|| mesh.push_vertex(&[p[0], p[1], p[2], 0.0, 0.0, 0.0]));
+                mesh._indices.push(index);
+            }
+        }
+        mesh.compute_normals();
+    } else {
+        for triangle in triangles.iter() {
+            let n = face_normal(triangle);
+            for p in triangle.iter() {
+                let index = mesh.push_vertex(&[p[0], p[1], p[2], n[0], n[1], n[2]]);
+                mesh._indices.push(index);
+            }
+        }
+    }
+    return mesh;
+}
+
+/// Saves the triangles of a mesh as an STL file, either binary or ASCII. The normal of each
+/// facet is computed from its vertices.
+pub fn save(mesh: &Mesh, path: &str, binary: bool) {
+    let bytes = if binary {
+        write_binary(mesh)
+    } else {
+        write_ascii(mesh, "mesh").into_bytes()
+    };
+    fs::write(path, bytes).unwrap_or_else(|_| panic!("Error writing STL file {path}."));
+}
+
+/// Writes the triangles of a mesh as a binary STL file.
+pub fn write_binary(mesh: &Mesh) -> Vec<u8> {
+    let triangles = triangles(mesh);
+    let mut bytes = vec![0_u8; 80];
+    bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+    for triangle in triangles.iter() {
+        let n = face_normal(triangle);
+        for value in n.iter().chain(triangle.iter().flatten()) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0, 0]);
+    }
+    return bytes;
+}
+
+/// Writes the triangles of a mesh as an ASCII STL file.
+pub fn write_ascii(mesh: &Mesh, name: &str) -> String {
+    let mut out = format!("solid {name}\n");
+    for triangle in triangles(mesh).iter() {
+        let n = face_normal(triangle);
+        out += &format!(
+            "  facet normal {} {} {}\n    outer loop\n",
+            n[0], n[1], n[2]
+        );
+        for p in triangle.iter() {
+            out += &format!("      vertex {} {} {}\n", p[0], p[1], p[2]);
+        }
+        out += "    endloop\n  endfacet\n";
+    }
+    out += &format!("endsolid {name}\n");
+    return out;
+}
+
+fn triangles(mesh: &Mesh) -> Vec<[V3; 3]> {
+    let position = mesh
+        .attribute_offset(&VertexAttribute::Position)
+        .expect("Cannot export a mesh without positions.");
+    let size = mesh.vertex_size();
+    let count = mesh.vertex_count();
+    let point = |i: u32| {
+        if i as usize >= count {
+            panic!("Mesh index {i} is out of range ({count} vertices).");
+        }
+        let start = i as usize * size + position;
+        [
+            mesh._vertices[start],
+            mesh._vertices[start + 1],
+            mesh._vertices[start + 2],
+        ]
+    };
+    return mesh
+        ._indices
+        .chunks_exact(3)
+        .map(|t| [point(t[0]), point(t[1]), point(t[2])])
+        .collect();
+}
+
+fn face_normal(triangle: &[V3; 3]) -> V3 {
+    let face = linalg::cross_v3(
+        &linalg::sub_v3(&triangle[1], &triangle[0]),
+        &linalg::sub_v3(&triangle[2], &triangle[0]),
+    );
+    if linalg::norm_v3(&face) > 0.0 {
+        return linalg::normalize_v3(&face);
+    }
+    return [0.0, 0.0, 0.0];
+}
+
+/// Binary files are told apart by their size, as some of them also start with "solid".
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() < 84 {
+        return false;
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    return bytes.len() == 84 + count * 50 || !bytes.starts_with(b"solid");
+}
+
+fn parse_binary(bytes: &[u8]) -> Vec<[V3; 3]> {
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    if bytes.len() < 84 + count * 50 {
+        panic!("STL file is shorter than its {count} triangles.");
+    }
+    let read_f32 =
+        |at: usize| f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    let mut triangles = Vec::with_capacity(count);
+    for i in 0..count {
+        // Each record has a normal, three vertices and a two byte attribute count
+        let start = 84 + i * 50 + 12;
+        let mut triangle = [[0.0; 3]; 3];
+        for v in 0..3 {
+            for k in 0..3 {
+                triangle[v][k] = read_f32(start + (v * 3 + k) * 4);
+            }
+        }
+        triangles.push(triangle);
+    }
+    return triangles;
+}
+
+fn parse_ascii(content: &str) -> Vec<[V3; 3]> {
+    let mut triangles = vec![];
+    let mut current: Vec<V3> = vec![];
+    for (number, line) in content.lines().enumerate() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first() {
+            Some(&"vertex") => {
+                if tokens.len() < 4 {
+                    panic!("STL line {}: a vertex needs three values.", number + 1);
+                }
+                let mut p = [0.0; 3];
+                for k in 0..3 {
+                    p[k] = tokens[k + 1].parse().unwrap_or_else(|_| {
+                        panic!(
+                            "STL line {}: invalid number '{}'.",
+                            number + 1,
+                            tokens[k + 1]
+                        )
+                    });
+                }
+                current.push(p);
+            }
+            Some(&"endfacet") => {
+                // Facets with more than three vertices are triangulated as fans
+                for k in 1..current.len().saturating_sub(1) {
+                    triangles.push([current[0], current[k], current[k + 1]]);
+                }
+                current.clear();
+            }
+            _ => {}
+        }
+    }
+    return triangles;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCII_SQUARE: &str = "solid square
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid square
+";
+
+    #[test]
+    fn parses_ascii_files() {
+        let flat = parse(ASCII_SQUARE.as_bytes(), false);
+        assert_eq!(flat.vertex_count(), 6);
+        assert_eq!(flat._indices, vec![0, 1, 2, 3, 4, 5]);
+        // The normal of the file is ignored, and computed from the triangle
+        assert_eq!(
+            flat.get_attribute(0, &VertexAttribute::Normal),
+            Some(&[0.0, 0.0, 1.0][..])
+        );
+
+        let smooth = parse(ASCII_SQUARE.as_bytes(), true);
+        assert_eq!(smooth.vertex_count(), 4);
+        assert_eq!(smooth._indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn parses_binary_files() {
+        // Binary files may also start with "solid"
+        let mut bytes = b"solid binary".to_vec();
+        bytes.resize(80, 0);
+        bytes.extend_from_slice(&1_u32.to_le_bytes());
+        for value in [
+            0.0_f32, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 2.0, 0.0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0, 0]);
+
+        let mesh = parse(&bytes, false);
+        assert_eq!(
+            mesh.positions(),
+            vec![[0.0, 0.0, 0.0], [0.0, 0.0, 2.0], [0.0, 2.0, 0.0]]
+        );
+        assert_eq!(
+            mesh.get_attribute(0, &VertexAttribute::Normal),
+            Some(&[-1.0, 0.0, 0.0][..])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "STL file is shorter than its 2 triangles.")]
+    fn rejects_truncated_binary_files() {
+        let mut bytes = vec![0_u8; 80];
+        bytes.extend_from_slice(&2_u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 50]);
+        parse(&bytes, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mesh index 3 is out of range (3 vertices).")]
+    fn rejects_out_of_range_indices() {
+        let mut mesh = Mesh::new().attribute(VertexAttribute::Position, 3);
+        mesh.push_vertex(&[0.0, 0.0, 0.0]);
+        mesh.push_vertex(&[1.0, 0.0, 0.0]);
+        mesh.push_vertex(&[0.0, 1.0, 0.0]);
+        mesh.push_triangle(0, 1, 3);
+        write_binary(&mesh);
+    }
+
+    #[test]
+    fn reads_back_written_meshes() {
+        let mut mesh = Mesh::new().attribute(VertexAttribute::Position, 3);
+        mesh.push_vertex(&[0.0, 0.0, 0.0]);
+        mesh.push_vertex(&[1.5, 0.0, 0.0]);
+        mesh.push_vertex(&[1.5, 2.0, -0.1]);
+        mesh.push_vertex(&[0.0, 2.0, 0.0]);
+        mesh.push_triangle(0, 1, 2);
+        mesh.push_triangle(0, 2, 3);
+
+        let ascii = parse(write_ascii(&mesh, "quad").as_bytes(), true);
+        let binary = parse(&write_binary(&mesh), true);
+        for read in [ascii, binary] {
+            assert_eq!(read.positions(), mesh.positions());
+            assert_eq!(read._indices, mesh._indices);
+        }
+    }
+}
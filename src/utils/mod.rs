@@ -1,5 +1,7 @@
 pub mod conversions;
 pub mod images;
+pub mod json;
 pub mod math;
+pub mod mesh;
 pub mod str;
 pub mod types;
@@ -1,7 +1,36 @@
-pub fn name_to_ptr(name: &str) -> *const i8 {
-    return append_null(name).as_ptr() as *const i8;
+use std::ffi::CString;
+
+/// Makes a C string from a name, to be passed to OpenGL through `as_ptr`. The string must be
+/// kept alive for as long as the pointer is used.
+pub fn to_cstring(name: &str) -> CString {
+    return CString::new(name).expect("Names passed to OpenGL can't contain null bytes.");
 }
 
 pub fn append_null(name: &str) -> String {
     return format!("{name}\0");
 }
+
+/// Decodes standard base64 data, ignoring whitespace and padding.
+pub fn decode_base64(data: &str) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' | b' ' | b'\n' | b'\r' | b'\t' => continue,
+            _ => panic!("Invalid base64 character '{}'.", c as char),
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+        }
+    }
+    return result;
+}